@@ -0,0 +1,180 @@
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Once, ONCE_INIT};
+
+use error::RustcError;
+
+type Entries = Mutex<Vec<(PathBuf, Result<String, RustcError>)>>;
+
+static INIT: Once = ONCE_INIT;
+
+/// The process-wide cache of `rustc --verbose --version` results, keyed by the
+/// path to `rustc`. Allocated once by `INIT` and never freed.
+static mut CACHE: *const Entries = 0 as *const Entries;
+
+fn entries() -> &'static Entries {
+    unsafe {
+        INIT.call_once(|| {
+            let entries = Box::new(Mutex::new(Vec::new()));
+            CACHE = mem::transmute::<Box<Entries>, *const Entries>(entries);
+        });
+
+        &*CACHE
+    }
+}
+
+/// Returns the result of `rustc --verbose --version` for the `rustc` at
+/// `path`, invoking `rustc` only if no result for `path` has been cached yet.
+/// Failures are cached, too, so `rustc` is invoked at most once per path.
+///
+/// This is shared by [`Version::read()`](::Version::read()),
+/// [`Channel::read()`](::Channel::read()), [`Date::read()`](::Date::read()),
+/// and every other function that reads `rustc`. If `VERSION_CHECK_DISABLE` is
+/// set, nothing is looked up or cached.
+pub fn verbose_version(path: &Path) -> Result<String, RustcError> {
+    if ::is_disabled() {
+        return Err(RustcError::Disabled);
+    }
+
+    verbose_version_with(path, ::rustc_verbose_version_at)
+}
+
+/// Like [`verbose_version()`] but calls `spawn` to invoke `rustc` on a miss.
+fn verbose_version_with<F: FnOnce(&Path) -> Result<String, RustcError>>(
+    path: &Path,
+    spawn: F,
+) -> Result<String, RustcError> {
+    let mut entries = match entries().lock() {
+        Ok(entries) => entries,
+        Err(_) => return spawn(path),
+    };
+
+    if let Some(entry) = entries.iter().find(|entry| &*entry.0 == path) {
+        return entry.1.clone();
+    }
+
+    let result = spawn(path);
+    entries.push((path.to_path_buf(), result.clone()));
+    result
+}
+
+/// Invokes the `rustc` at `path` regardless of any cached result, replacing
+/// the cached result with the new one. If `VERSION_CHECK_DISABLE` is set,
+/// nothing is cached.
+pub fn refresh(path: &Path) -> Result<String, RustcError> {
+    if ::is_disabled() {
+        return Err(RustcError::Disabled);
+    }
+
+    refresh_with(path, ::rustc_verbose_version_at)
+}
+
+/// Like [`refresh()`] but calls `spawn` to invoke `rustc`.
+fn refresh_with<F: FnOnce(&Path) -> Result<String, RustcError>>(
+    path: &Path,
+    spawn: F,
+) -> Result<String, RustcError> {
+    let mut entries = match entries().lock() {
+        Ok(entries) => entries,
+        Err(_) => return spawn(path),
+    };
+
+    let result = spawn(path);
+    entries.retain(|entry| &*entry.0 != path);
+    entries.push((path.to_path_buf(), result.clone()));
+    result
+}
+
+/// Replaces the cached result for the `rustc` at `path` with `result`.
+#[cfg(any(test, feature = "testing"))]
+pub fn set(path: &Path, result: Result<String, RustcError>) {
+    if let Ok(mut entries) = entries().lock() {
+        entries.retain(|entry| &*entry.0 != path);
+        entries.push((path.to_path_buf(), result));
+    }
+}
+
+/// Removes all cached results.
+#[cfg(feature = "testing")]
+pub fn clear() {
+    if let Ok(mut entries) = entries().lock() {
+        entries.clear();
+    }
+}
+
+/// Removes the cached result for the `rustc` at `path`, if any.
+#[cfg(test)]
+fn remove(path: &Path) {
+    if let Ok(mut entries) = entries().lock() {
+        entries.retain(|entry| &*entry.0 != path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::path::Path;
+
+    use super::{verbose_version, verbose_version_with, refresh_with, set, remove};
+    use error::RustcError;
+
+    #[test]
+    fn spawns_once() {
+        let path = Path::new("/version_check/cache/spawns_once/rustc");
+        let spawns = Cell::new(0);
+        let spawn = |_: &Path| {
+            spawns.set(spawns.get() + 1);
+            Ok("rustc 1.70.0 (90c541806 2023-05-31)".to_string())
+        };
+
+        // As done by `Version::read()`, `Channel::read()`, and `Date::read()`.
+        for _ in 0..3 {
+            let output = verbose_version_with(path, spawn);
+            assert_eq!(output, Ok("rustc 1.70.0 (90c541806 2023-05-31)".into()));
+        }
+
+        assert_eq!(spawns.get(), 1);
+    }
+
+    #[test]
+    fn refreshes() {
+        let path = Path::new("/version_check/cache/refreshes/rustc");
+        let spawns = Cell::new(0);
+        let spawn = |_: &Path| {
+            spawns.set(spawns.get() + 1);
+            Ok(format!("rustc 1.{}.0", 69 + spawns.get()))
+        };
+
+        assert_eq!(verbose_version_with(path, spawn), Ok("rustc 1.70.0".into()));
+        assert_eq!(refresh_with(path, spawn), Ok("rustc 1.71.0".into()));
+        assert_eq!(verbose_version_with(path, spawn), Ok("rustc 1.71.0".into()));
+        assert_eq!(refresh_with(path, spawn), Ok("rustc 1.72.0".into()));
+        assert_eq!(verbose_version(path), Ok("rustc 1.72.0".into()));
+        assert_eq!(spawns.get(), 3);
+    }
+
+    #[test]
+    fn caches_failures() {
+        let path = Path::new("/version_check/missing/rustc");
+        let first = verbose_version(path);
+        assert!(first.is_err());
+        assert_eq!(verbose_version(path), first);
+    }
+
+    #[test]
+    fn set_and_remove() {
+        let path = Path::new("/version_check/cache/set/rustc");
+        set(path, Ok("rustc 1.70.0".into()));
+        assert_eq!(verbose_version(path), Ok("rustc 1.70.0".into()));
+
+        set(path, Err(RustcError::MissingVersion));
+        assert_eq!(verbose_version(path), Err(RustcError::MissingVersion));
+
+        // Other tests read through the cache concurrently: only remove `path`.
+        remove(path);
+        assert!(match verbose_version(path) {
+            Err(RustcError::Spawn(_)) => true,
+            _ => false
+        });
+    }
+}
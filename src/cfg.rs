@@ -0,0 +1,165 @@
+use {Version, Date, PartialTriple};
+
+/// A rule mapping requirements on the running or installed `rustc` to a `cfg`
+/// flag, as evaluated by [`apply_cfg_rules()`].
+///
+/// Each requirement is optional; the `cfg` is set only when every present
+/// requirement holds. A rule with no requirements always holds.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check::CfgRule;
+///
+/// let rule = CfgRule {
+///     cfg: "nightly_let_chains",
+///     min_version: Some("1.64.0"),
+///     channel: Some("nightly"),
+///     min_date: Some("2022-07-01"),
+/// };
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CfgRule<'a> {
+    /// The `cfg` flag to set, as in `cargo:rustc-cfg={cfg}`.
+    pub cfg: &'a str,
+    /// The minimum version, compared as in [`Version::at_least()`].
+    pub min_version: Option<&'a str>,
+    /// The exact release channel: `"dev"`, `"nightly"`, `"beta"`, or
+    /// `"stable"`.
+    pub channel: Option<&'a str>,
+    /// The minimum release date, in `YYYY-MM-DD` format, inclusive.
+    pub min_date: Option<&'a str>,
+}
+
+impl<'a> CfgRule<'a> {
+    /// Returns whether every present requirement is satisfied by `triple`. A
+    /// requirement on an attribute that is unknown is not satisfied. Returns
+    /// `None` if the rule is invalid: a requirement could not be parsed.
+    fn holds(&self, triple: &PartialTriple) -> Option<bool> {
+        let mut holds = true;
+        if let Some(min_version) = self.min_version {
+            match Version::parse(min_version) {
+                Some(_) => holds &= triple.is_min_version(min_version) == Some(true),
+                None => return None
+            }
+        }
+
+        if let Some(name) = self.channel {
+            match ::channel_from_name(name) {
+                Some(_) => holds &= triple.is_channel(name) == Some(true),
+                None => return None
+            }
+        }
+
+        if let Some(min_date) = self.min_date {
+            match Date::parse(min_date) {
+                Some(_) => holds &= triple.is_min_date(min_date) == Some(true),
+                None => return None
+            }
+        }
+
+        Some(holds)
+    }
+}
+
+/// Sets `cfg` flags based on rules combining version, channel, and date
+/// requirements on the running or installed `rustc`.
+///
+/// The version, channel, and date are read once, from a single invocation of
+/// `rustc`, and `cargo:rustc-cfg={cfg}` is printed for every rule whose
+/// present requirements all hold, as described in [`CfgRule`]. Invalid rules,
+/// where a requirement cannot be parsed, are skipped. For rules with only a
+/// minimum version, [`apply_version_cfgs()`](crate::apply_version_cfgs()) is more
+/// concise. To obtain the lines without printing them, use
+/// [`plan_cfg_rules()`].
+///
+/// # Example
+///
+/// ```rust
+/// use version_check::{self as rustc, CfgRule};
+///
+/// rustc::apply_cfg_rules(&[
+///     CfgRule { cfg: "has_let_else", min_version: Some("1.65.0"), channel: None, min_date: None },
+///     CfgRule {
+///         cfg: "nightly_let_chains",
+///         min_version: None,
+///         channel: Some("nightly"),
+///         min_date: Some("2022-07-01"),
+///     },
+/// ]);
+/// ```
+pub fn apply_cfg_rules(rules: &[CfgRule]) {
+    for line in plan_cfg_rules(rules) {
+        println!("{}", line);
+    }
+}
+
+/// Returns the lines [`apply_cfg_rules()`] would print for `rules`, in order,
+/// without printing them.
+///
+/// Each line is of the form `cargo:rustc-cfg={cfg}`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check::{self as rustc, CfgRule};
+///
+/// let always = CfgRule { cfg: "always", min_version: None, channel: None, min_date: None };
+/// assert_eq!(rustc::plan_cfg_rules(&[always]), ["cargo:rustc-cfg=always"]);
+/// ```
+pub fn plan_cfg_rules(rules: &[CfgRule]) -> Vec<String> {
+    plan_with(rules, &PartialTriple::read())
+}
+
+fn plan_with(rules: &[CfgRule], triple: &PartialTriple) -> Vec<String> {
+    rules.iter()
+        .filter(|rule| rule.holds(triple) == Some(true))
+        .map(|rule| format!("cargo:rustc-cfg={}", rule.cfg))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CfgRule, plan_with};
+    use {PartialTriple, Version};
+
+    #[test]
+    fn test_holds() {
+        let rule = |min_version, channel, min_date| CfgRule {
+            cfg: "test", min_version: min_version, channel: channel, min_date: min_date
+        };
+
+        let triple = PartialTriple { version: Version::parse("1.70.0"), ..Default::default() };
+        let holds = |rule: CfgRule| rule.holds(&triple);
+
+        assert_eq!(holds(rule(None, None, None)), Some(true));
+        assert_eq!(holds(rule(Some("1.56.0"), None, None)), Some(true));
+        assert_eq!(holds(rule(Some("1.71.0"), None, None)), Some(false));
+
+        // A requirement on an unknown attribute is not satisfied.
+        assert_eq!(holds(rule(Some("1.56.0"), Some("stable"), None)), Some(false));
+        assert_eq!(holds(rule(Some("1.56.0"), None, Some("2000-01-01"))), Some(false));
+
+        assert_eq!(holds(rule(Some("one.two"), None, None)), None);
+        assert_eq!(holds(rule(None, Some("1.70.0"), None)), None);
+        assert_eq!(holds(rule(None, None, Some("2023-13-01"))), None);
+    }
+
+    #[test]
+    fn test_plan() {
+        let rule = |cfg, min_version, channel| CfgRule {
+            cfg: cfg, min_version: min_version, channel: channel, min_date: None
+        };
+
+        let rules = [
+            rule("a", Some("1.69.0"), None),
+            rule("b", Some("1.71.0"), None),
+            rule("c", None, Some("stable")),
+            rule("d", Some("one.two"), None),
+        ];
+
+        let triple = PartialTriple { version: Version::parse("1.70.0"), ..Default::default() };
+        assert_eq!(plan_with(&rules, &triple), ["cargo:rustc-cfg=a"]);
+        assert!(plan_with(&rules, &PartialTriple::default()).is_empty());
+    }
+}
@@ -1,6 +1,11 @@
 use std::fmt;
+use std::cmp::Ordering;
+use std::path::Path;
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+use RustcError;
+
+/// The variant order is the release maturity order used by `Ord`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, PartialOrd, Ord)]
 enum Kind {
     Dev,
     Nightly,
@@ -9,13 +14,76 @@ enum Kind {
 }
 
 /// Release channel: "dev", "nightly", "beta", or "stable".
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+///
+/// Channels are ordered by release maturity: `dev` < `nightly` < `beta` <
+/// `stable`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, PartialOrd, Ord)]
 pub struct Channel(Kind);
 
 impl Channel {
+    /// Returns the `dev` release channel.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Channel;
+    ///
+    /// assert!(Channel::dev().is_dev());
+    /// assert_eq!(Channel::dev(), Channel::parse("1.3.0-dev").unwrap());
+    /// ```
+    pub fn dev() -> Channel {
+        Channel(Kind::Dev)
+    }
+
+    /// Returns the `nightly` release channel.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Channel;
+    ///
+    /// assert!(Channel::nightly().is_nightly());
+    /// assert_eq!(Channel::nightly(), Channel::parse("1.42.2-nightly").unwrap());
+    /// ```
+    pub fn nightly() -> Channel {
+        Channel(Kind::Nightly)
+    }
+
+    /// Returns the `beta` release channel.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Channel;
+    ///
+    /// assert!(Channel::beta().is_beta());
+    /// assert_eq!(Channel::beta(), Channel::parse("1.32.0-beta").unwrap());
+    /// ```
+    pub fn beta() -> Channel {
+        Channel(Kind::Beta)
+    }
+
+    /// Returns the `stable` release channel.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Channel;
+    ///
+    /// assert!(Channel::stable().is_stable());
+    /// assert_eq!(Channel::stable(), Channel::parse("1.4.0").unwrap());
+    /// ```
+    pub fn stable() -> Channel {
+        Channel(Kind::Stable)
+    }
+
     /// Reads the release channel of the running compiler. If it cannot be
     /// determined (see the [top-level documentation](crate)), returns `None`.
     ///
+    /// If `VERSION_CHECK_FAKE_CHANNEL` is set, its value, a channel name like
+    /// `nightly` or a version like `1.70.0-nightly`, is parsed and returned
+    /// instead, without invoking the compiler. See [testing](crate#testing).
+    ///
     /// # Example
     ///
     /// ```rust
@@ -27,11 +95,109 @@ impl Channel {
     /// };
     /// ```
     pub fn read() -> Option<Channel> {
-        ::get_version_and_date()
+        if let Some(channel) = ::fake_value("VERSION_CHECK_FAKE_CHANNEL") {
+            return Channel::parse(&channel);
+        }
+
+        Channel::read_result().ok()
+    }
+
+    /// Like [`Channel::read()`] but returns `default` if the release channel
+    /// cannot be determined, as when `rustc` reports a version with a
+    /// pre-release tag that is not `dev`, `nightly`, or `beta`, such as some
+    /// distribution builds do.
+    ///
+    /// `default` should be chosen conservatively. [`Channel::stable()`] is
+    /// usually the right choice: it never enables nightly-only code paths for
+    /// a compiler that might not support them. To inspect the unrecognized
+    /// tag, see [`raw_channel()`](crate::raw_channel()).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Channel;
+    ///
+    /// let channel = Channel::read_or(Channel::stable());
+    /// if channel.supports_features() {
+    ///     println!("cargo:rustc-cfg=nightly");
+    /// }
+    /// ```
+    pub fn read_or(default: Channel) -> Channel {
+        Channel::read().unwrap_or(default)
+    }
+
+    /// Reads the release channel of the `rustc` at `path`, which may also be a
+    /// program name to look up in `PATH`. Unlike [`Channel::read()`],
+    /// `VERSION_CHECK_OVERRIDE` is not consulted. If the release channel cannot
+    /// be determined, returns `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Channel;
+    ///
+    /// match Channel::read_for_path("/usr/local/bin/rustc") {
+    ///     Some(c) => format!("The channel is: {}", c),
+    ///     None => format!("Failed to read the release channel.")
+    /// };
+    ///
+    /// assert!(Channel::read_for_path("/nonexistent/rustc").is_none());
+    /// ```
+    pub fn read_for_path<P: AsRef<Path>>(path: P) -> Option<Channel> {
+        ::get_version_and_date_at(path.as_ref())
             .and_then(|(version, _)| version)
             .and_then(|version| Channel::parse(&version))
     }
 
+    /// Reads the release channel of the running compiler. If it cannot be
+    /// determined, returns the reason why: either `rustc` could not be read
+    /// or its version string does not name a recognized release channel.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::{Channel, RustcError};
+    ///
+    /// match Channel::read_result() {
+    ///     Ok(c) => format!("The channel is: {}", c),
+    ///     Err(RustcError::UnknownChannel(v)) => format!("Unknown channel: {}", v),
+    ///     Err(e) => format!("Failed to read the release channel: {}", e),
+    /// };
+    /// ```
+    pub fn read_result() -> Result<Channel, RustcError> {
+        let version = match ::read_version_and_date() {
+            Ok((Some(version), _)) => version,
+            Ok((None, _)) => return Err(RustcError::MissingVersion),
+            Err(e) => return Err(e)
+        };
+
+        Channel::parse(&version).ok_or(RustcError::UnknownChannel(version))
+    }
+
+    /// Heuristically extracts the release channel encoded in the `rustup`
+    /// toolchain name `name`, such as the value of `RUSTUP_TOOLCHAIN`, without
+    /// invoking `rustc`.
+    ///
+    /// Names starting with `stable`, `beta`, or `nightly` yield that channel,
+    /// and names pinned to a version, like `1.70.0-x86_64-unknown-linux-gnu`,
+    /// yield `stable`. Custom toolchain names yield `None`. As with
+    /// [`Version::from_toolchain_name()`](::Version::from_toolchain_name()),
+    /// this is only a hint and should not replace [`Channel::read()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Channel;
+    ///
+    /// let channel = Channel::from_toolchain_name("nightly-2023-06-01-x86_64-unknown-linux-gnu");
+    /// assert_eq!(channel, Some(Channel::nightly()));
+    /// assert_eq!(Channel::from_toolchain_name("1.70.0"), Some(Channel::stable()));
+    /// assert_eq!(Channel::from_toolchain_name("stage1"), None);
+    /// ```
+    pub fn from_toolchain_name(name: &str) -> Option<Channel> {
+        ::toolchain_name_parts(name).1
+    }
+
     /// Parse a Rust release channel from a Rust release version string (of the
     /// form `major[.minor[.patch[-channel]]]`). Returns `None` if `version` is
     /// not a valid Rust version string.
@@ -78,6 +244,35 @@ impl Channel {
         }
     }
 
+    /// Returns the pre-release tag that marks a version string as belonging to
+    /// this channel, as recognized by [`Channel::parse()`]: `Some("dev")`,
+    /// `Some("nightly")`, or `Some("beta")`. Stable releases have no
+    /// pre-release tag, so returns `None` for `stable`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Channel;
+    ///
+    /// assert_eq!(Channel::dev().pre_release_tag(), Some("dev"));
+    /// assert_eq!(Channel::nightly().pre_release_tag(), Some("nightly"));
+    /// assert_eq!(Channel::beta().pre_release_tag(), Some("beta"));
+    /// assert_eq!(Channel::stable().pre_release_tag(), None);
+    ///
+    /// let version = match Channel::nightly().pre_release_tag() {
+    ///     Some(tag) => format!("1.70.0-{}", tag),
+    ///     None => format!("1.70.0"),
+    /// };
+    ///
+    /// assert_eq!(Channel::parse(&version), Some(Channel::nightly()));
+    /// ```
+    pub fn pre_release_tag(&self) -> Option<&'static str> {
+        match self.0 {
+            Kind::Dev | Kind::Nightly | Kind::Beta => Some(self.as_str()),
+            Kind::Stable => None,
+        }
+    }
+
     /// Returns `true` if this channel supports feature flags. In other words,
     /// returns `true` if the channel is either `dev` or `nightly`.
     ///
@@ -107,6 +302,119 @@ impl Channel {
         }
     }
 
+    /// Returns the rank of this channel by access to compiler features, where
+    /// a higher rank means more access: `stable` (`0`) < `beta` (`1`) <
+    /// `nightly` (`2`) < `dev` (`3`). A `dev` build of the compiler is ranked
+    /// highest as it is the most capable, even if it is not the most recent.
+    ///
+    /// This ranking concerns feature capability, not release maturity or
+    /// recency. It is the reverse of `Ord` for `Channel`, which orders
+    /// channels by release maturity.
+    ///
+    /// The ranks are stable and can be used as a compact, serializable
+    /// representation of a channel; [`Channel::from_feature_rank()`] is the
+    /// inverse.
+    ///
+    /// **Please see the note on [feature detection](crate#feature-detection).**
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Channel;
+    ///
+    /// assert!(Channel::dev().feature_rank() > Channel::nightly().feature_rank());
+    /// assert!(Channel::nightly().feature_rank() > Channel::beta().feature_rank());
+    /// assert!(Channel::beta().feature_rank() > Channel::stable().feature_rank());
+    ///
+    /// // At least as capable as nightly: `nightly` or `dev`.
+    /// let channel = Channel::parse("1.3.0-dev").unwrap();
+    /// assert!(channel.feature_rank() >= Channel::nightly().feature_rank());
+    /// ```
+    pub fn feature_rank(&self) -> u8 {
+        match self.0 {
+            Kind::Stable => 0,
+            Kind::Beta => 1,
+            Kind::Nightly => 2,
+            Kind::Dev => 3,
+        }
+    }
+
+    /// Returns the channel with the [feature rank](Channel::feature_rank())
+    /// `rank`: `stable` for `0`, `beta` for `1`, `nightly` for `2`, and `dev`
+    /// for `3`. Returns `None` for any other `rank`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Channel;
+    ///
+    /// assert_eq!(Channel::from_feature_rank(0), Some(Channel::stable()));
+    /// assert_eq!(Channel::from_feature_rank(3), Some(Channel::dev()));
+    /// assert_eq!(Channel::from_feature_rank(4), None);
+    ///
+    /// let nightly = Channel::nightly();
+    /// assert_eq!(Channel::from_feature_rank(nightly.feature_rank()), Some(nightly));
+    /// ```
+    pub fn from_feature_rank(rank: u8) -> Option<Channel> {
+        match rank {
+            0 => Some(Channel(Kind::Stable)),
+            1 => Some(Channel(Kind::Beta)),
+            2 => Some(Channel(Kind::Nightly)),
+            3 => Some(Channel(Kind::Dev)),
+            _ => None
+        }
+    }
+
+    /// Compares this channel to `other` by recency in the release train: how
+    /// bleeding-edge each channel is. From least to most recent, the channels
+    /// are `stable`, `beta`, `nightly`, and `dev`, as code reaches `stable`
+    /// last.
+    ///
+    /// This is the reverse of `Ord` for `Channel`, which orders channels by
+    /// release maturity. It is unrelated to [`Channel::feature_rank()`], which
+    /// concerns access to compiler features, though it happens to agree.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::cmp::Ordering;
+    /// use version_check::Channel;
+    ///
+    /// assert_eq!(Channel::stable().cmp_recency(&Channel::beta()), Ordering::Less);
+    /// assert_eq!(Channel::beta().cmp_recency(&Channel::nightly()), Ordering::Less);
+    /// assert_eq!(Channel::dev().cmp_recency(&Channel::nightly()), Ordering::Greater);
+    /// assert_eq!(Channel::beta().cmp_recency(&Channel::beta()), Ordering::Equal);
+    ///
+    /// // `Ord` orders by maturity instead.
+    /// assert!(Channel::stable() > Channel::beta());
+    /// ```
+    pub fn cmp_recency(&self, other: &Channel) -> Ordering {
+        other.0.cmp(&self.0)
+    }
+
+    /// Returns `true` if this channel is at least as mature as `other` and
+    /// `false` otherwise. From least to most mature, the channels are `dev`,
+    /// `nightly`, `beta`, and `stable`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Channel;
+    ///
+    /// // Beta or stable.
+    /// assert!(Channel::stable().is_at_least(Channel::beta()));
+    /// assert!(Channel::beta().is_at_least(Channel::beta()));
+    /// assert!(!Channel::nightly().is_at_least(Channel::beta()));
+    /// assert!(!Channel::dev().is_at_least(Channel::beta()));
+    ///
+    /// // Any channel.
+    /// assert!(Channel::dev().is_at_least(Channel::dev()));
+    /// assert!(Channel::stable().is_at_least(Channel::dev()));
+    /// ```
+    pub fn is_at_least(&self, other: Channel) -> bool {
+        *self >= other
+    }
+
     /// Returns `true` if this channel is `dev` and `false` otherwise.
     ///
     /// # Example
@@ -193,3 +501,37 @@ impl fmt::Display for Channel {
         write!(f, "{}", self.as_str())
     }
 }
+
+impl AsRef<str> for Channel {
+    /// Returns the name of the release channel, as in `Display`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Channel;
+    ///
+    /// let channel = Channel::nightly();
+    /// assert_eq!(channel.as_ref(), "nightly");
+    /// assert_eq!(Channel::parse(channel.as_ref()), Some(channel));
+    /// ```
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<Channel> for String {
+    /// Returns the name of the release channel, as in `Display`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Channel;
+    ///
+    /// let name: String = Channel::beta().into();
+    /// assert_eq!(name, "beta");
+    /// assert_eq!(Channel::parse(&name), Some(Channel::beta()));
+    /// ```
+    fn from(channel: Channel) -> String {
+        channel.as_str().to_string()
+    }
+}
@@ -0,0 +1,107 @@
+use PartialTriple;
+
+/// A single named check on the running or installed `rustc`, as evaluated by
+/// [`evaluate()`].
+///
+/// Versions are compared ignoring pre-release tags, as in
+/// [`is_min_version()`](crate::is_min_version()), and dates inclusively, as in
+/// [`is_min_date()`](crate::is_min_date()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    /// The version is at least this version.
+    MinVersion(String),
+    /// The version is at most this version.
+    MaxVersion(String),
+    /// The release date is on or after this date, in `YYYY-MM-DD` format.
+    MinDate(String),
+    /// The release date is on or before this date, in `YYYY-MM-DD` format.
+    MaxDate(String),
+    /// The release channel is the one named: `"dev"`, `"nightly"`, `"beta"`,
+    /// or `"stable"`.
+    Channel(String),
+}
+
+impl Check {
+    /// Returns the result of this check against `triple`, or `None` if the
+    /// check cannot be parsed or the attribute it needs is unknown.
+    fn holds(&self, triple: &PartialTriple) -> Option<bool> {
+        match *self {
+            Check::MinVersion(ref s) => triple.is_min_version(s),
+            Check::MaxVersion(ref s) => triple.is_max_version(s),
+            Check::MinDate(ref s) => triple.is_min_date(s),
+            Check::MaxDate(ref s) => triple.is_max_date(s),
+            Check::Channel(ref s) => triple.is_channel(s),
+        }
+    }
+}
+
+/// Evaluates every named check in `checks` against the running or installed
+/// `rustc` and returns each name paired with its result, in order.
+///
+/// The version, channel, and date are read once, from a single invocation of
+/// `rustc`, regardless of the number of checks. Returns `None` if any check
+/// cannot be parsed or needs an attribute of `rustc` that cannot be
+/// determined; an empty list of checks evaluates to an empty list without
+/// invoking `rustc`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check::{self as rustc, Check};
+///
+/// let checks = [
+///     ("let_else", Check::MinVersion("1.65.0".into())),
+///     ("nightly", Check::Channel("nightly".into())),
+/// ];
+///
+/// if let Some(results) = rustc::evaluate(&checks) {
+///     for (name, passed) in results {
+///         println!("cargo:warning={}: {}", name, passed);
+///     }
+/// }
+/// ```
+pub fn evaluate<'a>(checks: &[(&'a str, Check)]) -> Option<Vec<(&'a str, bool)>> {
+    if checks.is_empty() {
+        return Some(vec![]);
+    }
+
+    evaluate_with(checks, &PartialTriple::read())
+}
+
+fn evaluate_with<'a>(checks: &[(&'a str, Check)], triple: &PartialTriple)
+    -> Option<Vec<(&'a str, bool)>>
+{
+    let mut results = Vec::with_capacity(checks.len());
+    for check in checks {
+        match check.1.holds(triple) {
+            Some(result) => results.push((check.0, result)),
+            None => return None,
+        }
+    }
+
+    Some(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Check, evaluate_with};
+    use {PartialTriple, Version};
+
+    #[test]
+    fn test_evaluate() {
+        let triple = PartialTriple { version: Version::parse("1.70.0"), ..Default::default() };
+        let checks = [
+            ("new", Check::MinVersion("1.71.0".into())),
+            ("old", Check::MinVersion("1.56.0".into())),
+        ];
+
+        assert_eq!(evaluate_with(&checks, &triple), Some(vec![("new", false), ("old", true)]));
+
+        // A single check that cannot be evaluated fails the whole list.
+        let checks = [("old", Check::MinVersion("1.56.0".into())),
+            ("nightly", Check::Channel("nightly".into()))];
+        assert_eq!(evaluate_with(&checks, &triple), None);
+        assert_eq!(evaluate_with(&[("x", Check::MinVersion("one".into()))], &triple), None);
+        assert_eq!(::evaluate(&[]), Some(vec![]));
+    }
+}
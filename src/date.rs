@@ -1,4 +1,7 @@
-use std::fmt;
+use std::{error, fmt};
+use std::cmp::Ordering;
+use std::str::FromStr;
+use std::path::Path;
 
 /// Release date including year, month, and day.
 // Internal storage is: y[31..9] | m[8..5] | d[5...0].
@@ -9,6 +12,9 @@ impl Date {
     /// Reads the release date of the running compiler. If it cannot be
     /// determined (see the [top-level documentation](crate)), returns `None`.
     ///
+    /// If `VERSION_CHECK_FAKE_DATE` is set, its value is parsed and returned
+    /// instead, without invoking the compiler. See [testing](crate#testing).
+    ///
     /// # Example
     ///
     /// ```rust
@@ -20,14 +26,66 @@ impl Date {
     /// };
     /// ```
     pub fn read() -> Option<Date> {
+        if let Some(date) = ::fake_value("VERSION_CHECK_FAKE_DATE") {
+            return Date::parse(&date);
+        }
+
         ::get_version_and_date()
             .and_then(|(_, date)| date)
             .and_then(|date| Date::parse(&date))
     }
 
+    /// Reads the release date of the `rustc` at `path`, which may also be a
+    /// program name to look up in `PATH`. Unlike [`Date::read()`],
+    /// `VERSION_CHECK_OVERRIDE` is not consulted. If the release date cannot be
+    /// determined, returns `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Date;
+    ///
+    /// match Date::read_for_path("/usr/local/bin/rustc") {
+    ///     Some(d) => format!("The release date is: {}", d),
+    ///     None => format!("Failed to read the release date.")
+    /// };
+    ///
+    /// assert!(Date::read_for_path("/nonexistent/rustc").is_none());
+    /// ```
+    pub fn read_for_path<P: AsRef<Path>>(path: P) -> Option<Date> {
+        ::get_version_and_date_at(path.as_ref())
+            .and_then(|(_, date)| date)
+            .and_then(|date| Date::parse(&date))
+    }
+
+    /// Heuristically extracts the date encoded in the `rustup` toolchain name
+    /// `name`, such as the value of `RUSTUP_TOOLCHAIN`, without invoking
+    /// `rustc`.
+    ///
+    /// Only dated channel names, like
+    /// `nightly-2023-06-01-x86_64-unknown-linux-gnu`, encode a date. As with
+    /// [`Version::from_toolchain_name()`](::Version::from_toolchain_name()),
+    /// this is only a hint and should not replace [`Date::read()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Date;
+    ///
+    /// let date = Date::from_toolchain_name("nightly-2023-06-01-x86_64-unknown-linux-gnu");
+    /// assert_eq!(date, Date::parse("2023-06-01"));
+    /// assert_eq!(Date::from_toolchain_name("1.70.0"), None);
+    /// ```
+    pub fn from_toolchain_name(name: &str) -> Option<Date> {
+        ::toolchain_name_parts(name).2
+    }
+
     /// Parse a release date of the form `%Y-%m-%d`. Returns `None` if `date` is
     /// not in `%Y-%m-%d` format.
     ///
+    /// An RFC 3339-style time and zone suffix following a `T`, as in
+    /// `2023-06-01T00:00:00Z`, is accepted and ignored.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -40,6 +98,7 @@ impl Date {
     /// assert!(date.exactly("2016-04-20"));
     ///
     /// assert!(Date::parse("2021-12-31").unwrap().exactly("2021-12-31"));
+    /// assert!(Date::parse("2023-06-01T00:00:00Z").unwrap().exactly("2023-06-01"));
     ///
     /// assert!(Date::parse("March 13, 2018").is_none());
     /// assert!(Date::parse("1-2-3-4-5").is_none());
@@ -47,22 +106,48 @@ impl Date {
     /// assert!(Date::parse("2020-12-12 1").is_none());
     /// assert!(Date::parse("2020-10").is_none());
     /// assert!(Date::parse("2020").is_none());
+    /// assert!(Date::parse("2020-13-01T00:00:00Z").is_none());
     /// ```
     pub fn parse(date: &str) -> Option<Date> {
+        Date::from_str(date).ok()
+    }
+
+    /// Parses `date` as in [`Date::parse()`], returning the first invalid
+    /// component on error.
+    fn parse_with_error(date: &str) -> Result<Date, ParseDateError> {
+        // Drop a trailing `T<time>[<zone>]`, as found in RFC 3339 timestamps.
+        let date = match date.find(|c| c == 'T' || c == 't') {
+            Some(i) if date[i + 1..].starts_with(|c: char| c.is_digit(10)) => &date[..i],
+            _ => date,
+        };
+
+        const COMPONENTS: [&'static str; 3] = ["year", "month", "day"];
+
         let mut ymd = [0u16; 3];
+        let mut count = 0;
         for (i, split) in date.split('-').map(|s| s.parse::<u16>()).enumerate() {
             ymd[i] = match (i, split) {
-                (3, _) | (_, Err(_)) => return None,
+                // Anything after the day is part of an invalid day.
+                (3, _) => return Err(ParseDateError::new("day")),
+                (_, Err(_)) => return Err(ParseDateError::new(COMPONENTS[i])),
                 (_, Ok(v)) => v,
             };
+
+            count += 1;
         }
 
         let (year, month, day) = (ymd[0], ymd[1], ymd[2]);
-        if year == 0 || month == 0 || month > 12 || day == 0 || day > 31 {
-            return None;
+        if count < 3 {
+            return Err(ParseDateError::new(COMPONENTS[count]));
+        } else if year == 0 {
+            return Err(ParseDateError::new("year"));
+        } else if month == 0 || month > 12 {
+            return Err(ParseDateError::new("month"));
+        } else if day == 0 || day > 31 {
+            return Err(ParseDateError::new("day"));
         }
 
-        Some(Date::from_ymd(year, month as u8, day as u8))
+        Ok(Date::from_ymd(year, month as u8, day as u8))
     }
 
     /// Creates a `Date` from `(year, month, day)` date components.
@@ -90,6 +175,43 @@ impl Date {
         Date(year | month | day)
     }
 
+    /// Creates a `Date` from `(year, month, day)` date components if they form
+    /// a valid calendar date and returns `None` otherwise.
+    ///
+    /// Unlike [`Date::from_ymd()`], which truncates its inputs, the `year` must
+    /// be nonzero, the `month` must be in `1..=12`, and the `day` must exist in
+    /// that month, accounting for leap years.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Date;
+    ///
+    /// assert_eq!(Date::from_ymd_checked(2021, 7, 30), Date::parse("2021-07-30"));
+    /// assert_eq!(Date::from_ymd_checked(2024, 2, 29), Date::parse("2024-02-29"));
+    ///
+    /// assert!(Date::from_ymd_checked(2023, 2, 29).is_none());
+    /// assert!(Date::from_ymd_checked(2021, 4, 31).is_none());
+    /// assert!(Date::from_ymd_checked(2021, 13, 1).is_none());
+    /// assert!(Date::from_ymd_checked(2021, 0, 1).is_none());
+    /// assert!(Date::from_ymd_checked(2021, 1, 0).is_none());
+    /// ```
+    pub fn from_ymd_checked(year: u16, month: u8, day: u8) -> Option<Date> {
+        let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return None,
+        };
+
+        match year != 0 && day != 0 && day <= days {
+            true => Some(Date::from_ymd(year, month, day)),
+            false => None
+        }
+    }
+
     /// Return the original (YYYY, MM, DD).
     fn to_ymd(&self) -> (u16, u8, u8) {
         let y = self.0 >> 9;
@@ -98,6 +220,85 @@ impl Date {
         (y as u16, m as u8, d as u8)
     }
 
+    /// Returns the UTC calendar date of the instant `secs` seconds after the
+    /// Unix epoch, `1970-01-01T00:00:00Z`, or `None` if its year does not fit
+    /// in a `u16`.
+    ///
+    /// Paired with the system clock, this yields the current date, for example
+    /// to check how old a nightly is. The conversion is purely arithmetic; no
+    /// time zone database is consulted, so the date is always in UTC.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Date;
+    ///
+    /// assert_eq!(Date::from_unix_time(0), Date::parse("1970-01-01"));
+    /// assert_eq!(Date::from_unix_time(1_709_164_800), Date::parse("2024-02-29"));
+    ///
+    /// // A timestamp as read from the system clock: 2023-05-31T12:00:00Z.
+    /// let now = 1_685_534_400;
+    /// assert_eq!(Date::from_unix_time(now), Date::parse("2023-05-31"));
+    /// ```
+    pub fn from_unix_time(secs: u64) -> Option<Date> {
+        // Shift the epoch to 0000-03-01 so that leap days end each year, then
+        // split into 400-year eras of 146097 days. See Howard Hinnant's
+        // `civil_from_days`: http://howardhinnant.github.io/date_algorithms.html
+        let z = secs / 86400 + 719468;
+        let era = z / 146097;
+        let doe = z % 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = era * 400 + yoe + if month <= 2 { 1 } else { 0 };
+
+        match year <= ::std::u16::MAX as u64 {
+            true => Some(Date::from_ymd(year as u16, month as u8, day as u8)),
+            false => None
+        }
+    }
+
+    /// Returns the number of days since the Unix epoch, `1970-01-01`, which is
+    /// negative for earlier dates. The inverse of the date computation in
+    /// [`Date::from_unix_time()`].
+    fn days_since_epoch(&self) -> i64 {
+        let (year, month, day) = self.to_ymd();
+        let (month, day) = (month as i64, day as i64);
+        let year = year as i64 - if month <= 2 { 1 } else { 0 };
+        let era = if year >= 0 { year } else { year - 399 } / 400;
+        let yoe = year - era * 400;
+        let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+
+    /// Returns the number of days from `earlier` to `self`, which is negative
+    /// if `earlier` is after `self`.
+    ///
+    /// With `earlier` set to the `rustc` release date and `self` to today's
+    /// date, as in [`Date::from_unix_time()`], this is the age of the compiler
+    /// in days.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Date;
+    ///
+    /// let nightly = Date::parse("2024-02-20").unwrap();
+    /// let today = Date::parse("2024-03-01").unwrap();
+    /// assert_eq!(today.days_since(&nightly), 10);
+    /// assert_eq!(nightly.days_since(&today), -10);
+    ///
+    /// if today.days_since(&nightly) > 90 {
+    ///     println!("cargo:warning=nightly from {} is over 90 days old", nightly);
+    /// }
+    /// ```
+    pub fn days_since(&self, earlier: &Date) -> i64 {
+        self.days_since_epoch() - earlier.days_since_epoch()
+    }
+
     /// Returns `true` if `self` occurs on or after `date`.
     ///
     /// If `date` occurs before `self`, or if `date` is not in `%Y-%m-%d`
@@ -172,6 +373,56 @@ impl Date {
             .map(|date| self == &date)
             .unwrap_or(false)
     }
+
+    /// Returns `true` if `self` occurs strictly before `date`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Date;
+    ///
+    /// let date = Date::parse("2020-01-01").unwrap();
+    ///
+    /// assert!(date.is_before(&Date::parse("2020-01-02").unwrap()));
+    /// assert!(!date.is_before(&date));
+    /// assert!(!date.is_before(&Date::parse("2019-12-31").unwrap()));
+    /// ```
+    pub fn is_before(&self, date: &Date) -> bool {
+        self < date
+    }
+
+    /// Returns `true` if `self` occurs strictly after `date`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Date;
+    ///
+    /// let date = Date::parse("2020-01-01").unwrap();
+    ///
+    /// assert!(date.is_after(&Date::parse("2019-12-31").unwrap()));
+    /// assert!(!date.is_after(&date));
+    /// assert!(!date.is_after(&Date::parse("2020-01-02").unwrap()));
+    /// ```
+    pub fn is_after(&self, date: &Date) -> bool {
+        self > date
+    }
+
+    /// Returns `true` if `self` occurs on `date`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Date;
+    ///
+    /// let date = Date::parse("2020-01-01").unwrap();
+    ///
+    /// assert!(date.is_on(&Date::from_ymd(2020, 1, 1)));
+    /// assert!(!date.is_on(&Date::parse("2019-12-31").unwrap()));
+    /// ```
+    pub fn is_on(&self, date: &Date) -> bool {
+        self == date
+    }
 }
 
 impl fmt::Display for Date {
@@ -181,16 +432,134 @@ impl fmt::Display for Date {
     }
 }
 
+impl FromStr for Date {
+    type Err = ParseDateError;
+
+    /// Parses a date as in [`Date::parse()`]. On failure, the error reports
+    /// which component of the date was invalid.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Date;
+    ///
+    /// let date: Date = "2023-06-01".parse().unwrap();
+    /// assert_eq!(date, Date::parse("2023-06-01").unwrap());
+    ///
+    /// assert_eq!("2023-13-01".parse::<Date>().unwrap_err().component(), "month");
+    /// assert_eq!("2023-06-32".parse::<Date>().unwrap_err().component(), "day");
+    /// ```
+    fn from_str(s: &str) -> Result<Date, ParseDateError> {
+        Date::parse_with_error(s)
+    }
+}
+
+impl PartialEq<str> for Date {
+    /// Parses `other` and compares. Returns `false` if `other` is not in
+    /// `%Y-%m-%d` format.
+    fn eq(&self, other: &str) -> bool {
+        Date::parse(other).map(|other| *self == other).unwrap_or(false)
+    }
+}
+
+impl<'a> PartialEq<&'a str> for Date {
+    fn eq(&self, other: &&'a str) -> bool {
+        *self == **other
+    }
+}
+
+impl PartialOrd<str> for Date {
+    /// Parses `other` and compares. Returns `None` if `other` is not in
+    /// `%Y-%m-%d` format.
+    fn partial_cmp(&self, other: &str) -> Option<Ordering> {
+        Date::parse(other).and_then(|other| self.partial_cmp(&other))
+    }
+}
+
+impl<'a> PartialOrd<&'a str> for Date {
+    fn partial_cmp(&self, other: &&'a str) -> Option<Ordering> {
+        self.partial_cmp(*other)
+    }
+}
+
+/// An error parsing a [`Date`] from a string.
+///
+/// Reports which component of the date, the `year`, `month`, or `day`, was
+/// missing or invalid.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ParseDateError {
+    component: &'static str,
+}
+
+impl ParseDateError {
+    fn new(component: &'static str) -> ParseDateError {
+        ParseDateError { component: component }
+    }
+
+    /// Returns the name of the first invalid component: `year`, `month`, or
+    /// `day`.
+    pub fn component(&self) -> &'static str {
+        self.component
+    }
+}
+
+impl fmt::Display for ParseDateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid date: missing or invalid {}", self.component)
+    }
+}
+
+impl error::Error for ParseDateError {
+    fn description(&self) -> &str {
+        "invalid date"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Date;
 
+    #[test]
+    fn str_comparisons() {
+        let date = Date::parse("2021-10-21").unwrap();
+        assert!(date == "2021-10-21");
+        assert!(date == *"2021-10-21");
+        assert!(date >= "2021-10-21");
+        assert!(date > "2021-09-30");
+        assert!(date < "2022-01-01");
+        assert!(date != "2021-10-22");
+        assert!(date != "October 21, 2021");
+        assert_eq!(date.partial_cmp("2021-13-01"), None);
+        assert_eq!(date.partial_cmp(""), None);
+    }
+
     macro_rules! reflexive_display {
         ($string:expr) => (
             assert_eq!(Date::parse($string).unwrap().to_string(), $string);
         )
     }
 
+    #[test]
+    fn ordering_helpers() {
+        let date = |s| Date::parse(s).unwrap();
+
+        // Adjacent days across month and year boundaries, in order.
+        let days = ["2019-12-31", "2020-01-01", "2020-01-31", "2020-02-01", "2020-02-28",
+            "2020-02-29", "2020-03-01", "2020-12-31", "2021-01-01"];
+
+        for (i, a) in days.iter().enumerate() {
+            for (j, b) in days.iter().enumerate() {
+                assert_eq!(date(a).is_before(&date(b)), i < j);
+                assert_eq!(date(a).is_after(&date(b)), i > j);
+                assert_eq!(date(a).is_on(&date(b)), i == j);
+            }
+        }
+
+        assert!(date("2023-12-31").is_before(&date("2024-01-01")));
+        assert!(date("2024-01-01").is_after(&date("2023-12-31")));
+        assert!(date("2099-01-01").is_after(&date("2024-12-31")));
+    }
+
     #[test]
     fn display() {
         reflexive_display!("2019-05-08");
@@ -200,4 +569,78 @@ mod tests {
         reflexive_display!("1999-02-19");
         reflexive_display!("9999-12-31");
     }
+
+    #[test]
+    fn unix_time() {
+        let date = |s| Date::parse(s).unwrap();
+
+        assert_eq!(Date::from_unix_time(0), Some(date("1970-01-01")));
+        assert_eq!(Date::from_unix_time(86399), Some(date("1970-01-01")));
+        assert_eq!(Date::from_unix_time(86400), Some(date("1970-01-02")));
+        assert_eq!(Date::from_unix_time(951_782_400), Some(date("2000-02-29")));
+        assert_eq!(Date::from_unix_time(4_107_542_400), Some(date("2100-03-01")));
+        assert_eq!(Date::from_unix_time(::std::u64::MAX), None);
+
+        for &s in &["1970-01-01", "1999-12-31", "2000-02-29", "2024-02-29", "9999-12-31"] {
+            let days = date(s).days_since(&date("1970-01-01"));
+            assert!(days >= 0);
+            assert_eq!(Date::from_unix_time(days as u64 * 86400), Some(date(s)));
+        }
+
+        assert_eq!(date("1969-12-31").days_since(&date("1970-01-01")), -1);
+        assert_eq!(date("2001-01-01").days_since(&date("2000-01-01")), 366);
+        assert_eq!(date("2101-01-01").days_since(&date("2100-01-01")), 365);
+    }
+
+    #[test]
+    fn from_ymd_checked() {
+        assert!(Date::from_ymd_checked(2000, 2, 29).is_some());
+        assert!(Date::from_ymd_checked(1900, 2, 29).is_none());
+        assert!(Date::from_ymd_checked(2100, 2, 29).is_none());
+        assert!(Date::from_ymd_checked(2024, 2, 30).is_none());
+        assert!(Date::from_ymd_checked(2023, 2, 28).is_some());
+        assert!(Date::from_ymd_checked(2023, 12, 31).is_some());
+        assert!(Date::from_ymd_checked(2023, 6, 31).is_none());
+        assert!(Date::from_ymd_checked(2023, 9, 31).is_none());
+        assert!(Date::from_ymd_checked(2023, 11, 31).is_none());
+        assert!(Date::from_ymd_checked(2023, 1, 32).is_none());
+        assert!(Date::from_ymd_checked(0, 1, 1).is_none());
+        assert_eq!(Date::from_ymd_checked(9999, 12, 31).unwrap().to_string(), "9999-12-31");
+    }
+
+    #[test]
+    fn parse_error() {
+        let component = |s: &str| s.parse::<Date>().unwrap_err().component();
+
+        assert_eq!(component(""), "year");
+        assert_eq!(component("March 13, 2018"), "year");
+        assert_eq!(component("0000-01-01"), "year");
+        assert_eq!(component("2020"), "month");
+        assert_eq!(component("2020-x-01"), "month");
+        assert_eq!(component("2020-00-01"), "month");
+        assert_eq!(component("2020-13-01"), "month");
+        assert_eq!(component("2020-10"), "day");
+        assert_eq!(component("2020-12-12 1"), "day");
+        assert_eq!(component("2020-12-00"), "day");
+        assert_eq!(component("2020-12-32"), "day");
+        assert_eq!(component("1-2-3-4-5"), "day");
+
+        for s in &["2020-12-12", "2023-06-01T00:00:00Z", "2020-300-23120", "2020"] {
+            assert_eq!(s.parse::<Date>().ok(), Date::parse(s));
+        }
+    }
+
+    #[test]
+    fn rfc3339_suffix() {
+        let date = Date::parse("2023-06-01T00:00:00Z").unwrap();
+        assert_eq!(date, Date::from_ymd(2023, 6, 1));
+        assert_eq!(date.to_string(), "2023-06-01");
+
+        assert!(Date::parse("2023-06-01t12:30:00+02:00").unwrap().exactly("2023-06-01"));
+        assert!(Date::parse("2023-06-01T").is_none());
+        assert!(Date::parse("2023-06-01Tnoon").is_none());
+        assert!(Date::parse("2023-13-01T00:00:00Z").is_none());
+        assert!(Date::parse("2023-06-32T00:00:00Z").is_none());
+        assert!(Date::parse("T00:00:00Z").is_none());
+    }
 }
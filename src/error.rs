@@ -0,0 +1,80 @@
+use std::{fmt, io};
+use std::error::Error;
+
+/// The reason the installed or running `rustc` could not be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RustcError {
+    /// `rustc` could not be executed. Contains the kind of I/O error that
+    /// occurred when attempting to spawn it.
+    Spawn(io::ErrorKind),
+    /// The output of `rustc` did not contain a version string.
+    MissingVersion,
+    /// The version string reported by `rustc` could not be parsed. Contains
+    /// the version string.
+    InvalidVersion(String),
+    /// The release channel in the version string reported by `rustc` was not
+    /// recognized. Contains the version string.
+    UnknownChannel(String),
+    /// `rustc` was not invoked because `VERSION_CHECK_DISABLE` is set.
+    Disabled,
+}
+
+impl fmt::Display for RustcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RustcError::Spawn(kind) => write!(f, "failed to execute rustc: {:?}", kind),
+            RustcError::MissingVersion => write!(f, "rustc output is missing a version"),
+            RustcError::InvalidVersion(ref v) => write!(f, "invalid rustc version: {}", v),
+            RustcError::UnknownChannel(ref v) => write!(f, "unknown rustc channel: {}", v),
+            RustcError::Disabled => write!(f, "rustc detection is disabled"),
+        }
+    }
+}
+
+impl Error for RustcError {
+    // `description` is required in `1.0.0`.
+    fn description(&self) -> &str {
+        match *self {
+            RustcError::Spawn(_) => "failed to execute rustc",
+            RustcError::MissingVersion => "rustc output is missing a version",
+            RustcError::InvalidVersion(_) => "invalid rustc version",
+            RustcError::UnknownChannel(_) => "unknown rustc channel",
+            RustcError::Disabled => "rustc detection is disabled",
+        }
+    }
+}
+
+/// The reason a feature probe did not produce a clean answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProbeError {
+    /// The probe could not be run: its source file could not be written or
+    /// `rustc` could not be executed.
+    Unavailable,
+    /// The probe failed to compile for a reason other than the feature being
+    /// rejected. Contains the exit code of `rustc`, if any, and its standard
+    /// error.
+    Failed(Option<i32>, String),
+}
+
+impl fmt::Display for ProbeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProbeError::Unavailable => write!(f, "feature probe could not be run"),
+            ProbeError::Failed(Some(code), ref stderr) => {
+                write!(f, "feature probe failed with exit code {}: {}", code, stderr.trim())
+            }
+            ProbeError::Failed(None, ref stderr) => {
+                write!(f, "feature probe failed: {}", stderr.trim())
+            }
+        }
+    }
+}
+
+impl Error for ProbeError {
+    fn description(&self) -> &str {
+        match *self {
+            ProbeError::Unavailable => "feature probe could not be run",
+            ProbeError::Failed(..) => "feature probe failed",
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use {Version, Date, PartialTriple};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Constraint {
+    MinVersion(String),
+    MaxVersion(String),
+    Channel(String),
+    MinDate(String),
+}
+
+/// A conjunction of version, channel, and date constraints on the running or
+/// installed `rustc`, checked with a single invocation of `rustc`.
+///
+/// Constraints are added with [`Gate::min_version()`],
+/// [`Gate::max_version()`], [`Gate::channel()`], and [`Gate::min_date()`].
+/// Adding a constraint never invokes `rustc`; [`Gate::passes()`] reads it
+/// once and checks the constraints in the order they were added, stopping at
+/// the first that fails.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check::Gate;
+///
+/// let passes = Gate::new()
+///     .min_version("1.70.0")
+///     .channel("nightly")
+///     .min_date("2023-04-01")
+///     .passes();
+///
+/// if let Some(true) = passes {
+///     println!("cargo:rustc-cfg=recent_nightly");
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Gate {
+    constraints: Vec<Constraint>,
+    invalid: bool,
+}
+
+impl Gate {
+    /// Returns a new `Gate` without any constraints.
+    pub fn new() -> Gate {
+        Gate { constraints: vec![], invalid: false }
+    }
+
+    fn push(&mut self, valid: bool, constraint: Constraint) -> &mut Gate {
+        match valid {
+            true => self.constraints.push(constraint),
+            false => self.invalid = true,
+        }
+
+        self
+    }
+
+    /// Requires the version to be at least `min_version`, as in
+    /// [`is_min_version()`](crate::is_min_version()).
+    pub fn min_version(&mut self, min_version: &str) -> &mut Gate {
+        let valid = Version::parse(min_version).is_some();
+        self.push(valid, Constraint::MinVersion(min_version.into()))
+    }
+
+    /// Requires the version to be at most `max_version`, as in
+    /// [`is_max_version()`](crate::is_max_version()).
+    pub fn max_version(&mut self, max_version: &str) -> &mut Gate {
+        let valid = Version::parse(max_version).is_some();
+        self.push(valid, Constraint::MaxVersion(max_version.into()))
+    }
+
+    /// Requires the release channel to be the one named `channel`: `"dev"`,
+    /// `"nightly"`, `"beta"`, or `"stable"`.
+    pub fn channel(&mut self, channel: &str) -> &mut Gate {
+        let valid = ::channel_from_name(channel).is_some();
+        self.push(valid, Constraint::Channel(channel.into()))
+    }
+
+    /// Requires the release date to be on or after `min_date`, as in
+    /// [`is_min_date()`](crate::is_min_date()).
+    pub fn min_date(&mut self, min_date: &str) -> &mut Gate {
+        let valid = Date::parse(min_date).is_some();
+        self.push(valid, Constraint::MinDate(min_date.into()))
+    }
+
+    /// Checks whether the running or installed `rustc` satisfies every
+    /// constraint.
+    ///
+    /// Constraints are checked in the order they were added. Returns
+    /// `Some(false)` at the first constraint that fails and `Some(true)` if
+    /// all pass; a gate without constraints passes without invoking `rustc`.
+    /// Returns `None` if a constraint could not be parsed or if an attribute
+    /// needed to check a constraint, before one fails, cannot be determined.
+    pub fn passes(&self) -> Option<bool> {
+        if self.invalid {
+            return None;
+        }
+
+        if self.constraints.is_empty() {
+            return Some(true);
+        }
+
+        Gate::check(&self.constraints, &PartialTriple::read())
+    }
+
+    fn check(constraints: &[Constraint], triple: &PartialTriple) -> Option<bool> {
+        for constraint in constraints {
+            let passes = match *constraint {
+                Constraint::MinVersion(ref min) => triple.is_min_version(min),
+                Constraint::MaxVersion(ref max) => triple.is_max_version(max),
+                Constraint::Channel(ref channel) => triple.is_channel(channel),
+                Constraint::MinDate(ref min) => triple.is_min_date(min),
+            };
+
+            match passes {
+                Some(true) => continue,
+                other => return other,
+            }
+        }
+
+        Some(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Gate;
+    use {PartialTriple, Version, Channel};
+
+    #[test]
+    fn test_check() {
+        let triple = PartialTriple::from((Version::parse("1.70.0"), Channel::parse("1.70.0"),
+            None));
+
+        let check = |gate: &mut Gate| Gate::check(&gate.constraints, &triple);
+        assert_eq!(check(&mut Gate::new()), Some(true));
+        assert_eq!(check(Gate::new().min_version("1.56.0").channel("stable")), Some(true));
+        assert_eq!(check(Gate::new().min_version("1.56.0").max_version("1.69.0")), Some(false));
+
+        // Short-circuits before reaching an unknown attribute.
+        assert_eq!(check(Gate::new().channel("nightly").min_date("2023-01-01")), Some(false));
+        assert_eq!(check(Gate::new().channel("stable").min_date("2023-01-01")), None);
+
+        assert_eq!(Gate::new().min_version("one.two").passes(), None);
+        assert_eq!(Gate::new().channel("1.70.0").passes(), None);
+        assert_eq!(Gate::new().min_date("2023-13-01").min_version("1.0.0").passes(), None);
+        assert_eq!(Gate::new().passes(), Some(true));
+        assert_eq!(Gate::new().min_version("1.0.0").max_version("65535.0.0").passes(), Some(true));
+    }
+}
@@ -0,0 +1,54 @@
+use std::mem;
+use std::path::Path;
+use std::sync::{Mutex, Once, ONCE_INIT};
+
+// We don't use `dyn` because it's not available in `1.0.0`.
+#[allow(bare_trait_objects)]
+type Hook = Box<FnMut(&[&str]) -> Option<String> + Send>;
+
+static INIT: Once = ONCE_INIT;
+
+/// The process-wide hook used to run `rustc` instead of spawning it directly.
+/// Allocated once by `INIT` and never freed.
+static mut HOOK: *const Mutex<Option<Hook>> = 0 as *const Mutex<Option<Hook>>;
+
+fn hook() -> &'static Mutex<Option<Hook>> {
+    unsafe {
+        INIT.call_once(|| {
+            let hook = Box::new(Mutex::new(None));
+            HOOK = mem::transmute::<Box<Mutex<Option<Hook>>>, *const Mutex<Option<Hook>>>(hook);
+        });
+
+        &*HOOK
+    }
+}
+
+/// Sets the hook to `f`, replacing any previous hook.
+pub fn set(f: Hook) {
+    if let Ok(mut hook) = hook().lock() {
+        *hook = Some(f);
+    }
+}
+
+/// Removes the hook, if any.
+pub fn clear() {
+    if let Ok(mut hook) = hook().lock() {
+        *hook = None;
+    }
+}
+
+/// Calls the hook, if one is set, with the path to `rustc` followed by
+/// `args`, and returns its result. Returns `None` if no hook is set.
+pub fn call(path: &Path, args: &[&str]) -> Option<Option<String>> {
+    let mut hook = match hook().lock() {
+        Ok(hook) => hook,
+        Err(_) => return None,
+    };
+
+    hook.as_mut().map(|f| {
+        let program = path.to_string_lossy();
+        let mut argv = vec![&*program];
+        argv.extend(args.iter().cloned());
+        f(&argv)
+    })
+}
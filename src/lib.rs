@@ -2,7 +2,31 @@
 //! version requirements. The version is queried by calling the Rust compiler
 //! with `--version`. The path to the compiler is determined first via the
 //! `RUSTC` environment variable. If it is not set, then `rustc` is used. If
-//! that fails, no determination is made, and calls return `None`.
+//! that fails, no determination is made, and calls return `None`. Because
+//! Cargo sets `RUSTC` for build scripts to the compiler used for the build,
+//! the queried compiler is the one Cargo actually uses, even when a
+//! `rust-toolchain.toml` selects a toolchain other than the one on `PATH`. If
+//! `RUSTC` contains whitespace and does not name an existing file, as in
+//! `ccache rustc`, it is split into a program and leading arguments. If the
+//! compiler is a `rustup` proxy and `RUSTUP_TOOLCHAIN` is set, the toolchain
+//! is passed to the proxy explicitly. If `RUSTC_WRAPPER` is set, the compiler
+//! is invoked through the wrapper. The result is cached, so the compiler is
+//! queried for its version at most once per process; see [`preload()`].
+//!
+//! The compiler need not be invoked at all: if the `VERSION_CHECK_OVERRIDE`
+//! environment variable is set, its value, either the output of `rustc
+//! --verbose --version` or a bare version like `1.70.0` or `1.72.0-nightly`,
+//! is used as the compiler's version information. A bare version carries no
+//! release date. If the value cannot be parsed, calls return `None`.
+//!
+//! As a lightweight testing aid, individual attributes can be faked instead;
+//! see [testing](#testing).
+//!
+//! Hermetic builds that must not spawn any process can set the
+//! `VERSION_CHECK_DISABLE` environment variable to any non-empty value. The
+//! compiler is then never invoked, even to compile feature probes, and every
+//! attribute is treated as undeterminable: calls return `None`, and
+//! `VERSION_CHECK_OVERRIDE` is ignored.
 //!
 //! # Examples
 //!
@@ -23,7 +47,8 @@
 //!   ```
 //!
 //!   See [`is_max_version`] or [`is_exact_version`] to check if the compiler
-//!   is _at most_ or _exactly_ a certain version.
+//!   is _at most_ or _exactly_ a certain version, and [`is_greater_version`] or
+//!   [`is_less_version`] to check if it is _strictly newer_ or _older_.
 //!   <br /><br />
 //!
 //! * Check that the running compiler was released on or after `2018-12-18`:
@@ -84,7 +109,8 @@
 //!
 //! To interact with the version, release date, and release channel as structs,
 //! use [`Version`], [`Date`], and [`Channel`], respectively. The [`triple()`]
-//! function returns all three values efficiently.
+//! function returns all three values efficiently. To query several attributes
+//! of the compiler while invoking it only once, use [`Rustc`].
 //!
 //! # Feature Detection
 //!
@@ -104,6 +130,38 @@
 //! recommendations, you should track the `nightly` channel closely to minimize
 //! the total impact of a nightly breakages.
 //!
+//! # Testing
+//!
+//! To test build scripts against compilers other than the installed one,
+//! individual attributes can be faked: if `VERSION_CHECK_FAKE_VERSION`,
+//! `VERSION_CHECK_FAKE_CHANNEL`, or `VERSION_CHECK_FAKE_DATE` is set, its value
+//! is parsed and returned by [`Version::read()`], [`Channel::read()`], or
+//! [`Date::read()`], respectively, and by the functions built on them, without
+//! invoking the compiler. An invalid value reads as `None`. Unset attributes
+//! are read as usual. For complete control over the compiler's output, see
+//! [`set_rustc_hook()`].
+//!
+//! ```rust
+//! use std::env;
+//! use version_check::{self as rustc, Version, Channel, Date};
+//!
+//! env::set_var("VERSION_CHECK_FAKE_VERSION", "1.70.0-beta.2");
+//! env::set_var("VERSION_CHECK_FAKE_CHANNEL", "nightly");
+//! env::set_var("VERSION_CHECK_FAKE_DATE", "2023-05-31");
+//! assert_eq!(Version::read(), Version::parse("1.70.0-beta.2"));
+//! assert_eq!(Channel::read(), Some(Channel::nightly()));
+//! assert_eq!(Date::read(), Date::parse("2023-05-31"));
+//! assert_eq!(rustc::is_feature_flaggable(), Some(true));
+//!
+//! // A channel name that is not recognized, as reported by some distribution
+//! // builds, reads as `None`.
+//! env::set_var("VERSION_CHECK_FAKE_CHANNEL", "1.70.0-custom");
+//! assert_eq!(Channel::read_or(Channel::stable()), Channel::stable());
+//!
+//! env::set_var("VERSION_CHECK_FAKE_VERSION", "one.seventy");
+//! assert_eq!(Version::read(), None);
+//! ```
+//!
 //! # Alternatives
 //!
 //! This crate is dead simple with no dependencies. If you need something more
@@ -116,25 +174,64 @@
 mod version;
 mod channel;
 mod date;
+mod error;
+mod rustc;
+mod probe;
+mod cache;
+mod cfg;
+mod hook;
+mod gate;
+mod triple;
+mod check;
+mod profile;
 
-use std::env;
+use std::{env, fs, io, mem, process, thread};
+use std::io::Write;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
 use std::process::Command;
+#[cfg(feature = "metrics")]
+use std::sync::atomic::{self, AtomicUsize, ATOMIC_USIZE_INIT};
 
 #[doc(inline)] pub use version::*;
 #[doc(inline)] pub use channel::*;
 #[doc(inline)] pub use date::*;
+#[doc(inline)] pub use error::*;
+#[doc(inline)] pub use rustc::*;
+#[doc(inline)] pub use probe::*;
+#[doc(inline)] pub use cfg::*;
+#[doc(inline)] pub use gate::*;
+#[doc(inline)] pub use triple::*;
+#[doc(inline)] pub use check::*;
+#[doc(inline)] pub use profile::*;
 
 /// Parses (version, date) as available from rustc version string.
 fn version_and_date_from_rustc_version(s: &str) -> (Option<String>, Option<String>) {
-    let last_line = s.lines().last().unwrap_or(s);
-    let mut components = last_line.trim().split(" ");
+    // Blank lines, as output by a misconfigured wrapper, are ignored.
+    let last_line = s.lines().rev().find(|line| !line.trim().is_empty()).unwrap_or("");
+    let mut components = last_line.split(|c: char| c.is_whitespace()).filter(|c| !c.is_empty());
     let version = components.nth(1);
-    let date = components.filter(|c| c.ends_with(')')).next()
-        .map(|s| s.trim_right().trim_right_matches(")").trim_left().trim_left_matches('('));
+
+    // Skip a `(built YYYY-MM-DD)` parenthetical, reported by `1.0.0`: it holds
+    // the build date, not the commit date.
+    let mut in_built = false;
+    let date = components.find(|c| {
+        in_built = in_built || c.starts_with("(built");
+        let (is_end, skip) = (c.ends_with(')'), in_built);
+        if is_end {
+            in_built = false;
+        }
+
+        is_end && !skip
+    }).map(|s| s.trim_right().trim_right_matches(")").trim_left().trim_left_matches('('));
     (version.map(|s| s.to_string()), date.map(|s| s.to_string()))
 }
 
 /// Parses (version, date) as available from rustc verbose version output.
+/// Lines that are neither the `rustc` header nor a known field, such as
+/// warnings interleaved by a wrapper, are skipped wherever they appear.
 fn version_and_date_from_rustc_verbose_version(s: &str) -> (Option<String>, Option<String>) {
     let (mut version, mut date) = (None, None);
     for line in s.lines() {
@@ -155,314 +252,4277 @@ fn version_and_date_from_rustc_verbose_version(s: &str) -> (Option<String>, Opti
     (version, date)
 }
 
-/// Returns (version, date) as available from `rustc --version`.
-fn get_version_and_date() -> Option<(Option<String>, Option<String>)> {
-    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
-    Command::new(rustc).arg("--verbose").arg("--version").output().ok()
-        .and_then(|output| String::from_utf8(output.stdout).ok())
-        .map(|s| version_and_date_from_rustc_verbose_version(&s))
-}
+/// Returns the value of the `key: value` line `key` in rustc verbose version
+/// output, if there is one.
+fn verbose_field<'a>(s: &'a str, key: &str) -> Option<&'a str> {
+    for line in s.lines() {
+        let mut kv = line.trim().splitn(2, ':');
+        match (kv.next(), kv.next()) {
+            (Some(k), Some(v)) if k == key => return Some(v.trim()),
+            _ => continue
+        }
+    }
 
-/// Reads the triple of [`Version`], [`Channel`], and [`Date`] of the installed
-/// or running `rustc`.
-///
-/// If any attribute cannot be determined (see the [top-level
-/// documentation](crate)), returns `None`.
-///
-/// To obtain only one of three attributes, use [`Version::read()`],
-/// [`Channel::read()`], or [`Date::read()`].
-pub fn triple() -> Option<(Version, Channel, Date)> {
-    let (version_str, date_str) = match get_version_and_date() {
-        Some((Some(version), Some(date))) => (version, date),
-        _ => return None
-    };
+    None
+}
 
-    // Can't use `?` or `try!` for `Option` in 1.0.0.
-    match Version::parse(&version_str) {
-        Some(version) => match Channel::parse(&version_str) {
-            Some(channel) => match Date::parse(&date_str) {
-                Some(date) => Some((version, channel, date)),
-                _ => None,
-            },
-            _ => None,
-        },
+/// Returns the channel named exactly `name`: `dev`, `nightly`, `beta`, or
+/// `stable`.
+fn channel_from_name(name: &str) -> Option<Channel> {
+    match name {
+        "dev" => Some(Channel::dev()),
+        "nightly" => Some(Channel::nightly()),
+        "beta" => Some(Channel::beta()),
+        "stable" => Some(Channel::stable()),
         _ => None
     }
 }
 
-/// Checks that the running or installed `rustc` was released **on or after**
-/// some date.
-///
-/// The format of `min_date` must be YYYY-MM-DD. For instance: `2016-12-20` or
-/// `2017-01-09`.
-///
-/// If the date cannot be retrieved or parsed, or if `min_date` could not be
-/// parsed, returns `None`. Otherwise returns `true` if the installed `rustc`
-/// was release on or after `min_date` and `false` otherwise.
-pub fn is_min_date(min_date: &str) -> Option<bool> {
-    match (Date::read(), Date::parse(min_date)) {
-        (Some(rustc_date), Some(min_date)) => Some(rustc_date >= min_date),
+/// Parses whether commit metadata is known from rustc verbose version output.
+fn official_build_from_rustc_verbose_version(s: &str) -> Option<bool> {
+    match (verbose_field(s, "commit-hash"), verbose_field(s, "commit-date")) {
+        (Some("unknown"), _) | (_, Some("unknown")) => Some(false),
+        (Some(_), Some(_)) => Some(true),
         _ => None
     }
 }
 
-/// Checks that the running or installed `rustc` was released **on or before**
-/// some date.
-///
-/// The format of `max_date` must be YYYY-MM-DD. For instance: `2016-12-20` or
-/// `2017-01-09`.
-///
-/// If the date cannot be retrieved or parsed, or if `max_date` could not be
-/// parsed, returns `None`. Otherwise returns `true` if the installed `rustc`
-/// was release on or before `max_date` and `false` otherwise.
-pub fn is_max_date(max_date: &str) -> Option<bool> {
-    match (Date::read(), Date::parse(max_date)) {
-        (Some(rustc_date), Some(max_date)) => Some(rustc_date <= max_date),
-        _ => None
+/// Guesses whether rustc verbose version output is from a local development
+/// build: a `dev` channel release without commit metadata.
+fn dev_build_from_rustc_verbose_version(s: &str) -> Option<bool> {
+    let (version, _) = version_and_date_from_rustc_verbose_version(s);
+    let channel = match version.and_then(|v| Channel::parse(&v)) {
+        Some(channel) => channel,
+        None => return None
+    };
+
+    match (channel.is_dev(), official_build_from_rustc_verbose_version(s)) {
+        (false, _) => Some(false),
+        (true, Some(false)) => Some(true),
+        (true, _) => None
     }
 }
 
-/// Checks that the running or installed `rustc` was released **exactly** on
-/// some date.
-///
-/// The format of `date` must be YYYY-MM-DD. For instance: `2016-12-20` or
-/// `2017-01-09`.
-///
-/// If the date cannot be retrieved or parsed, or if `date` could not be parsed,
-/// returns `None`. Otherwise returns `true` if the installed `rustc` was
-/// release on `date` and `false` otherwise.
-pub fn is_exact_date(date: &str) -> Option<bool> {
-    match (Date::read(), Date::parse(date)) {
-        (Some(rustc_date), Some(date)) => Some(rustc_date == date),
-        _ => None
-    }
+/// Returns the `version`, `channel`, `date`, and `host` summarized from rustc
+/// verbose version output, in that order.
+fn summary_fields(s: &str) -> [(&'static str, Option<String>); 4] {
+    let (version, date) = version_and_date_from_rustc_verbose_version(s);
+    [
+        ("version", version.as_ref().and_then(|v| Version::parse(v)).map(|v| v.to_string())),
+        ("channel", version.as_ref().and_then(|v| Channel::parse(v)).map(|c| c.to_string())),
+        ("date", date.as_ref().and_then(|d| Date::parse(d)).map(|d| d.to_string())),
+        ("host", verbose_field(s, "host").map(|h| h.to_string())),
+    ]
 }
 
-/// Checks that the running or installed `rustc` is **at least** some minimum
-/// version.
-///
-/// The format of `min_version` is a semantic version: `1.3.0`, `1.15.0-beta`,
-/// `1.14.0`, `1.16.0-nightly`, etc.
-///
-/// If the version cannot be retrieved or parsed, or if `min_version` could not
-/// be parsed, returns `None`. Otherwise returns `true` if the installed `rustc`
-/// is at least `min_version` and `false` otherwise.
-pub fn is_min_version(min_version: &str) -> Option<bool> {
-    match (Version::read(), Version::parse(min_version)) {
-        (Some(rustc_ver), Some(min_ver)) => Some(rustc_ver >= min_ver),
-        _ => None
+/// Returns the `key=value` summary of rustc verbose version output.
+fn summary_from_rustc_verbose_version(s: &str) -> String {
+    let mut summary = String::new();
+    for &(key, ref value) in summary_fields(s).iter() {
+        if let Some(ref value) = *value {
+            if !summary.is_empty() {
+                summary.push(' ');
+            }
+
+            summary.push_str(&format!("{}={}", key, value));
+        }
     }
+
+    summary
 }
 
-/// Checks that the running or installed `rustc` is **at most** some maximum
-/// version.
-///
-/// The format of `max_version` is a semantic version: `1.3.0`, `1.15.0-beta`,
-/// `1.14.0`, `1.16.0-nightly`, etc.
-///
-/// If the version cannot be retrieved or parsed, or if `max_version` could not
-/// be parsed, returns `None`. Otherwise returns `true` if the installed `rustc`
-/// is at most `max_version` and `false` otherwise.
-pub fn is_max_version(max_version: &str) -> Option<bool> {
-    match (Version::read(), Version::parse(max_version)) {
-        (Some(rustc_ver), Some(max_ver)) => Some(rustc_ver <= max_ver),
-        _ => None
+/// Returns the JSON summary of rustc verbose version output, or `None` if
+/// nothing could be determined.
+fn summary_json_from_rustc_verbose_version(s: &str) -> Option<String> {
+    let fields = summary_fields(s);
+    if fields.iter().all(|field| field.1.is_none()) {
+        return None;
     }
-}
 
-/// Checks that the running or installed `rustc` is **exactly** some version.
-///
-/// The format of `version` is a semantic version: `1.3.0`, `1.15.0-beta`,
-/// `1.14.0`, `1.16.0-nightly`, etc.
-///
-/// If the version cannot be retrieved or parsed, or if `version` could not be
-/// parsed, returns `None`. Otherwise returns `true` if the installed `rustc` is
-/// exactly `version` and `false` otherwise.
-pub fn is_exact_version(version: &str) -> Option<bool> {
-    match (Version::read(), Version::parse(version)) {
-        (Some(rustc_ver), Some(version)) => Some(rustc_ver == version),
-        _ => None
+    let mut json = String::from("{");
+    for (i, &(key, ref value)) in fields.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+
+        json.push_str(&format!("\"{}\":", key));
+        match *value {
+            Some(ref value) => json_string(&mut json, value),
+            None => json.push_str("null"),
+        }
     }
-}
 
-/// Checks whether the running or installed `rustc` supports feature flags.
-///
-/// Returns true if the channel is either "nightly" or "dev".
-///
-/// **Please see the note on [feature detection](crate#feature-detection).**
-///
-/// Note that support for specific `rustc` features can be enabled or disabled
-/// via the `allow-features` compiler flag, which this function _does not_
-/// check. That is, this function _does not_ check whether a _specific_ feature
-/// is supported, but instead whether features are supported at all. To check
-/// for support for a specific feature, use [`supports_feature()`].
-///
-/// If the version could not be determined, returns `None`. Otherwise returns
-/// `true` if the running version supports feature flags and `false` otherwise.
-pub fn is_feature_flaggable() -> Option<bool> {
-    Channel::read().map(|c| c.supports_features())
+    json.push('}');
+    Some(json)
 }
 
-/// Checks whether the running or installed `rustc` supports `feature`.
-///
-/// **Please see the note on [feature detection](crate#feature-detection).**
-///
-/// Returns _true_ _iff_ [`is_feature_flaggable()`] returns `true` _and_ the
-/// feature is not disabled via exclusion in `allow-features` via `RUSTFLAGS` or
-/// `CARGO_ENCODED_RUSTFLAGS`. If the version could not be determined, returns
-/// `None`.
-///
-/// # Example
-///
-/// ```rust
-/// use version_check as rustc;
-///
-/// if let Some(true) = rustc::supports_feature("doc_cfg") {
-///    println!("cargo:rustc-cfg=has_doc_cfg");
-/// }
-/// ```
-pub fn supports_feature(feature: &str) -> Option<bool> {
-    match is_feature_flaggable() {
-        Some(true) => { /* continue */ }
-        Some(false) => return Some(false),
-        None => return None,
+/// Returns every field of rustc verbose version output, keyed by name.
+fn fields_from_rustc_verbose_version(s: &str) -> BTreeMap<String, String> {
+    let mut fields = BTreeMap::new();
+    for &(key, ref value) in summary_fields(s).iter() {
+        if let Some(ref value) = *value {
+            fields.insert(key.to_string(), value.clone());
+        }
     }
 
-    let env_flags = env::var_os("CARGO_ENCODED_RUSTFLAGS")
-        .map(|flags| (flags, '\x1f'))
-        .or_else(|| env::var_os("RUSTFLAGS").map(|flags| (flags, ' ')));
+    let verbose = [
+        ("binary", "binary"),
+        ("commit_hash", "commit-hash"),
+        ("commit_date", "commit-date"),
+        ("release", "release"),
+        ("llvm_version", "LLVM version"),
+    ];
 
-    if let Some((flags, delim)) = env_flags {
-        const ALLOW_FEATURES: &'static str = "allow-features=";
+    for &(key, field) in verbose.iter() {
+        match verbose_field(s, field) {
+            Some("unknown") | Some("") | None => continue,
+            Some(value) => fields.insert(key.to_string(), value.to_string()),
+        };
+    }
 
-        let rustflags = flags.to_string_lossy();
-        let allow_features = rustflags.split(delim)
-            .map(|flag| flag.trim_left_matches("-Z").trim())
-            .filter(|flag| flag.starts_with(ALLOW_FEATURES))
-            .map(|flag| &flag[ALLOW_FEATURES.len()..]);
+    fields
+}
 
-        if let Some(allow_features) = allow_features.last() {
-            return Some(allow_features.split(',').any(|f| f.trim() == feature));
+/// Appends `value` to `json` as a quoted, escaped JSON string.
+fn json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
         }
     }
 
-    // If there are no `RUSTFLAGS` or `CARGO_ENCODED_RUSTFLAGS` or they don't
-    // contain an `allow-features` flag, assume compiler allows all features.
-    Some(true)
+    json.push('"');
 }
 
-#[cfg(test)]
-mod tests {
-    use std::{env, fs};
+/// Returns the value of the cfg `key` in the output of `rustc --print cfg`.
+fn cfg_value<'a>(cfg: &'a str, key: &str) -> Option<&'a str> {
+    for line in cfg.lines() {
+        let mut kv = line.trim().splitn(2, '=');
+        match (kv.next(), kv.next()) {
+            (Some(k), Some(v)) if k == key => return Some(v.trim_matches('"')),
+            _ => continue
+        }
+    }
 
-    use super::version_and_date_from_rustc_version;
-    use super::version_and_date_from_rustc_verbose_version;
+    None
+}
 
-    macro_rules! check_parse {
-        (@ $f:expr, $s:expr => $v:expr, $d:expr) => ({
-            if let (Some(v), d) = $f(&$s) {
-                let e_d: Option<&str> = $d.into();
-                assert_eq!((v, d), ($v.to_string(), e_d.map(|s| s.into())));
-            } else {
-                panic!("{:?} didn't parse for version testing.", $s);
+/// Returns every value of the cfg `key` in the output of `rustc --print cfg`.
+fn cfg_values<'a>(cfg: &'a str, key: &str) -> Vec<&'a str> {
+    cfg.lines()
+        .filter_map(|line| {
+            let mut kv = line.trim().splitn(2, '=');
+            match (kv.next(), kv.next()) {
+                (Some(k), Some(v)) if k == key => Some(v.trim_matches('"')),
+                _ => None
             }
-        });
-        ($f:expr, $s:expr => $v:expr, $d:expr) => ({
-            let warn = "warning: invalid logging spec 'warning', ignoring it";
-            let warn2 = "warning: sorry, something went wrong :(sad)";
-            check_parse!(@ $f, $s => $v, $d);
-            check_parse!(@ $f, &format!("{}\n{}", warn, $s) => $v, $d);
-            check_parse!(@ $f, &format!("{}\n{}", warn2, $s) => $v, $d);
-            check_parse!(@ $f, &format!("{}\n{}\n{}", warn, warn2, $s) => $v, $d);
-            check_parse!(@ $f, &format!("{}\n{}\n{}", warn2, warn, $s) => $v, $d);
         })
-    }
+        .collect()
+}
 
-    macro_rules! check_terse_parse {
-        ($($s:expr => $v:expr, $d:expr,)+) => {$(
-            check_parse!(version_and_date_from_rustc_version, $s => $v, $d);
-        )+}
+/// Returns the path to `program` by searching `PATH` if `program` is a bare
+/// name, or `program` itself otherwise.
+fn resolve_program(program: &Path) -> Option<PathBuf> {
+    if program.components().count() > 1 {
+        return Some(program.to_path_buf());
     }
 
-    macro_rules! check_verbose_parse {
-        ($($s:expr => $v:expr, $d:expr,)+) => {$(
-            check_parse!(version_and_date_from_rustc_verbose_version, $s => $v, $d);
-        )+}
-    }
+    let path = match env::var_os("PATH") {
+        Some(path) => path,
+        None => return None
+    };
 
-    #[test]
-    fn test_version_parse() {
-        check_terse_parse! {
-            "rustc 1.18.0" => "1.18.0", None,
-            "rustc 1.8.0" => "1.8.0", None,
-            "rustc 1.20.0-nightly" => "1.20.0-nightly", None,
-            "rustc 1.20" => "1.20", None,
-            "rustc 1.3" => "1.3", None,
-            "rustc 1" => "1", None,
-            "rustc 1.5.1-beta" => "1.5.1-beta", None,
-            "rustc 1.20.0 (2017-07-09)" => "1.20.0", Some("2017-07-09"),
-            "rustc 1.20.0-dev (2017-07-09)" => "1.20.0-dev", Some("2017-07-09"),
-            "rustc 1.20.0-nightly (d84693b93 2017-07-09)" => "1.20.0-nightly", Some("2017-07-09"),
-            "rustc 1.20.0 (d84693b93 2017-07-09)" => "1.20.0", Some("2017-07-09"),
-            "rustc 1.30.0-nightly (3bc2ca7e4 2018-09-20)" => "1.30.0-nightly", Some("2018-09-20"),
-        };
+    for dir in env::split_paths(&path) {
+        for name in &[program.to_path_buf(), program.with_extension("exe")] {
+            let candidate = dir.join(name);
+            if fs::metadata(&candidate).map(|m| m.is_file()).unwrap_or(false) {
+                return Some(candidate);
+            }
+        }
     }
 
-    #[test]
-    fn test_verbose_version_parse() {
-        check_verbose_parse! {
-            "rustc 1.0.0 (a59de37e9 2015-05-13) (built 2015-05-14)\n\
-                binary: rustc\n\
-                commit-hash: a59de37e99060162a2674e3ff45409ac73595c0e\n\
-                commit-date: 2015-05-13\n\
-                build-date: 2015-05-14\n\
-                host: x86_64-unknown-linux-gnu\n\
-                release: 1.0.0" => "1.0.0", Some("2015-05-13"),
+    None
+}
 
-            "rustc 1.0.0 (a59de37e9 2015-05-13) (built 2015-05-14)\n\
-                commit-hash: a59de37e99060162a2674e3ff45409ac73595c0e\n\
-                commit-date: 2015-05-13\n\
-                build-date: 2015-05-14\n\
-                host: x86_64-unknown-linux-gnu\n\
-                release: 1.0.0" => "1.0.0", Some("2015-05-13"),
+/// Returns `true` if `program` resolves to a `rustup` proxy: a binary that
+/// lives alongside a `rustup` binary.
+fn is_rustup_proxy(program: &Path) -> bool {
+    let dir = match resolve_program(program) {
+        Some(path) => match path.parent() {
+            Some(dir) => dir.to_path_buf(),
+            None => return false
+        },
+        None => return false
+    };
 
-            "rustc 1.50.0 (cb75ad5db 2021-02-10)\n\
-                binary: rustc\n\
-                commit-hash: cb75ad5db02783e8b0222fee363c5f63f7e2cf5b\n\
-                commit-date: 2021-02-10\n\
+    ["rustup", "rustup.exe"].iter()
+        .any(|rustup| fs::metadata(dir.join(rustup)).map(|m| m.is_file()).unwrap_or(false))
+}
+
+/// Heuristically splits a `rustup` toolchain name, as in `RUSTUP_TOOLCHAIN`,
+/// into the version, channel, and date it encodes, if any.
+///
+/// A name starting with a version, like `1.70.0-x86_64-unknown-linux-gnu` or
+/// `1.70`, is a stable release of that version without a date. A name starting
+/// with a channel, like `nightly-2023-06-01-x86_64-unknown-linux-gnu` or
+/// `beta`, has that channel, a date if one follows the channel, and no
+/// version. Any other name, such as that of a custom toolchain, encodes
+/// nothing.
+fn toolchain_name_parts(name: &str) -> (Option<Version>, Option<Channel>, Option<Date>) {
+    let mut parts = name.trim().split('-');
+    let first = parts.next().unwrap_or("");
+    match first {
+        "stable" | "beta" | "nightly" => {
+            let date: Vec<_> = parts.take(3).collect();
+            let date = match date.len() {
+                3 => Date::parse(&format!("{}-{}-{}", date[0], date[1], date[2])),
+                _ => None
+            };
+
+            (None, channel_from_name(first), date)
+        }
+        _ if first.starts_with(|c: char| c.is_digit(10)) => match Version::parse(first) {
+            Some(version) => (Some(version), Some(Channel::stable()), None),
+            None => (None, None, None)
+        },
+        _ => (None, None, None)
+    }
+}
+
+/// Returns the value of the testing aid `var`, one of the
+/// `VERSION_CHECK_FAKE_*` environment variables, if it is set and
+/// `VERSION_CHECK_DISABLE` is not.
+fn fake_value(var: &str) -> Option<String> {
+    match is_disabled() {
+        true => None,
+        false => env::var_os(var).map(|value| value.to_string_lossy().into_owned())
+    }
+}
+
+/// Returns `true` if `VERSION_CHECK_DISABLE` is set to a non-empty value, in
+/// which case `rustc` must never be invoked.
+fn is_disabled() -> bool {
+    env::var_os("VERSION_CHECK_DISABLE").map_or(false, |value| &*value != OsStr::new(""))
+}
+
+/// Returns the path to the installed or running `rustc`: `RUSTC`, if it is
+/// set, or `rustc` otherwise.
+///
+/// Cargo sets `RUSTC` for build scripts to the compiler it builds with, so it
+/// always takes precedence over a `rustc` found in `PATH`.
+fn rustc_path() -> PathBuf {
+    rustc_path_from(env::var_os("RUSTC"))
+}
+
+/// Returns the path to `rustc` given the value of `RUSTC`: the value, if it
+/// is set and non-empty, or `rustc` otherwise.
+fn rustc_path_from(rustc: Option<OsString>) -> PathBuf {
+    match rustc {
+        Some(ref rustc) if rustc != "" => PathBuf::from(rustc),
+        _ => PathBuf::from("rustc")
+    }
+}
+
+/// Returns a `Command` that invokes the `rustc` at `path` via `wrapper`, if it
+/// is `Some` and non-empty, passing `path` as the wrapper's first argument.
+/// Otherwise invokes `path` directly. Neither is ever split or concatenated.
+fn wrapped_rustc(wrapper: Option<OsString>, path: &Path) -> Command {
+    let (program, args) = wrapped_program(wrapper, path);
+    let mut cmd = Command::new(program);
+    cmd.args(&args);
+    cmd
+}
+
+/// Returns the program and arguments of the `Command` returned by
+/// `wrapped_rustc()`.
+fn wrapped_program(wrapper: Option<OsString>, path: &Path) -> (OsString, Vec<OsString>) {
+    match wrapper {
+        Some(ref wrapper) if wrapper != "" => (wrapper.clone(), vec![path.as_os_str().to_owned()]),
+        _ => (path.as_os_str().to_owned(), vec![])
+    }
+}
+
+/// Splits a command line into whitespace-separated tokens. A token may be
+/// quoted with `'` or `"` to include whitespace; there are no escapes, and an
+/// unterminated quote extends to the end of the line.
+fn split_command_line(line: &str) -> Vec<String> {
+    let (mut tokens, mut token, mut in_token) = (vec![], String::new(), false);
+    let mut quote = None;
+    for c in line.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => token.push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                in_token = true;
+            }
+            None if c.is_whitespace() => if in_token {
+                tokens.push(mem::replace(&mut token, String::new()));
+                in_token = false;
+            },
+            None => {
+                token.push(c);
+                in_token = true;
+            }
+        }
+    }
+
+    if in_token {
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+/// Splits `path` into a program and leading arguments if it contains
+/// whitespace and does not name an existing file, as in a `RUSTC` of `ccache
+/// rustc`. Otherwise, `path` is the program, and there are no arguments.
+fn split_program(path: &Path) -> (PathBuf, Vec<String>) {
+    let line = match path.to_str() {
+        Some(line) if line.contains(|c: char| c.is_whitespace()) => line,
+        _ => return (path.to_path_buf(), vec![])
+    };
+
+    if fs::metadata(path).is_ok() {
+        return (path.to_path_buf(), vec![]);
+    }
+
+    let mut tokens = split_command_line(line).into_iter();
+    match tokens.next() {
+        Some(program) => (PathBuf::from(program), tokens.collect()),
+        None => (path.to_path_buf(), vec![])
+    }
+}
+
+/// Returns a `Command` that invokes the `rustc` at `path`.
+///
+/// If `path` is a command line, such as `ccache rustc`, it is split as
+/// described in `split_program()`.
+///
+/// If `RUSTC_WRAPPER` is set, as Cargo does for build scripts when a wrapper
+/// like `sccache` is configured, the wrapper is invoked with `path` as its
+/// first argument. If `RUSTUP_TOOLCHAIN` is set and `rustc` is a `rustup`
+/// proxy, the toolchain is passed explicitly as `+toolchain` so that the
+/// toolchain queried matches the one used by the build.
+fn rustc_at(path: &Path) -> Command {
+    let (program, args) = split_program(path);
+    let mut cmd = wrapped_rustc(env::var_os("RUSTC_WRAPPER"), &program);
+    if let Ok(toolchain) = env::var("RUSTUP_TOOLCHAIN") {
+        // A toolchain can also be a path, which can't be passed via `+`.
+        let is_name = !toolchain.is_empty() && !toolchain.contains(|c| c == '/' || c == '\\');
+        if is_name && is_rustup_proxy(&program) {
+            cmd.arg(format!("+{}", toolchain));
+        }
+    }
+
+    cmd.args(&args);
+    cmd
+}
+
+/// Returns a `Command` that invokes the installed or running `rustc`.
+fn rustc() -> Command {
+    rustc_at(&rustc_path())
+}
+
+/// Runs the `rustc` at `path` with `args`, or calls the hook set with
+/// [`set_rustc_hook()`] instead if there is one, and returns whether it
+/// succeeded and its standard output.
+fn rustc_output(path: &Path, args: &[&str]) -> io::Result<(bool, String)> {
+    rustc_output_with(path, args, hook::call)
+}
+
+/// Like `rustc_output()` but calls `call_hook` in place of the hook set with
+/// [`set_rustc_hook()`]. `call_hook` returns `None` if there is no hook and the
+/// result of the hook otherwise.
+fn rustc_output_with<F: FnOnce(&Path, &[&str]) -> Option<Option<String>>>(
+    path: &Path,
+    args: &[&str],
+    call_hook: F,
+) -> io::Result<(bool, String)> {
+    match call_hook(path, args) {
+        Some(Some(stdout)) => return Ok((true, stdout)),
+        Some(None) => return Err(io::Error::new(io::ErrorKind::Other, "rustc hook failed")),
+        None => {}
+    }
+
+    record_spawn();
+    let output = try!(rustc_at(path).args(args).output());
+    Ok((output.status.success(), String::from_utf8_lossy(&output.stdout).into_owned()))
+}
+
+/// The number of times `rustc` has been invoked, as reported by
+/// [`spawn_count()`].
+#[cfg(feature = "metrics")]
+static SPAWN_COUNT: AtomicUsize = ATOMIC_USIZE_INIT;
+
+/// Records that `rustc` is about to be invoked.
+#[cfg(feature = "metrics")]
+fn record_spawn() {
+    SPAWN_COUNT.fetch_add(1, atomic::Ordering::SeqCst);
+}
+
+/// Records nothing: the `metrics` feature is disabled.
+#[cfg(not(feature = "metrics"))]
+fn record_spawn() {}
+
+/// Runs `rustc --print {what}`, passing `--target {target}` if `target` is
+/// `Some`, and returns its output if it succeeds.
+fn rustc_print(what: &str, target: Option<&str>) -> Option<String> {
+    rustc_print_at(&rustc_path(), what, target)
+}
+
+/// Like `rustc_print()` but for the `rustc` at `path`.
+fn rustc_print_at(path: &Path, what: &str, target: Option<&str>) -> Option<String> {
+    if is_disabled() {
+        return None;
+    }
+
+    match rustc_output(path, &print_args(what, target)) {
+        Ok((true, stdout)) => Some(stdout),
+        _ => None
+    }
+}
+
+/// Returns the arguments to `rustc` for `rustc_print()`.
+fn print_args<'a>(what: &'a str, target: Option<&'a str>) -> Vec<&'a str> {
+    let mut args = vec!["--print", what];
+    if let Some(target) = target {
+        args.push("--target");
+        args.push(target);
+    }
+
+    args
+}
+
+/// Runs `rustc --print {whats[0]} --print {whats[1]} ...` for the `rustc` at
+/// `path` and returns the output of each request, in order, if it succeeds.
+///
+/// Every request but the last must print exactly one line, which is how the
+/// combined output is split. If the combined invocation fails, as it may on
+/// compilers that reject repeated `--print` flags, each request is instead
+/// run separately.
+fn rustc_print_all_at(path: &Path, whats: &[&str]) -> Vec<Option<String>> {
+    let separately = || whats.iter().map(|what| rustc_print_at(path, what, None)).collect();
+    if is_disabled() || whats.len() < 2 {
+        return separately();
+    }
+
+    let mut args = vec![];
+    for what in whats {
+        args.push("--print");
+        args.push(what);
+    }
+
+    let output = match rustc_output(path, &args) {
+        Ok((true, stdout)) => stdout,
+        _ => return separately()
+    };
+
+    let outputs: Vec<_> = output.splitn(whats.len(), '\n')
+        .map(|s| Some(s.to_string()))
+        .collect();
+
+    match outputs.len() == whats.len() {
+        true => outputs,
+        false => separately()
+    }
+}
+
+/// Returns the value of the cfg `key` for `target` or the host if `None`.
+/// `cargo_var` is the `CARGO_CFG_*` environment variable corresponding to `key`.
+fn target_cfg_value(target: Option<&str>, key: &str, cargo_var: &str) -> Option<String> {
+    // In build scripts, Cargo sets `CARGO_CFG_*` for `TARGET`: skip the spawn.
+    if let (Some(target), Ok(cargo_target)) = (target, env::var("TARGET")) {
+        if target == cargo_target {
+            if let Ok(value) = env::var(cargo_var) {
+                return Some(value);
+            }
+        }
+    }
+
+    print_cfg(target).and_then(|cfg| cfg_value(&cfg, key).map(|v| v.to_string()))
+}
+
+/// Returns the `rustc --verbose --version`-style output corresponding to the
+/// value of `VERSION_CHECK_OVERRIDE`: either such output itself or a version.
+fn override_verbose_version(value: &str) -> Result<String, RustcError> {
+    let value = value.trim();
+    if value.lines().any(|line| line.trim().starts_with("rustc ")) {
+        return Ok(value.to_string());
+    }
+
+    match Version::parse(value) {
+        Some(_) => Ok(format!("rustc {}\nrelease: {}", value, value)),
+        None => Err(RustcError::InvalidVersion(value.to_string()))
+    }
+}
+
+/// Returns the output of `rustc --verbose --version` or the reason `rustc`
+/// could not be read. If `VERSION_CHECK_OVERRIDE` is set, `rustc` is not
+/// invoked and its value is used instead. If `VERSION_CHECK_DISABLE` is set,
+/// nothing is read at all.
+fn rustc_verbose_version() -> Result<String, RustcError> {
+    if is_disabled() {
+        return Err(RustcError::Disabled);
+    }
+
+    if let Ok(value) = env::var("VERSION_CHECK_OVERRIDE") {
+        return override_verbose_version(&value);
+    }
+
+    cache::verbose_version(&rustc_path())
+}
+
+/// Returns the output of `rustc --verbose --version` for the `rustc` at
+/// `path` or the reason it could not be read.
+fn rustc_verbose_version_at(path: &Path) -> Result<String, RustcError> {
+    if is_disabled() {
+        return Err(RustcError::Disabled);
+    }
+
+    // Only ASCII fields are parsed, so stray invalid bytes, such as those in
+    // localized warnings, are replaced instead of failing entirely.
+    match rustc_output(path, &["--verbose", "--version"]) {
+        Ok((_, stdout)) => Ok(stdout),
+        Err(e) => Err(RustcError::Spawn(e.kind()))
+    }
+}
+
+/// Returns (version, date) as available from `rustc --version` or the reason
+/// `rustc` could not be read.
+fn read_version_and_date() -> Result<(Option<String>, Option<String>), RustcError> {
+    rustc_verbose_version().map(|s| version_and_date_from_rustc_verbose_version(&s))
+}
+
+/// Returns (version, date) as available from `rustc --verbose --version` for
+/// the `rustc` at `path`.
+fn get_version_and_date_at(path: &Path) -> Option<(Option<String>, Option<String>)> {
+    cache::verbose_version(path).ok().map(|s| version_and_date_from_rustc_verbose_version(&s))
+}
+
+/// Returns (version, date) as available from `rustc --version`.
+fn get_version_and_date() -> Option<(Option<String>, Option<String>)> {
+    read_version_and_date().ok()
+}
+
+/// Reads the triple of [`Version`], [`Channel`], and [`Date`] of the installed
+/// or running `rustc`.
+///
+/// If any attribute cannot be determined (see the [top-level
+/// documentation](crate)), returns `None`.
+///
+/// If `rustc --verbose --version` reports a `binary` other than `rustc`,
+/// likely due to a misconfigured wrapper, the output is not trusted and this
+/// function returns `None`. See [`binary_name()`].
+///
+/// To obtain only one of three attributes, use [`Version::read()`],
+/// [`Channel::read()`], or [`Date::read()`].
+pub fn triple() -> Option<(Version, Channel, Date)> {
+    rustc_verbose_version().ok().and_then(|output| triple_from_rustc_verbose_version(&output))
+}
+
+/// Parses the triple from rustc verbose version output `output`, or returns
+/// `None` if the output is untrusted or any attribute is missing.
+fn triple_from_rustc_verbose_version(output: &str) -> Option<(Version, Channel, Date)> {
+    if !is_trusted_binary(output) {
+        return None;
+    }
+
+    let (version_str, date_str) = match version_and_date_from_rustc_verbose_version(output) {
+        (Some(version), Some(date)) => (version, date),
+        _ => return None
+    };
+
+    // Can't use `?` or `try!` for `Option` in 1.0.0.
+    match Version::parse(&version_str) {
+        Some(version) => match Channel::parse(&version_str) {
+            Some(channel) => match Date::parse(&date_str) {
+                Some(date) => Some((version, channel, date)),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None
+    }
+}
+
+/// Returns the raw pre-release tag of the version reported by the running or
+/// installed `rustc`, such as `nightly`, `beta.2`, or an unrecognized tag.
+///
+/// [`Channel::read()`] returns `None` when the tag is not one of `dev`,
+/// `nightly`, or `beta`, as is the case for some distribution builds. The
+/// version itself remains available via [`Version::read()`] or
+/// [`triple_partial()`], which reads such a version as a release. This exposes
+/// the unrecognized tag so that callers can decide how to treat it, for
+/// instance by falling back with [`Channel::read_or()`].
+///
+/// If `rustc` cannot be read or its version has no pre-release tag, as for
+/// stable releases, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check::{self as rustc, Channel};
+///
+/// if Channel::read().is_none() {
+///     if let Some(tag) = rustc::raw_channel() {
+///         println!("cargo:warning=unrecognized rustc channel `{}`", tag);
+///     }
+/// }
+/// ```
+pub fn raw_channel() -> Option<String> {
+    match get_version_and_date() {
+        Some((Some(version), _)) => pre_release_tag(&version).map(|tag| tag.to_string()),
+        _ => None
+    }
+}
+
+/// Returns the text following the first `-` in the version string `version`,
+/// if there is any.
+fn pre_release_tag(version: &str) -> Option<&str> {
+    let version = version.trim();
+    let version = version.find(|c: char| c.is_whitespace()).map_or(version, |i| &version[..i]);
+    match version.splitn(2, '-').nth(1) {
+        Some("") | None => None,
+        Some(tag) => Some(tag)
+    }
+}
+
+/// Reads the [`triple()`] of the `rustc` found in `PATH`, ignoring `RUSTC`.
+///
+/// This is the ambient toolchain: the compiler that would run if `rustc` were
+/// invoked from a shell, as selected by `rustup` overrides and
+/// `RUSTUP_TOOLCHAIN` when `rustc` is a `rustup` proxy. In contrast,
+/// [`target_toolchain()`] reads the compiler that Cargo designates via
+/// `RUSTC`, which is also the compiler [`triple()`] and every other function
+/// in this crate read.
+///
+/// Cargo compiles build scripts, proc-macros, and the crate being built all
+/// with the `RUSTC` it sets, so in a Cargo build the two usually agree. They
+/// differ when `RUSTC` points to a compiler other than the first `rustc` in
+/// `PATH`, as with a `RUSTC` override in the environment or in
+/// `.cargo/config.toml`, or when a wrapper changes which toolchain a `rustup`
+/// proxy selects.
+///
+/// `VERSION_CHECK_OVERRIDE` is not consulted, as with
+/// [`Version::read_for_path()`]. If the triple cannot be determined, returns
+/// `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if rustc::host_toolchain() != rustc::target_toolchain() {
+///     println!("cargo:warning=`rustc` in PATH differs from RUSTC");
+/// }
+/// ```
+pub fn host_toolchain() -> Option<(Version, Channel, Date)> {
+    cache::verbose_version(Path::new("rustc")).ok()
+        .and_then(|output| triple_from_rustc_verbose_version(&output))
+}
+
+/// Reads the [`triple()`] of the `rustc` named by the `RUSTC` environment
+/// variable, which Cargo sets for build scripts to the compiler it builds the
+/// crate with.
+///
+/// Unlike [`triple()`], this does not fall back to the `rustc` in `PATH` when
+/// `RUSTC` is unset or empty, as when not running in a build script; use
+/// [`host_toolchain()`] for that compiler. See [`host_toolchain()`] for when
+/// the two differ.
+///
+/// `VERSION_CHECK_OVERRIDE` is not consulted, as with
+/// [`Version::read_for_path()`]. If `RUSTC` is unset or empty, or the triple
+/// cannot be determined, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some((version, channel, _)) = rustc::target_toolchain() {
+///     println!("cargo:warning=building with rustc {} ({})", version, channel);
+/// }
+/// ```
+pub fn target_toolchain() -> Option<(Version, Channel, Date)> {
+    match env::var_os("RUSTC") {
+        Some(ref rustc) if rustc != "" => cache::verbose_version(Path::new(rustc)).ok()
+            .and_then(|output| triple_from_rustc_verbose_version(&output)),
+        _ => None
+    }
+}
+
+/// Reads whichever of the [`Version`], [`Channel`], and [`Date`] of the
+/// installed or running `rustc` can be determined.
+///
+/// Unlike [`triple()`], which returns `None` unless all three attributes are
+/// known, each attribute is returned independently, as `None` if it cannot be
+/// determined. This is useful for compilers that report their release date as
+/// `unknown`, such as many distribution builds, whose version and channel
+/// remain usable. To match the result against constraints that ignore unknown
+/// attributes, see [`PartialTriple`].
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// let (version, channel, date) = rustc::triple_partial();
+/// if version.map_or(false, |v| v.at_least("1.56.0")) {
+///     println!("cargo:rustc-cfg=edition_2021");
+/// }
+///
+/// match (channel, date) {
+///     (Some(c), Some(d)) => format!("{} from {}", c, d),
+///     (Some(c), None) => format!("{} from an unknown date", c),
+///     _ => format!("unknown channel"),
+/// };
+/// ```
+pub fn triple_partial() -> (Option<Version>, Option<Channel>, Option<Date>) {
+    match get_version_and_date() {
+        Some((version, date)) => {
+            let channel = version.as_ref().and_then(|v| Channel::parse(v));
+            let version = version.and_then(|v| Version::parse(&v));
+            (version, channel, date.and_then(|d| Date::parse(&d)))
+        }
+        None => (None, None, None)
+    }
+}
+
+/// Reads the [`triple()`] of the running or installed `rustc`, bypassing and
+/// refreshing the cache.
+///
+/// Normally, `rustc` is invoked at most once per process, and the result is
+/// cached. The cache is keyed by the path to `rustc`, so changing `RUSTC` to
+/// point to a different compiler already results in a fresh read. This
+/// function is an escape hatch for the rare case where the compiler at the
+/// same path has changed mid-process, as when switching toolchains: `rustc`
+/// is invoked again and its result replaces the cached one, so subsequent
+/// reads, including [`triple()`] and [`Version::read()`], observe it. Normal
+/// code should not need this function.
+///
+/// As with [`triple()`], `VERSION_CHECK_OVERRIDE` and `VERSION_CHECK_DISABLE`
+/// are respected, and a hook set with [`set_rustc_hook()`] is consulted.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some((version, channel, date)) = rustc::read_uncached() {
+///     println!("rustc {} ({}) from {}", version, channel, date);
+/// }
+/// ```
+pub fn read_uncached() -> Option<(Version, Channel, Date)> {
+    if !is_disabled() && env::var("VERSION_CHECK_OVERRIDE").is_err() {
+        let _ = cache::refresh(&rustc_path());
+    }
+
+    triple()
+}
+
+/// Reads the [`triple()`] of the running or installed `rustc` on a new thread.
+///
+/// This is a thin wrapper around [`triple()`] for tools that want to overlap
+/// toolchain detection with other work; it requires no async runtime. The
+/// result is stored in the same process-wide cache as a synchronous read, so
+/// once the thread completes, later calls to [`triple()`], [`Version::read()`],
+/// and similar functions do not invoke `rustc` again. The returned handle
+/// yields the result when joined.
+///
+/// There is no `Triple` type; the result is a `(Version, Channel, Date)`
+/// tuple, as from [`triple()`].
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// let handle = rustc::read_async();
+/// // ... other work ...
+/// if let Ok(Some((version, channel, date))) = handle.join() {
+///     println!("rustc {} ({}) from {}", version, channel, date);
+/// }
+/// ```
+pub fn read_async() -> thread::JoinHandle<Option<(Version, Channel, Date)>> {
+    thread::spawn(triple)
+}
+
+/// Reads the [`triple()`] of the running or installed `rustc` once and returns
+/// the result of applying the predicate `f` to it.
+///
+/// This allows arbitrary combinations of version, channel, and date checks
+/// to be expressed in one call. If the triple cannot be read, returns `None`
+/// without calling `f`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// let recent_nightly = rustc::matches(|version, channel, date| {
+///     version.at_least("1.70.0") && channel.is_nightly() && date.at_least("2023-05-01")
+/// });
+///
+/// if let Some(true) = recent_nightly {
+///     println!("cargo:rustc-cfg=recent_nightly");
+/// }
+/// ```
+pub fn matches<F: FnOnce(&Version, &Channel, &Date) -> bool>(f: F) -> Option<bool> {
+    triple().map(|(version, channel, date)| f(&version, &channel, &date))
+}
+
+/// Compares two toolchains, each a [`triple()`], by which is effectively
+/// newer.
+///
+/// The triples are compared in three steps, stopping at the first that is not
+/// equal:
+///
+///   1. The versions, via `Ord`. Pre-release tags are considered, as in
+///      [`compare()`]: `1.70.0-nightly` < `1.70.0-beta` < `1.70.0` <
+///      `1.71.0-nightly`.
+///   2. The release dates: a later date is newer.
+///   3. The channels, by maturity, as by `Ord` on [`Channel`]: `dev` <
+///      `nightly` < `beta` < `stable`.
+///
+/// Sorting with this comparison orders toolchains from oldest to newest.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check::{self as rustc, Version, Channel, Date};
+///
+/// let triple = |v, d| {
+///     let version = Version::parse(v).unwrap();
+///     (version, version.channel(), Date::parse(d).unwrap())
+/// };
+///
+/// let mut toolchains = vec![
+///     triple("1.70.0", "2023-05-31"),
+///     triple("1.71.0-nightly", "2023-05-30"),
+///     triple("1.70.0-nightly", "2023-04-20"),
+///     triple("1.70.0-nightly", "2023-04-10"),
+/// ];
+///
+/// toolchains.sort_by(rustc::cmp_triples);
+/// assert_eq!(toolchains[0], triple("1.70.0-nightly", "2023-04-10"));
+/// assert_eq!(toolchains[3], triple("1.71.0-nightly", "2023-05-30"));
+/// ```
+pub fn cmp_triples(a: &(Version, Channel, Date), b: &(Version, Channel, Date)) -> Ordering {
+    match a.0.cmp(&b.0) {
+        Ordering::Equal => match a.2.cmp(&b.2) {
+            Ordering::Equal => a.1.cmp(&b.1),
+            ordering => ordering
+        },
+        ordering => ordering
+    }
+}
+
+/// Detects the version of the running or installed `rustc` now, caching the
+/// result for the remainder of the process.
+///
+/// `rustc` is invoked at most once per process to read its version, release
+/// channel, and release date; every function that does so, including
+/// [`triple()`] and [`Rustc::from_env()`], shares the cached result. Calling
+/// `preload()` early in a build script thus controls when that invocation
+/// happens and fails fast if it won't succeed. Functions that compile probes
+/// or query `rustc --print`, such as [`supports_all_features()`], still invoke
+/// `rustc` each time.
+///
+/// Returns `None` if the version cannot be determined (see the [top-level
+/// documentation](crate)).
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if rustc::preload().is_none() {
+///     println!("cargo:warning=failed to detect the rustc version");
+/// }
+///
+/// // Does not invoke `rustc` again.
+/// let _ = rustc::is_min_version("1.31.0");
+/// ```
+pub fn preload() -> Option<()> {
+    get_version_and_date()
+        .and_then(|(version, _)| version)
+        .and_then(|version| Version::parse(&version))
+        .map(|_| ())
+}
+
+/// Reads the name of the binary reported by the running or installed `rustc`
+/// in the `binary` field of `rustc --verbose --version`, normally `rustc`.
+///
+/// A different name suggests that a wrapper forwarded the version query to
+/// some other program, in which case the reported version should not be
+/// trusted. [`triple()`] returns `None` in that case.
+///
+/// If `rustc` cannot be read or does not report a `binary` field, as is the
+/// case for very old compilers, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// match rustc::binary_name() {
+///     Some(ref name) if name != "rustc" => println!("cargo:warning=unexpected binary: {}", name),
+///     _ => { /* `rustc` or unknown */ }
+/// }
+/// ```
+pub fn binary_name() -> Option<String> {
+    rustc_verbose_version().ok()
+        .and_then(|s| verbose_field(&s, "binary").map(|name| name.to_string()))
+}
+
+/// Returns the major version of the LLVM backend of the running or installed
+/// `rustc`, as reported in the `LLVM version` line of `rustc --verbose
+/// --version`.
+///
+/// The major version is the leading integer of the reported version, so both
+/// `LLVM version: 12.0.0` and `LLVM version: 7.0` are handled. If the line is
+/// absent, as it is for compilers built without LLVM or for very old
+/// compilers, or its value does not begin with a number, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(major) = rustc::llvm_major() {
+///     println!("cargo:warning=rustc uses LLVM {}", major);
+/// }
+/// ```
+pub fn llvm_major() -> Option<u64> {
+    rustc_verbose_version().ok().and_then(|s| llvm_major_from_rustc_verbose_version(&s))
+}
+
+/// Checks whether the LLVM backend of the running or installed `rustc` has a
+/// major version of at least `major`, as reported by [`llvm_major()`].
+///
+/// If the LLVM version cannot be determined, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::llvm_at_least(16) {
+///     println!("cargo:rustc-cfg=llvm_16");
+/// }
+/// ```
+pub fn llvm_at_least(major: u64) -> Option<bool> {
+    llvm_major().map(|llvm_major| llvm_major >= major)
+}
+
+/// Parses the LLVM major version from rustc verbose version output.
+fn llvm_major_from_rustc_verbose_version(s: &str) -> Option<u64> {
+    verbose_field(s, "LLVM version").and_then(|version| {
+        let end = version.find(|c: char| !c.is_digit(10)).unwrap_or(version.len());
+        version[..end].parse().ok()
+    })
+}
+
+/// Returns `false` if the `rustc --verbose --version` output `s` reports a
+/// `binary` other than `rustc` and `true` otherwise.
+fn is_trusted_binary(s: &str) -> bool {
+    verbose_field(s, "binary").map_or(true, |name| name == "rustc")
+}
+
+/// Checks whether the running or installed `rustc` is managed by `rustup`.
+///
+/// The `rustc` is resolved as it would be invoked, via `RUSTC` or `PATH`. It
+/// is considered managed by `rustup` if it is a `rustup` proxy, such as those
+/// in `~/.cargo/bin`, or if it resides within a `.rustup` directory, as
+/// toolchain binaries do. This is informational, intended to help diagnose
+/// unexpected toolchain selection.
+///
+/// If the `rustc` cannot be resolved to an existing file, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// match rustc::is_rustup_managed() {
+///     Some(true) => "rustc is managed by rustup",
+///     Some(false) => "rustc is a system installation",
+///     None => "rustc could not be found",
+/// };
+/// ```
+pub fn is_rustup_managed() -> Option<bool> {
+    is_rustup_managed_at(&rustc_path())
+}
+
+/// Like `is_rustup_managed()` but for the `rustc` at `path`.
+fn is_rustup_managed_at(path: &Path) -> Option<bool> {
+    let path = match resolve_program(path) {
+        Some(path) => path,
+        None => return None
+    };
+
+    if !fs::metadata(&path).map(|m| m.is_file()).unwrap_or(false) {
+        return None;
+    }
+
+    let in_rustup_dir = path.components().any(|c| c.as_os_str() == ".rustup");
+    Some(in_rustup_dir || is_rustup_proxy(&path))
+}
+
+/// Runs `rustup` with `args` and returns its standard output if it succeeds.
+fn rustup_output(args: &[&str]) -> Option<String> {
+    if is_disabled() {
+        return None;
+    }
+
+    Command::new("rustup").args(args).output().ok()
+        .and_then(|output| match output.status.success() {
+            true => Some(String::from_utf8_lossy(&output.stdout).into_owned()),
+            false => None
+        })
+}
+
+/// Parses the toolchain names from the output of `rustup toolchain list`,
+/// dropping annotations such as `(active, default)`.
+fn toolchains_from_rustup_list(s: &str) -> Vec<&str> {
+    s.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && *line != "no installed toolchains")
+        .filter_map(|line| line.split(|c: char| c.is_whitespace()).next())
+        .collect()
+}
+
+/// Reads the versions of all toolchains installed via `rustup`.
+///
+/// This is `rustup`-specific: toolchains are listed with `rustup toolchain
+/// list`, and each toolchain's `rustc`, as located by `rustup which`, is read
+/// as for any other `rustc`, once per process. Returns the name of each
+/// toolchain, as in `stable-x86_64-unknown-linux-gnu`, paired with its
+/// version, in the order listed by `rustup`. Toolchains whose version cannot
+/// be determined are omitted. This is intended for diagnostics; to query the
+/// toolchain used by the build, use [`Version::read()`].
+///
+/// If `rustup` is not available or fails, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(toolchains) = rustc::installed_toolchains() {
+///     for (name, version) in toolchains {
+///         println!("{}: {}", name, version);
+///     }
+/// }
+/// ```
+pub fn installed_toolchains() -> Option<Vec<(String, Version)>> {
+    let list = match rustup_output(&["toolchain", "list"]) {
+        Some(list) => list,
+        None => return None
+    };
+
+    let mut toolchains = vec![];
+    for name in toolchains_from_rustup_list(&list) {
+        let path = match rustup_output(&["which", "--toolchain", name, "rustc"]) {
+            Some(path) => PathBuf::from(path.trim()),
+            None => continue
+        };
+
+        let version = cache::verbose_version(&path).ok()
+            .and_then(|s| version_and_date_from_rustc_verbose_version(&s).0)
+            .and_then(|v| Version::parse(&v));
+
+        if let Some(version) = version {
+            toolchains.push((name.to_string(), version));
+        }
+    }
+
+    Some(toolchains)
+}
+
+/// Sets a hook that is called instead of spawning `rustc` to obtain the output
+/// of `rustc --verbose --version` and `rustc --print`.
+///
+/// This allows the crate to be used where spawning processes directly is
+/// forbidden, such as in sandboxes that route all execution through a custom
+/// runner. The hook is called with the path to `rustc`, as determined by
+/// `RUSTC`, followed by the arguments, as in `["rustc", "--verbose",
+/// "--version"]`. It should return the standard output of the invocation if
+/// it succeeds and `None` otherwise. `RUSTC_WRAPPER` and `RUSTUP_TOOLCHAIN`
+/// are not applied; the hook runs `rustc` however it sees fit. Feature probes,
+/// which compile code, are unaffected.
+///
+/// The hook replaces any previously set hook and remains in effect until
+/// [`clear_rustc_hook()`] is called. Because version information is cached,
+/// the hook should be set before the first query. The hook must not call
+/// into this crate.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// rustc::set_rustc_hook(|args| match args.get(1) {
+///     Some(&"--verbose") => Some("rustc 1.70.0 (90c541806 2023-05-31)".into()),
+///     _ => None
+/// });
+///
+/// rustc::clear_rustc_hook();
+/// ```
+pub fn set_rustc_hook<F: FnMut(&[&str]) -> Option<String> + Send + 'static>(hook: F) {
+    hook::set(Box::new(hook))
+}
+
+/// Removes the hook set with [`set_rustc_hook()`], if any, so that `rustc` is
+/// spawned directly again.
+pub fn clear_rustc_hook() {
+    hook::clear()
+}
+
+/// Sets the cached version information of the running or installed `rustc`
+/// to `triple`, as if it had been read, for testing purposes. Only available
+/// with the `testing` feature enabled.
+///
+/// With `Some((version, channel, date))`, subsequent reads report `version`
+/// with `channel`'s pre-release tag and `date` as the release date. With
+/// `None`, subsequent reads behave as if `rustc` could not be found. In both
+/// cases, `rustc` is not invoked to read its version until [`clear_cache()`]
+/// is called. `VERSION_CHECK_OVERRIDE` and `VERSION_CHECK_DISABLE` continue to
+/// take precedence.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "testing")] {
+/// use version_check as rustc;
+/// use version_check::{Version, Channel, Date};
+///
+/// let version = Version::parse("1.70.0").unwrap();
+/// let date = Date::parse("2023-05-31").unwrap();
+/// rustc::set_cached_triple(Some((version, Channel::nightly(), date)));
+/// assert_eq!(rustc::is_feature_flaggable(), Some(true));
+///
+/// rustc::set_cached_triple(None);
+/// assert_eq!(rustc::triple(), None);
+///
+/// rustc::clear_cache();
+/// # }
+/// ```
+#[cfg(feature = "testing")]
+pub fn set_cached_triple(triple: Option<(Version, Channel, Date)>) {
+    let result = match triple {
+        Some((version, channel, date)) => {
+            let version = match channel.pre_release_tag() {
+                _ if version.channel() == channel => version.to_string(),
+                Some(tag) => format!("{}-{}", version.stripped(), tag),
+                None => version.stripped().to_string(),
+            };
+
+            Ok(format!("rustc {} ({})\nrelease: {}\ncommit-date: {}", version, date, version, date))
+        }
+        None => Err(RustcError::Spawn(io::ErrorKind::NotFound))
+    };
+
+    cache::set(&rustc_path(), result);
+}
+
+/// Clears the cached version information of the running or installed `rustc`,
+/// whether read or set via [`set_cached_triple()`], so that the next read
+/// invokes `rustc` again. Only available with the `testing` feature enabled.
+#[cfg(feature = "testing")]
+pub fn clear_cache() {
+    cache::clear();
+}
+
+/// Returns the number of times `rustc` has been invoked by this crate in the
+/// current process. Only available with the `metrics` feature enabled.
+///
+/// Every process spawned to run `rustc` is counted, including feature probes,
+/// but calls answered by the cache, by `VERSION_CHECK_OVERRIDE`, or by a hook
+/// set with [`set_rustc_hook()`] are not. This makes it possible to verify that
+/// caching is effective in build scripts that query `rustc` heavily.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "metrics")] {
+/// use version_check as rustc;
+///
+/// rustc::preload();
+/// let spawned = rustc::spawn_count();
+/// rustc::is_min_version("1.56.0");
+/// rustc::is_feature_flaggable();
+/// assert_eq!(rustc::spawn_count(), spawned);
+/// # }
+/// ```
+#[cfg(feature = "metrics")]
+pub fn spawn_count() -> usize {
+    SPAWN_COUNT.load(atomic::Ordering::SeqCst)
+}
+
+/// Reads the build date of the running or installed `rustc`.
+///
+/// The build date is reported in the `build-date` field of `rustc --verbose
+/// --version` and is distinct from the release date reported by
+/// [`Date::read()`]. Only very old compilers, such as `1.0.0`, report it.
+///
+/// If `rustc` cannot be read, or if it does not report a valid build date,
+/// returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// match rustc::build_date() {
+///     Some(d) => format!("rustc was built on {}", d),
+///     None => format!("rustc does not report a build date.")
+/// };
+/// ```
+pub fn build_date() -> Option<Date> {
+    rustc_verbose_version().ok()
+        .and_then(|s| verbose_field(&s, "build-date").and_then(Date::parse))
+}
+
+/// Returns the edition the running or installed `rustc` uses when no
+/// `--edition` flag is passed.
+///
+/// Every version of `rustc` to date, including those supporting newer
+/// editions, defaults to the `2015` edition; it is Cargo, not `rustc`, that
+/// selects a newer edition from a crate's manifest. Code compiled without an
+/// explicit `--edition`, such as feature probes, is thus compiled as `2015`
+/// code. To determine the newest edition supported instead, use
+/// [`Capabilities::edition_max()`].
+///
+/// If the version cannot be determined (see the [top-level
+/// documentation](crate)), returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(edition) = rustc::default_edition() {
+///     assert_eq!(edition, "2015");
+/// }
+/// ```
+pub fn default_edition() -> Option<&'static str> {
+    Version::read().map(|_| "2015")
+}
+
+/// Checks whether the running or installed `rustc` reports the commit it was
+/// built from.
+///
+/// Official builds report a `commit-hash` and `commit-date` in `rustc
+/// --verbose --version`. Custom and distribution builds often report them as
+/// `unknown`, in which case the release date cannot be determined and
+/// date-based checks such as [`is_min_date()`] return `None`.
+///
+/// If `rustc` cannot be read or does not report commit metadata at all,
+/// returns `None`. Otherwise returns `true` if both the commit hash and date
+/// are known and `false` otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(false) = rustc::is_official_build() {
+///     println!("cargo:warning=rustc has no commit date; skipping date checks");
+/// }
+/// ```
+pub fn is_official_build() -> Option<bool> {
+    rustc_verbose_version().ok()
+        .and_then(|s| official_build_from_rustc_verbose_version(&s))
+}
+
+/// Guesses whether the running or installed `rustc` is a local development
+/// build, which may be unoptimized or have debug assertions enabled.
+///
+/// **This is a heuristic.** `rustc` does not report how it was built, so this
+/// function infers it: a `dev` channel release with `unknown` commit metadata
+/// (see [`is_official_build()`]) is assumed to be a local build, while any
+/// `nightly`, `beta`, or `stable` release, including a distribution build, is
+/// assumed to be an optimized build.
+///
+/// If `rustc` cannot be read, if its channel cannot be determined, or if it is
+/// a `dev` release that reports known commit metadata, nothing can be inferred
+/// and this function returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::is_dev_build() {
+///     println!("cargo:warning=rustc appears to be a local development build");
+/// }
+/// ```
+pub fn is_dev_build() -> Option<bool> {
+    rustc_verbose_version().ok()
+        .and_then(|s| dev_build_from_rustc_verbose_version(&s))
+}
+
+/// Guesses whether the crate being built is compiled incrementally by default.
+///
+/// `rustc` itself never compiles incrementally unless asked to; Cargo enables
+/// incremental compilation for the `dev` and `test` profiles and disables it
+/// for `release` and `bench`. This is a heuristic that considers, in order:
+///
+///   1. `CARGO_INCREMENTAL`: `0` forces incremental compilation off and `1`
+///      forces it on, overriding profile settings.
+///   2. `PROFILE`, set by Cargo for build scripts: `debug` implies on, and
+///      `release` implies off.
+///
+/// The release channel of `rustc` does not affect the default and is not
+/// considered, and `rustc` is not invoked. Per-profile `incremental` settings
+/// in `Cargo.toml` are not visible to build scripts and are not considered.
+/// If neither variable is set to a recognized value, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::incremental_default() {
+///     println!("cargo:warning=incremental build; skipping expensive codegen");
+/// }
+/// ```
+pub fn incremental_default() -> Option<bool> {
+    let incremental = env::var("CARGO_INCREMENTAL").ok();
+    let profile = env::var("PROFILE").ok();
+    incremental_from(incremental.as_ref().map(|s| &**s), profile.as_ref().map(|s| &**s))
+}
+
+/// Guesses the incremental default from `CARGO_INCREMENTAL` and `PROFILE`.
+fn incremental_from(cargo_incremental: Option<&str>, profile: Option<&str>) -> Option<bool> {
+    match (cargo_incremental.map(|v| v.trim()), profile) {
+        (Some("0"), _) => Some(false),
+        (Some("1"), _) => Some(true),
+        (_, Some("debug")) => Some(true),
+        (_, Some("release")) => Some(false),
+        _ => None
+    }
+}
+
+/// Returns `true` if the target `triple` is an Apple platform, such as
+/// `aarch64-apple-darwin` or `x86_64-apple-ios`.
+fn is_apple_triple(triple: &str) -> bool {
+    triple.split('-').nth(1) == Some("apple")
+}
+
+/// Returns `true` if the target `triple` is Windows with the MSVC environment,
+/// such as `x86_64-pc-windows-msvc`.
+fn is_windows_msvc_triple(triple: &str) -> bool {
+    let mut components = triple.split('-').skip(2);
+    components.next() == Some("windows") && components.next() == Some("msvc")
+}
+
+/// Reads the host target triple of the running or installed `rustc` and
+/// applies `f` to it.
+fn with_host<F: FnOnce(&str) -> bool>(f: F) -> Option<bool> {
+    rustc_verbose_version().ok()
+        .and_then(|s| verbose_field(&s, "host").map(f))
+}
+
+/// Checks whether the host of the running or installed `rustc` is an Apple
+/// platform: its target triple's vendor is `apple`, as in
+/// `aarch64-apple-darwin`.
+///
+/// Note that in a build script, the host is the platform the build runs on,
+/// which differs from the target when cross-compiling. Use Cargo's
+/// `CARGO_CFG_TARGET_*` environment variables to inspect the target instead.
+///
+/// If the host triple cannot be determined, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::is_apple() {
+///     println!("cargo:rustc-cfg=apple_host");
+/// }
+/// ```
+pub fn is_apple() -> Option<bool> {
+    with_host(is_apple_triple)
+}
+
+/// Checks whether the host of the running or installed `rustc` is Windows
+/// with the MSVC environment: its target triple's OS is `windows` and its
+/// environment `msvc`, as in `x86_64-pc-windows-msvc`.
+///
+/// See the note in [`is_apple()`] about hosts and targets. If the host triple
+/// cannot be determined, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::is_windows_msvc() {
+///     println!("cargo:rustc-cfg=msvc_host");
+/// }
+/// ```
+pub fn is_windows_msvc() -> Option<bool> {
+    with_host(is_windows_msvc_triple)
+}
+
+/// Checks whether the crate being built is being cross-compiled: whether the
+/// host target triple of the running or installed `rustc` differs from the
+/// target triple in the `TARGET` environment variable, which Cargo sets when
+/// running build scripts.
+///
+/// If the host triple cannot be determined or `TARGET` is not set, as outside
+/// of a build script, returns `None`. Otherwise returns `true` if the triples
+/// differ and `false` if they are equal.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::is_cross_compiling() {
+///     println!("cargo:rustc-cfg=cross_compiling");
+/// }
+/// ```
+pub fn is_cross_compiling() -> Option<bool> {
+    env::var("TARGET").ok().and_then(|target| with_host(|host| host != target))
+}
+
+/// Returns a compact JSON summary of the running or installed `rustc`, for
+/// build tooling that parses build script output.
+///
+/// The summary is a single-line JSON object with exactly the string-valued
+/// keys `version`, `channel`, `date`, and `host`, in that order, as reported
+/// by [`summary()`]. Keys whose value cannot be determined are `null`. For
+/// instance:
+///
+/// ```text
+/// {"version":"1.70.0","channel":"stable","date":"2023-05-31","host":"x86_64-unknown-linux-gnu"}
+/// ```
+///
+/// This format is stable: keys are neither renamed nor removed, and any keys
+/// added in the future will follow the existing ones. If `rustc` cannot be
+/// read or nothing can be determined, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(json) = rustc::summary_json() {
+///     println!("cargo:warning=rustc: {}", json);
+/// }
+/// ```
+pub fn summary_json() -> Option<String> {
+    rustc_verbose_version().ok()
+        .and_then(|s| summary_json_from_rustc_verbose_version(&s))
+}
+
+/// Returns a single-line, machine-readable summary of the running or installed
+/// `rustc`.
+///
+/// The summary is a space-separated list of `key=value` pairs for the keys
+/// `version`, `channel`, `date`, and `host`, in that order. For instance:
+///
+/// ```text
+/// version=1.70.0 channel=stable date=2023-05-31 host=x86_64-unknown-linux-gnu
+/// ```
+///
+/// Pairs whose value cannot be determined are omitted. If `rustc` cannot be
+/// read or nothing can be determined, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(summary) = rustc::summary() {
+///     println!("cargo:warning=detected rustc: {}", summary);
+/// }
+/// ```
+pub fn summary() -> Option<String> {
+    rustc_verbose_version().ok()
+        .map(|s| summary_from_rustc_verbose_version(&s))
+        .and_then(|summary| match summary.is_empty() {
+            true => None,
+            false => Some(summary)
+        })
+}
+
+/// Returns every attribute of the running or installed `rustc` that can be
+/// determined, keyed by name, from a single read of `rustc --verbose
+/// --version`.
+///
+/// The keys are stable and are exactly:
+///
+///   * `version`, `channel`, `date`, `host`: as in [`summary()`]. `version`
+///     is the parsed [`Version`], including any pre-release tag.
+///   * `binary`, `commit_hash`, `commit_date`, `release`, `llvm_version`: the
+///     raw values of the `binary`, `commit-hash`, `commit-date`, `release`,
+///     and `LLVM version` lines.
+///
+/// Keys whose value cannot be determined, or is reported as `unknown`, are
+/// omitted. If `rustc` cannot be read or nothing can be determined, returns
+/// `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(fields) = rustc::fields() {
+///     for (key, value) in &fields {
+///         println!("cargo:warning=rustc {}: {}", key, value);
+///     }
+/// }
+/// ```
+pub fn fields() -> Option<BTreeMap<String, String>> {
+    rustc_verbose_version().ok()
+        .map(|s| fields_from_rustc_verbose_version(&s))
+        .and_then(|fields| match fields.is_empty() {
+            true => None,
+            false => Some(fields)
+        })
+}
+
+/// Compares the versions of the `rustc` compilers at paths `rustc_a` and
+/// `rustc_b`.
+///
+/// Each compiler is invoked once and its [`Version`] read. The versions are
+/// compared via `Ord`, so pre-release tags are considered: `1.50.0-nightly` is
+/// older than `1.50.0`. Bare names, like `rustc`, are looked up in `PATH`.
+///
+/// If either version cannot be determined, returns `None`. Otherwise returns
+/// the ordering of the version of `rustc_a` relative to that of `rustc_b`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::cmp::Ordering;
+/// use version_check as rustc;
+///
+/// match rustc::compare("/usr/bin/rustc", "rustc") {
+///     Some(Ordering::Less) => "/usr/bin/rustc is older",
+///     Some(Ordering::Equal) => "both are the same version",
+///     Some(Ordering::Greater) => "/usr/bin/rustc is newer",
+///     None => "couldn't determine both versions",
+/// };
+/// ```
+pub fn compare<A: AsRef<Path>, B: AsRef<Path>>(rustc_a: A, rustc_b: B) -> Option<Ordering> {
+    let read = |path: &Path| rustc_verbose_version_at(path).ok()
+        .and_then(|s| version_and_date_from_rustc_verbose_version(&s).0)
+        .and_then(|version| Version::parse(&version));
+
+    match (read(rustc_a.as_ref()), read(rustc_b.as_ref())) {
+        (Some(a), Some(b)) => Some(a.cmp(&b)),
+        _ => None
+    }
+}
+
+/// Checks that the running or installed `rustc` is at least some version,
+/// including its pre-release tag, **and** was released on or after some date.
+///
+/// Unlike [`is_min_version()`], pre-release tags, including beta ordinals,
+/// are considered as described in [`Version`]: `1.70.0-beta.4` is at least
+/// `1.70.0-beta.2`, but `1.70.0-beta.1` is not. This allows requiring a
+/// specific beta and freshness of that beta at once. Both attributes are read
+/// from a single invocation of `rustc`.
+///
+/// If the version or date cannot be determined, or if `min_version` or
+/// `min_date` cannot be parsed, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// match rustc::at_least_beta("1.70.0-beta.2", "2023-04-25") {
+///     Some(true) => "Yep! It's at least beta 2 from 2023-04-25.",
+///     Some(false) => "No, it's older.",
+///     None => "Couldn't determine the rustc version or date."
+/// };
+/// ```
+pub fn at_least_beta(min_version: &str, min_date: &str) -> Option<bool> {
+    match (triple(), Version::parse(min_version), Date::parse(min_date)) {
+        (Some((version, _, date)), Some(min_version), Some(min_date)) => {
+            Some(version >= min_version && date >= min_date)
+        }
+        _ => None
+    }
+}
+
+/// Checks that the running or installed `rustc` was released **on or after**
+/// some date.
+///
+/// The format of `min_date` must be YYYY-MM-DD. For instance: `2016-12-20` or
+/// `2017-01-09`.
+///
+/// If the date cannot be retrieved or parsed, or if `min_date` could not be
+/// parsed, returns `None`. Otherwise returns `true` if the installed `rustc`
+/// was release on or after `min_date` and `false` otherwise.
+pub fn is_min_date(min_date: &str) -> Option<bool> {
+    check_min_date(min_date).map(|(is_min, _)| is_min)
+}
+
+/// Like [`is_min_date()`] but additionally returns the detected release date,
+/// read once, so that it can be logged without reading it again.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some((is_min, date)) = rustc::check_min_date("2021-10-21") {
+///     println!("cargo:warning=rustc from {} is at least 2021-10-21: {}", date, is_min);
+/// }
+/// ```
+pub fn check_min_date(min_date: &str) -> Option<(bool, Date)> {
+    match (Date::read(), Date::parse(min_date)) {
+        (Some(rustc_date), Some(min_date)) => Some((rustc_date >= min_date, rustc_date)),
+        _ => None
+    }
+}
+
+/// Like [`is_min_date()`] but returns `default` if the release date cannot be
+/// determined or `min_date` cannot be parsed.
+///
+/// This chooses, in one call, whether an undeterminable toolchain should be
+/// treated as satisfying the check, so that, for instance, a safe fallback can
+/// be enabled when nothing is known.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if !rustc::is_min_date_or("2021-10-21", true) {
+///     println!("cargo:rustc-cfg=old_nightly_fallback");
+/// }
+/// ```
+pub fn is_min_date_or(min_date: &str, default: bool) -> bool {
+    is_min_date(min_date).unwrap_or(default)
+}
+
+/// Checks that the running or installed `rustc` was released **on or before**
+/// some date.
+///
+/// The format of `max_date` must be YYYY-MM-DD. For instance: `2016-12-20` or
+/// `2017-01-09`.
+///
+/// If the date cannot be retrieved or parsed, or if `max_date` could not be
+/// parsed, returns `None`. Otherwise returns `true` if the installed `rustc`
+/// was release on or before `max_date` and `false` otherwise.
+pub fn is_max_date(max_date: &str) -> Option<bool> {
+    match (Date::read(), Date::parse(max_date)) {
+        (Some(rustc_date), Some(max_date)) => Some(rustc_date <= max_date),
+        _ => None
+    }
+}
+
+/// Like [`is_max_date()`] but returns `default` if the release date cannot be
+/// determined or `max_date` cannot be parsed. See [`is_min_date_or()`].
+pub fn is_max_date_or(max_date: &str, default: bool) -> bool {
+    is_max_date(max_date).unwrap_or(default)
+}
+
+/// Checks that the running or installed `rustc` was released **exactly** on
+/// some date.
+///
+/// The format of `date` must be YYYY-MM-DD. For instance: `2016-12-20` or
+/// `2017-01-09`.
+///
+/// If the date cannot be retrieved or parsed, or if `date` could not be parsed,
+/// returns `None`. Otherwise returns `true` if the installed `rustc` was
+/// release on `date` and `false` otherwise.
+pub fn is_exact_date(date: &str) -> Option<bool> {
+    match (Date::read(), Date::parse(date)) {
+        (Some(rustc_date), Some(date)) => Some(rustc_date == date),
+        _ => None
+    }
+}
+
+/// Compares the release date of the running or installed `rustc` to `date`.
+///
+/// The date is read once, so a single call suffices to branch on whether the
+/// compiler was released before, on, or after `date`.
+///
+/// If the date cannot be retrieved or parsed, or if `date` could not be
+/// parsed, returns `None`. Otherwise returns the ordering of the release date
+/// of the installed `rustc` relative to `date`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::cmp::Ordering;
+/// use version_check as rustc;
+///
+/// match rustc::compare_date("2023-06-01") {
+///     Some(Ordering::Less) => "Released before June 1, 2023.",
+///     Some(Ordering::Equal) => "Released on June 1, 2023.",
+///     Some(Ordering::Greater) => "Released after June 1, 2023.",
+///     None => "Couldn't determine the release date.",
+/// };
+/// ```
+pub fn compare_date(date: &str) -> Option<Ordering> {
+    match (Date::read(), Date::parse(date)) {
+        (Some(rustc_date), Some(date)) => Some(rustc_date.cmp(&date)),
+        _ => None
+    }
+}
+
+/// Checks that the running or installed `rustc` is **at least** some minimum
+/// version. The check is inclusive: `>= min_version`. For an exclusive check,
+/// use [`is_greater_version()`].
+///
+/// The format of `min_version` is a semantic version: `1.3.0`, `1.15.0-beta`,
+/// `1.14.0`, `1.16.0-nightly`, etc. Pre-release tags are ignored.
+///
+/// If the version cannot be retrieved or parsed, or if `min_version` could not
+/// be parsed, returns `None`. Otherwise returns `true` if the installed `rustc`
+/// is at least `min_version` and `false` otherwise.
+pub fn is_min_version(min_version: &str) -> Option<bool> {
+    check_min_version(min_version).map(|(is_min, _)| is_min)
+}
+
+/// Like [`is_min_version()`] but additionally returns the detected version,
+/// read once, so that it can be logged without reading it again.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some((is_min, version)) = rustc::check_min_version("1.56.0") {
+///     println!("cargo:warning=rustc {} is at least 1.56.0: {}", version, is_min);
+/// }
+/// ```
+pub fn check_min_version(min_version: &str) -> Option<(bool, Version)> {
+    match (Version::read(), Version::parse(min_version)) {
+        (Some(rustc_ver), Some(min_ver)) => {
+            Some((rustc_ver.stripped() >= min_ver.stripped(), rustc_ver))
+        }
+        _ => None
+    }
+}
+
+/// Like [`is_min_version()`] but returns `default` if the version cannot be
+/// determined or `min_version` cannot be parsed.
+///
+/// This chooses, in one call, whether an undeterminable toolchain should be
+/// treated as satisfying the check, and combines cleanly with other checks.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// // Assume a modern compiler when the version is unknown.
+/// if rustc::is_min_version_or("1.56.0", true) {
+///     println!("cargo:rustc-cfg=edition_2021");
+/// }
+/// ```
+pub fn is_min_version_or(min_version: &str, default: bool) -> bool {
+    is_min_version(min_version).unwrap_or(default)
+}
+
+/// Checks that the running or installed `rustc` is **at least** some minimum
+/// version **and** on the release channel named `channel`.
+///
+/// `min_version` is compared as in [`is_min_version()`]. `channel` is one of
+/// `"dev"`, `"nightly"`, `"beta"`, or `"stable"`. The version is read once, and
+/// the channel is derived from it. This avoids enabling a stable-only feature
+/// on, say, `1.75.0-nightly`, which satisfies `is_min_version("1.75.0")`.
+///
+/// If the version cannot be retrieved or parsed, if `min_version` could not be
+/// parsed, or if `channel` is not a known channel name, returns `None`.
+/// Otherwise returns `true` if the installed `rustc` is at least `min_version`
+/// and on `channel` and `false` otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::is_min_version_on_channel("1.75.0", "stable") {
+///     println!("cargo:rustc-cfg=stable_async_fn_in_trait");
+/// }
+/// ```
+pub fn is_min_version_on_channel(min_version: &str, channel: &str) -> Option<bool> {
+    let channel = match channel_from_name(channel) {
+        Some(channel) => channel,
+        None => return None
+    };
+
+    match (Version::read(), Version::parse(min_version)) {
+        (Some(rustc_ver), Some(min_ver)) => {
+            Some(rustc_ver.stripped() >= min_ver.stripped() && rustc_ver.channel() == channel)
+        }
+        _ => None
+    }
+}
+
+/// Checks that the running or installed `rustc` is **at least** some minimum
+/// version **and** on the stable channel.
+///
+/// This is the stable-only counterpart to [`is_min_version()`], which ignores
+/// pre-release tags and so is also satisfied by, say, `1.75.0-nightly` when
+/// `min_version` is `1.75.0`. It is equivalent to
+/// [`is_min_version_on_channel(min_version, "stable")`](is_min_version_on_channel()).
+///
+/// If the version cannot be retrieved or parsed, or if `min_version` could not
+/// be parsed, returns `None`. Otherwise returns `true` if the installed `rustc`
+/// is a stable release of at least `min_version` and `false` otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::is_at_least_stable("1.75.0") {
+///     println!("cargo:rustc-cfg=stable_async_fn_in_trait");
+/// }
+/// ```
+pub fn is_at_least_stable(min_version: &str) -> Option<bool> {
+    is_min_version_on_channel(min_version, "stable")
+}
+
+/// Checks that the running or installed `rustc` is **at least** some minimum
+/// version, where only a `beta` counts as the release it precedes.
+///
+/// A beta carries the version number of the upcoming stable release, and its
+/// features are frozen, so `1.70.0-beta.2` satisfies `>= 1.70.0`. Otherwise,
+/// versions are compared as by `Ord`, with pre-release tags respected: a
+/// `1.70.0-nightly` or `1.70.0-dev` does _not_ satisfy `>= 1.70.0`, as it
+/// predates the features stabilized in the `1.70.0` release.
+///
+/// This differs from [`is_min_version()`], which ignores pre-release tags
+/// entirely and so also accepts nightly and dev builds of `min_version`.
+///
+/// If the version cannot be retrieved or parsed, or if `min_version` could not
+/// be parsed, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::is_min_version_beta_counts("1.70.0") {
+///     println!("cargo:rustc-cfg=has_once_cell");
+/// }
+/// ```
+pub fn is_min_version_beta_counts(min_version: &str) -> Option<bool> {
+    match (Version::read(), Version::parse(min_version)) {
+        (Some(rustc_ver), Some(min_ver)) => Some(beta_counts(&rustc_ver, &min_ver)),
+        _ => None
+    }
+}
+
+fn beta_counts(rustc_ver: &Version, min_ver: &Version) -> bool {
+    match rustc_ver.channel().is_beta() {
+        true => rustc_ver.stripped() >= min_ver.stripped(),
+        false => *rustc_ver >= *min_ver,
+    }
+}
+
+/// Checks that the running or installed `rustc` is **at most** some maximum
+/// version. The check is inclusive: `<= max_version`. For an exclusive check,
+/// use [`is_less_version()`].
+///
+/// The format of `max_version` is a semantic version: `1.3.0`, `1.15.0-beta`,
+/// `1.14.0`, `1.16.0-nightly`, etc. Pre-release tags are ignored.
+///
+/// If the version cannot be retrieved or parsed, or if `max_version` could not
+/// be parsed, returns `None`. Otherwise returns `true` if the installed `rustc`
+/// is at most `max_version` and `false` otherwise.
+pub fn is_max_version(max_version: &str) -> Option<bool> {
+    match (Version::read(), Version::parse(max_version)) {
+        (Some(rustc_ver), Some(max_ver)) => Some(rustc_ver.stripped() <= max_ver.stripped()),
+        _ => None
+    }
+}
+
+/// Like [`is_max_version()`] but returns `default` if the version cannot be
+/// determined or `max_version` cannot be parsed. See [`is_min_version_or()`].
+pub fn is_max_version_or(max_version: &str, default: bool) -> bool {
+    is_max_version(max_version).unwrap_or(default)
+}
+
+/// Checks that the running or installed `rustc` is **exactly** some version.
+///
+/// The format of `version` is a semantic version: `1.3.0`, `1.15.0-beta`,
+/// `1.14.0`, `1.16.0-nightly`, etc.
+///
+/// If the version cannot be retrieved or parsed, or if `version` could not be
+/// parsed, returns `None`. Otherwise returns `true` if the installed `rustc` is
+/// exactly `version` and `false` otherwise.
+pub fn is_exact_version(version: &str) -> Option<bool> {
+    match (Version::read(), Version::parse(version)) {
+        (Some(rustc_ver), Some(version)) => Some(rustc_ver.stripped() == version.stripped()),
+        _ => None
+    }
+}
+
+/// Checks that the running or installed `rustc` is **strictly greater** than
+/// some version. The check is exclusive: `> version`. For an inclusive check,
+/// use [`is_min_version()`].
+///
+/// The format of `version` is a semantic version: `1.3.0`, `1.15.0-beta`,
+/// `1.14.0`, `1.16.0-nightly`, etc. Pre-release tags are ignored.
+///
+/// If the version cannot be retrieved or parsed, or if `version` could not be
+/// parsed, returns `None`. Otherwise returns `true` if the installed `rustc` is
+/// greater than `version` and `false` otherwise.
+pub fn is_greater_version(version: &str) -> Option<bool> {
+    match (Version::read(), Version::parse(version)) {
+        (Some(rustc_ver), Some(version)) => Some(rustc_ver.stripped() > version.stripped()),
+        _ => None
+    }
+}
+
+/// Checks that the running or installed `rustc` is **strictly less** than
+/// some version. The check is exclusive: `< version`. For an inclusive check,
+/// use [`is_max_version()`].
+///
+/// The format of `version` is a semantic version: `1.3.0`, `1.15.0-beta`,
+/// `1.14.0`, `1.16.0-nightly`, etc. Pre-release tags are ignored.
+///
+/// If the version cannot be retrieved or parsed, or if `version` could not be
+/// parsed, returns `None`. Otherwise returns `true` if the installed `rustc` is
+/// less than `version` and `false` otherwise.
+pub fn is_less_version(version: &str) -> Option<bool> {
+    match (Version::read(), Version::parse(version)) {
+        (Some(rustc_ver), Some(version)) => Some(rustc_ver.stripped() < version.stripped()),
+        _ => None
+    }
+}
+
+/// Checks that the running or installed `rustc` is **at least** `min_version`.
+///
+/// Like [`is_min_version()`] but takes an already parsed [`Version`].
+/// Pre-release tags are ignored.
+///
+/// If the version cannot be retrieved or parsed, returns `None`. Otherwise
+/// returns `true` if the installed `rustc` is at least `min_version` and
+/// `false` otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+/// use version_check::Version;
+///
+/// let msrv = Version::from_mmp(1, 31, 0);
+/// if let Some(false) = rustc::is_min(&msrv) {
+///     println!("cargo:warning=rustc is older than {}", msrv);
+/// }
+/// ```
+pub fn is_min(min_version: &Version) -> Option<bool> {
+    Version::read().map(|rustc_ver| rustc_ver.stripped() >= min_version.stripped())
+}
+
+/// Checks that the running or installed `rustc` is **at most** `max_version`.
+///
+/// Like [`is_max_version()`] but takes an already parsed [`Version`].
+/// Pre-release tags are ignored.
+///
+/// If the version cannot be retrieved or parsed, returns `None`. Otherwise
+/// returns `true` if the installed `rustc` is at most `max_version` and `false`
+/// otherwise.
+pub fn is_max(max_version: &Version) -> Option<bool> {
+    Version::read().map(|rustc_ver| rustc_ver.stripped() <= max_version.stripped())
+}
+
+/// Checks that the running or installed `rustc` is **exactly** `version`.
+///
+/// Like [`is_exact_version()`] but takes an already parsed [`Version`].
+/// Pre-release tags are ignored.
+///
+/// If the version cannot be retrieved or parsed, returns `None`. Otherwise
+/// returns `true` if the installed `rustc` is exactly `version` and `false`
+/// otherwise.
+pub fn is_exact(version: &Version) -> Option<bool> {
+    Version::read().map(|rustc_ver| rustc_ver.stripped() == version.stripped())
+}
+
+/// Compares the version of the running or installed `rustc` to `version`.
+///
+/// The version is read once, so a single call suffices to branch on whether
+/// the compiler is older than, exactly, or newer than `version`. As with
+/// [`is_min_version()`] and friends, pre-release tags are ignored.
+///
+/// If the version cannot be retrieved or parsed, or if `version` could not be
+/// parsed, returns `None`. Otherwise returns the ordering of the version of the
+/// installed `rustc` relative to `version`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::cmp::Ordering;
+/// use version_check as rustc;
+///
+/// match rustc::compare_version("1.70.0") {
+///     Some(Ordering::Less) => "Older than 1.70.0.",
+///     Some(Ordering::Equal) => "Exactly 1.70.0.",
+///     Some(Ordering::Greater) => "Newer than 1.70.0.",
+///     None => "Couldn't determine the rustc version.",
+/// };
+/// ```
+pub fn compare_version(version: &str) -> Option<Ordering> {
+    match (Version::read(), Version::parse(version)) {
+        (Some(rustc_ver), Some(version)) => Some(rustc_ver.stripped().cmp(&version.stripped())),
+        _ => None
+    }
+}
+
+/// Checks whether the running or installed `rustc` version satisfies `range`.
+///
+/// `range` is a comma-separated list of comparators such as `>=1.70.0-beta.2,
+/// <1.70.0`, including Cargo-style caret and tilde requirements such as
+/// `^1.70` and `~1.70.3`; see [`Version::in_range()`] for the syntax and the
+/// supported operators. Pre-release tags, including beta ordinals, are
+/// respected.
+///
+/// If the version cannot be retrieved or parsed, or if `range` could not be
+/// parsed, returns `None`. Otherwise returns `true` if the installed `rustc`
+/// satisfies every comparator in `range` and `false` otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::is_version_in_range(">=1.56.0, <2.0.0") {
+///     println!("cargo:rustc-cfg=edition_2021");
+/// }
+///
+/// if let Some(true) = rustc::is_version_in_range("^1.70") {
+///     println!("cargo:rustc-cfg=has_once_cell");
+/// }
+/// ```
+pub fn is_version_in_range(range: &str) -> Option<bool> {
+    Version::read().and_then(|version| version.in_range(range))
+}
+
+/// Checks whether the running or installed `rustc` satisfies every constraint
+/// in the whitespace-separated `spec`, as in `">=1.70.0 stable
+/// 2023-01-01..2024-01-01"`.
+///
+/// Each token in `spec` is one of:
+///
+///   * a version comparator, such as `>=1.70.0`, `<1.75.0-beta.2`, or
+///     `^1.70`, with the syntax and semantics of a comparator in
+///     [`Version::in_range()`], except that the operator is required;
+///   * a channel name: `dev`, `nightly`, `beta`, or `stable`;
+///   * a release date range `start..end`, including `start` and excluding
+///     `end`, either of which may be omitted, as in `2023-01-01..`.
+///
+/// The compiler is read once. An empty `spec` is satisfied by any compiler.
+///
+/// If the version cannot be retrieved or parsed, if a token is not recognized,
+/// or if `spec` contains a date range and the release date cannot be
+/// determined, returns `None`. Otherwise returns `true` if every constraint is
+/// satisfied and `false` otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::satisfies_spec(">=1.70.0 nightly 2023-06-01..") {
+///     println!("cargo:rustc-cfg=recent_nightly");
+/// }
+/// ```
+pub fn satisfies_spec(spec: &str) -> Option<bool> {
+    let (version, date) = match get_version_and_date() {
+        Some((Some(version), date)) => (version, date),
+        _ => return None
+    };
+
+    match Version::parse(&version) {
+        Some(version) => spec_satisfied_by(spec, version, date.and_then(|d| Date::parse(&d))),
+        None => None
+    }
+}
+
+/// Evaluates `spec`, as in [`satisfies_spec()`], against `version` and `date`.
+fn spec_satisfied_by(spec: &str, version: Version, date: Option<Date>) -> Option<bool> {
+    let mut satisfied = true;
+    for token in spec.split(|c: char| c.is_whitespace()).filter(|t| !t.is_empty()) {
+        let is_comparator = |c| c == '<' || c == '>' || c == '=' || c == '^' || c == '~';
+        let token_satisfied = if token.starts_with(is_comparator) {
+            version.in_range(token)
+        } else if token.contains("..") {
+            let mut bounds = token.splitn(2, "..");
+            let (start, end) = (bounds.next().unwrap_or(""), bounds.next().unwrap_or(""));
+            let parse = |bound: &str| match bound {
+                "" => Some(None),
+                bound => Date::parse(bound).map(Some),
+            };
+
+            match (parse(start), parse(end), date) {
+                (Some(None), Some(None), _) => None,
+                (Some(start), Some(end), Some(date)) => {
+                    Some(start.map_or(true, |s| date >= s) && end.map_or(true, |e| date < e))
+                }
+                _ => None
+            }
+        } else {
+            match token {
+                "dev" => Some(version.channel().is_dev()),
+                "nightly" => Some(version.channel().is_nightly()),
+                "beta" => Some(version.channel().is_beta()),
+                "stable" => Some(version.channel().is_stable()),
+                _ => None
+            }
+        };
+
+        match token_satisfied {
+            Some(token_satisfied) => satisfied = satisfied && token_satisfied,
+            None => return None
+        }
+    }
+
+    Some(satisfied)
+}
+
+/// Exits the build script with an error if the running or installed `rustc` is
+/// older than `min_version`.
+///
+/// If the version is less than `min_version`, as determined by
+/// [`is_min_version()`], prints a `cargo:warning` and an error message
+/// explaining the requirement and exits the process with status `1`. If the
+/// version is at least `min_version` or cannot be determined, does nothing. To
+/// also fail when the version cannot be determined, use
+/// [`require_min_version_strict()`].
+///
+/// If `min_version` cannot be parsed, always fails.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// // In `build.rs`:
+/// rustc::require_min_version("1.31.0");
+/// ```
+pub fn require_min_version(min_version: &str) {
+    require_min_version_with(min_version, false)
+}
+
+/// Like [`require_min_version()`] but additionally exits with an error if the
+/// version of the running or installed `rustc` cannot be determined.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// // In `build.rs`:
+/// rustc::require_min_version_strict("1.31.0");
+/// ```
+pub fn require_min_version_strict(min_version: &str) {
+    require_min_version_with(min_version, true)
+}
+
+fn require_min_version_with(min_version: &str, strict: bool) {
+    let message = match (Version::read(), Version::parse(min_version)) {
+        (_, None) => format!("invalid minimum rustc version `{}`", min_version),
+        (Some(version), Some(min)) if version.stripped() < min.stripped() => {
+            format!("rustc {} is not supported: {} or newer is required", version, min)
+        }
+        (None, Some(min)) if strict => {
+            format!("the rustc version could not be determined: {} or newer is required", min)
+        }
+        _ => return
+    };
+
+    println!("cargo:warning={}", message);
+    let _ = writeln!(io::stderr(), "error: {}", message);
+    process::exit(1);
+}
+
+/// Panics if the `rustc` the build script was compiled with is older than
+/// `min_version`.
+///
+/// Cargo compiles build scripts with the same `rustc` it runs them with, so
+/// this reads the running or installed `rustc` once, as [`Version::read()`],
+/// and compares it with `min_version`, ignoring pre-release tags. Use it in
+/// build scripts that themselves rely on newer `std` APIs, as opposed to
+/// requirements of the crate being built, for which
+/// [`require_min_version()`] is suited. No `cfg` is printed either way.
+///
+/// Panics with a message naming both versions if the version is less than
+/// `min_version`, and if `min_version` cannot be parsed. If the version is at
+/// least `min_version` or cannot be determined, does nothing.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// // In `build.rs`:
+/// rustc::assert_build_script_min_version("1.31.0");
+/// ```
+pub fn assert_build_script_min_version(min_version: &str) {
+    match (Version::read(), Version::parse(min_version)) {
+        (_, None) => panic!("invalid minimum rustc version `{}`", min_version),
+        (Some(version), Some(min)) if version.stripped() < min.stripped() => {
+            panic!("this build script requires rustc {} or newer, but rustc {} is in use",
+                min, version)
+        }
+        _ => {}
+    }
+}
+
+/// Sets `cfg` flags based on the version of the running or installed `rustc`.
+///
+/// `pairs` is a list of `(cfg, min_version)` pairs. The version is read once,
+/// and for every pair where the version is at least `min_version`, as
+/// determined by [`Version::at_least()`], `cargo:rustc-cfg={cfg}` is printed.
+/// Pairs where `min_version` cannot be parsed are skipped. If the version
+/// cannot be determined, nothing is printed. To also require a channel or
+/// release date, use [`apply_cfg_rules()`]. To obtain the lines without
+/// printing them, use [`plan_version_cfgs()`].
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// rustc::apply_version_cfgs(&[
+///     ("question_mark_operator", "1.13.0"),
+///     ("const_generics", "1.51.0"),
+///     ("let_else", "1.65.0"),
+/// ]);
+/// ```
+pub fn apply_version_cfgs(pairs: &[(&str, &str)]) {
+    for line in plan_version_cfgs(pairs) {
+        println!("{}", line);
+    }
+}
+
+/// Returns the lines [`apply_version_cfgs()`] would print for `pairs`, in
+/// order, without printing them.
+///
+/// Each line is of the form `cargo:rustc-cfg={cfg}`. This allows `cfg` logic
+/// to be tested or logged before it is committed to.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// let lines = rustc::plan_version_cfgs(&[("let_else", "1.65.0")]);
+/// for line in &lines {
+///     println!("cargo:warning=would print: {}", line);
+/// }
+///
+/// if let Some(true) = rustc::is_min_version("1.65.0") {
+///     assert_eq!(lines, ["cargo:rustc-cfg=let_else"]);
+/// }
+/// ```
+pub fn plan_version_cfgs(pairs: &[(&str, &str)]) -> Vec<String> {
+    let mut lines = vec![];
+    if let Some(version) = Version::read() {
+        for &(cfg, min_version) in pairs {
+            if version.at_least(min_version) {
+                lines.push(format!("cargo:rustc-cfg={}", cfg));
+            }
+        }
+    }
+
+    lines
+}
+
+/// Returns the lines [`version_cfg!`] prints for `pairs`, in order, without
+/// printing them.
+///
+/// For every pair, `cargo:rustc-check-cfg=cfg({cfg})` is returned first,
+/// followed by the lines of [`plan_version_cfgs()`] for `pairs`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// let lines = rustc::plan_declared_version_cfgs(&[("let_else", "1.65.0")]);
+/// assert_eq!(lines[0], "cargo:rustc-check-cfg=cfg(let_else)");
+///
+/// if let Some(true) = rustc::is_min_version("1.65.0") {
+///     assert_eq!(lines[1], "cargo:rustc-cfg=let_else");
+/// }
+/// ```
+pub fn plan_declared_version_cfgs(pairs: &[(&str, &str)]) -> Vec<String> {
+    let mut lines: Vec<_> = pairs.iter()
+        .map(|&(cfg, _)| format!("cargo:rustc-check-cfg=cfg({})", cfg))
+        .collect();
+
+    lines.extend(plan_version_cfgs(pairs));
+    lines
+}
+
+/// Sets `cfg` flags based on the version of the running or installed `rustc`,
+/// declaring each `cfg` to Cargo's `cfg` checker.
+///
+/// Each arm is of the form `min_version => cfg`. For every arm,
+/// `cargo:rustc-check-cfg=cfg({cfg})` is printed, so the `cfg` is known to
+/// `rustc` whether or not it is set. Then, as in [`apply_version_cfgs()`], the
+/// version is read once and `cargo:rustc-cfg={cfg}` is printed for every arm
+/// where the version is at least `min_version`. To obtain the lines without
+/// printing them, use [`plan_declared_version_cfgs()`].
+///
+/// # Example
+///
+/// In `build.rs`:
+///
+/// ```rust
+/// #[macro_use] extern crate version_check;
+///
+/// fn main() {
+///     version_cfg! {
+///         "1.51.0" => "has_const_generics",
+///         "1.65.0" => "has_let_else",
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! version_cfg {
+    ($($min_version:expr => $cfg:expr),+ $(,)*) => ({
+        let pairs = [$(($cfg, $min_version)),+];
+        for line in $crate::plan_declared_version_cfgs(&pairs) {
+            println!("{}", line);
+        }
+    })
+}
+
+/// Checks whether the running or installed `rustc` supports feature flags.
+///
+/// Returns true if the channel is either "nightly" or "dev".
+///
+/// **Please see the note on [feature detection](crate#feature-detection).**
+///
+/// Note that support for specific `rustc` features can be enabled or disabled
+/// via the `allow-features` compiler flag, which this function _does not_
+/// check. That is, this function _does not_ check whether a _specific_ feature
+/// is supported, but instead whether features are supported at all. To check
+/// for support for a specific feature, use [`supports_feature()`].
+///
+/// This function also _does not_ account for `RUSTC_BOOTSTRAP`, which can
+/// enable feature flags on stable and beta or disable them on nightly. To
+/// determine whether `#![feature(...)]` would actually compile in the current
+/// environment, use [`unstable_options_available()`].
+///
+/// If the version could not be determined, returns `None`. Otherwise returns
+/// `true` if the running version supports feature flags and `false` otherwise.
+pub fn is_feature_flaggable() -> Option<bool> {
+    Channel::read().map(|c| c.supports_features())
+}
+
+/// Checks whether the release channel of the running or installed `rustc` is
+/// the one named `channel` and returns the result alongside the detected
+/// channel, read once, so that it can be logged without reading it again.
+///
+/// `channel` is one of `"dev"`, `"nightly"`, `"beta"`, or `"stable"`. If the
+/// channel cannot be determined or `channel` is not a known channel name,
+/// returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some((false, channel)) = rustc::check_channel("nightly") {
+///     println!("cargo:warning=nightly features disabled on {}", channel);
+/// }
+/// ```
+pub fn check_channel(channel: &str) -> Option<(bool, Channel)> {
+    match (Channel::read(), channel_from_name(channel)) {
+        (Some(rustc_channel), Some(channel)) => Some((rustc_channel == channel, rustc_channel)),
+        _ => None
+    }
+}
+
+/// Like [`is_feature_flaggable()`] but returns `default` if the channel cannot
+/// be determined.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// // Never enable nightly-only code unless nightly is certain.
+/// if rustc::is_feature_flaggable_or(false) {
+///     println!("cargo:rustc-cfg=nightly");
+/// }
+/// ```
+pub fn is_feature_flaggable_or(default: bool) -> bool {
+    is_feature_flaggable().unwrap_or(default)
+}
+
+/// Returns how a `RUSTC_BOOTSTRAP` of `value` affects the crate `krate`:
+/// `Some(true)` if unstable features are force-enabled, `Some(false)` if they
+/// are force-disabled, and `None` if the channel decides.
+fn bootstrap_override(value: Option<&str>, krate: Option<&str>) -> Option<bool> {
+    match (value.map(|v| v.trim()), krate) {
+        (Some("1"), _) => Some(true),
+        (Some("-1"), _) => Some(false),
+        (Some(crates), Some(krate)) => {
+            let krate = krate.replace("-", "_");
+            match crates.split(',').any(|c| c.trim().replace("-", "_") == krate) {
+                true => Some(true),
+                false => None
+            }
+        }
+        _ => None
+    }
+}
+
+/// Checks whether unstable features and `-Z` options would be accepted by the
+/// running or installed `rustc`, accounting for `RUSTC_BOOTSTRAP`.
+///
+/// **Please see the note on [feature detection](crate#feature-detection).**
+///
+/// Without `RUSTC_BOOTSTRAP`, unstable features are available only on the
+/// nightly and dev channels, as reported by [`is_feature_flaggable()`]. The
+/// `RUSTC_BOOTSTRAP` environment variable, which `rustc` reads at compile
+/// time, changes this:
+///
+///   * `RUSTC_BOOTSTRAP=1` enables unstable features on every channel,
+///     including stable and beta.
+///   * `RUSTC_BOOTSTRAP=-1` disables unstable features on every channel,
+///     including nightly.
+///   * A comma-separated list of crate names enables unstable features only
+///     for the listed crates. The crate is taken to be the package being built,
+///     `CARGO_PKG_NAME`, as set by Cargo for build scripts, with `-` read as
+///     `_`.
+///
+/// The variable is read from the environment of the current process, so the
+/// result is accurate when `rustc` inherits that environment, as it does when
+/// Cargo runs a build script and then compiles the crate.
+///
+/// Relying on `RUSTC_BOOTSTRAP` is risky. It exists to build the compiler
+/// itself, carries no stability guarantees, and is set by the user rather than
+/// the crate: a dependent who sets it for an unrelated reason also opts every
+/// crate checking this function into unstable features that may break on any
+/// stable release. Prefer [`is_feature_flaggable()`], which ignores it, unless
+/// the crate's users explicitly opt in via `RUSTC_BOOTSTRAP`.
+///
+/// If the channel could not be determined, returns `None`. Otherwise returns
+/// `true` if unstable features and options would be accepted and `false`
+/// otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::unstable_options_available() {
+///     println!("cargo:rustc-cfg=unstable");
+/// }
+/// ```
+pub fn unstable_options_available() -> Option<bool> {
+    let bootstrap = env::var("RUSTC_BOOTSTRAP").ok();
+    let krate = env::var("CARGO_PKG_NAME").ok();
+    Channel::read().map(|channel| {
+        let value = bootstrap.as_ref().map(|v| &**v);
+        bootstrap_override(value, krate.as_ref().map(|k| &**k))
+            .unwrap_or(channel.supports_features())
+    })
+}
+
+/// Checks whether the running or installed `rustc` supports
+/// `#[cfg(version("1.70"))]`-style gating directly in source.
+///
+/// **Please see the note on [feature detection](crate#feature-detection).**
+///
+/// As of this writing, `cfg(version(..))` is unstable: it is available from
+/// `1.45.0` on nightly and dev channels with `#![feature(cfg_version)]` and on
+/// no stable or beta release. Crates that must build on stable should continue
+/// to gate on the version from a build script instead.
+///
+/// If the version could not be determined, returns `None`. Otherwise returns
+/// `true` if `cfg(version(..))` can be used, given the feature flag, and
+/// `false` otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::supports_cfg_version() {
+///     println!("cargo:rustc-cfg=has_cfg_version");
+/// }
+/// ```
+pub fn supports_cfg_version() -> Option<bool> {
+    Version::read().map(|version| {
+        version.channel().supports_features() && version.at_least("1.45.0")
+    })
+}
+
+/// Reads the release date of the running or installed `rustc` if it is a
+/// nightly or dev release.
+///
+/// Returns `None` if the channel or release date could not be determined or if
+/// the channel is stable or beta. This is useful for gating on the age of a
+/// nightly compiler without also matching stable and beta releases.
+///
+/// **Please see the note on [feature detection](crate#feature-detection).**
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// match rustc::nightly_date() {
+///     Some(date) if date.at_least("2023-06-01") => "A recent nightly.",
+///     Some(_) => "An old nightly.",
+///     None => "Not a nightly, or the date is unknown."
+/// };
+/// ```
+pub fn nightly_date() -> Option<Date> {
+    match get_version_and_date() {
+        Some((Some(version), Some(date))) => match Channel::parse(&version) {
+            Some(ref channel) if channel.supports_features() => Date::parse(&date),
+            _ => None
+        },
+        _ => None
+    }
+}
+
+/// Prints `cargo:rustc-cfg={cfg}` if the running or installed `rustc` is a
+/// nightly or dev release dated on or after `min_date` and returns whether it
+/// did.
+///
+/// **Please see the note on [feature detection](crate#feature-detection).**
+///
+/// The channel and date are read once, as in [`nightly_date()`]. If the
+/// toolchain is stable or beta, is older than `min_date`, or its channel or
+/// date cannot be determined, or if `min_date` cannot be parsed, nothing is
+/// printed and `false` is returned.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if !rustc::enable_cfg_if_nightly_since("nightly_let_chains", "2022-07-01") {
+///     println!("cargo:warning=let chains disabled");
+/// }
+/// ```
+pub fn enable_cfg_if_nightly_since(cfg: &str, min_date: &str) -> bool {
+    let enabled = match (nightly_date(), Date::parse(min_date)) {
+        (Some(date), Some(min_date)) => date >= min_date,
+        _ => false
+    };
+
+    if enabled {
+        println!("cargo:rustc-cfg={}", cfg);
+    }
+
+    enabled
+}
+
+/// Conservatively checks whether the running or installed `rustc` can use a
+/// nightly feature that is known to work as expected as of version
+/// `min_version` and release date `min_date`.
+///
+/// **Please see the note on [feature detection](crate#feature-detection).**
+///
+/// Returns `true` only if the channel supports feature flags (see
+/// [`is_feature_flaggable()`]), the version is at least `min_version`, and
+/// the release date is on or after `min_date`. Requiring both guards against
+/// enabling a feature on a toolchain that predates its current form. All three
+/// attributes are determined from a single invocation of `rustc`.
+///
+/// If the version, channel, or date cannot be determined, or if `min_version`
+/// or `min_date` cannot be parsed, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::can_use_nightly_feature("1.58.0", "2021-11-15") {
+///     println!("cargo:rustc-cfg=nightly_feature");
+/// }
+/// ```
+pub fn can_use_nightly_feature(min_version: &str, min_date: &str) -> Option<bool> {
+    match (triple(), Version::parse(min_version), Date::parse(min_date)) {
+        (Some((version, channel, date)), Some(min_version), Some(min_date)) => {
+            Some(channel.supports_features()
+                && version.stripped() >= min_version.stripped()
+                && date >= min_date)
+        }
+        _ => None
+    }
+}
+
+/// Checks whether the running or installed `rustc` is either a stable or beta
+/// release of at least `min_stable` or a nightly or dev release dated on or
+/// after `min_nightly_date`.
+///
+/// This encodes the common availability check for a feature that was
+/// stabilized in `min_stable` but usable on nightly, behind a feature flag,
+/// since `min_nightly_date`. A beta release of `min_stable` is accepted as it
+/// contains every feature stabilized in that version. Pre-release tags in
+/// `min_stable` are ignored. The version, channel, and date are determined from
+/// a single invocation of `rustc`.
+///
+/// If the version, channel, or date cannot be determined, or if `min_stable`
+/// or `min_nightly_date` cannot be parsed, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::accept_stable_or_nightly("1.70.0", "2023-04-01") {
+///     println!("cargo:rustc-cfg=has_feature");
+/// }
+/// ```
+pub fn accept_stable_or_nightly(min_stable: &str, min_nightly_date: &str) -> Option<bool> {
+    match (triple(), Version::parse(min_stable), Date::parse(min_nightly_date)) {
+        (Some((version, channel, date)), Some(min_stable), Some(min_date)) => {
+            Some(match channel.supports_features() {
+                true => date >= min_date,
+                false => version.stripped() >= min_stable.stripped(),
+            })
+        }
+        _ => None
+    }
+}
+
+/// Names of well-known language features and the version of `rustc` in which
+/// each was stabilized.
+const STABILIZED: &'static [(&'static str, &'static str)] = &[
+    ("question_mark", "1.13.0"),
+    ("pub_restricted", "1.18.0"),
+    ("i128_type", "1.26.0"),
+    ("conservative_impl_trait", "1.26.0"),
+    ("dyn_trait", "1.27.0"),
+    ("repr_transparent", "1.28.0"),
+    ("global_allocator", "1.28.0"),
+    ("min_const_fn", "1.31.0"),
+    ("try_from", "1.34.0"),
+    ("maybe_uninit", "1.36.0"),
+    ("async_await", "1.39.0"),
+    ("non_exhaustive", "1.40.0"),
+    ("track_caller", "1.46.0"),
+    ("min_const_generics", "1.51.0"),
+    ("capture_disjoint_fields", "1.56.0"),
+    ("const_panic", "1.57.0"),
+    ("format_args_capture", "1.58.0"),
+    ("asm", "1.59.0"),
+    ("destructuring_assignment", "1.59.0"),
+    ("let_else", "1.65.0"),
+    ("generic_associated_types", "1.65.0"),
+    ("async_fn_in_trait", "1.75.0"),
+    ("return_position_impl_trait_in_trait", "1.75.0"),
+    ("inline_const", "1.79.0"),
+    ("associated_type_bounds", "1.79.0"),
+    ("c_unwind", "1.81.0"),
+    ("lint_reasons", "1.81.0"),
+    ("let_chains", "1.88.0"),
+];
+
+/// Returns the version of `rustc` in which the language feature `feature` was
+/// stabilized, according to a small built-in table of well-known features.
+///
+/// `feature` is the name of the feature gate that preceded stabilization, such
+/// as `let_else`. If `feature` is not in the table, returns `None`. This never
+/// invokes `rustc`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+/// use version_check::Version;
+///
+/// assert_eq!(rustc::stabilized_in("let_else"), Version::parse("1.65.0"));
+/// assert_eq!(rustc::stabilized_in("never_type"), None);
+/// ```
+pub fn stabilized_in(feature: &str) -> Option<Version> {
+    STABILIZED.iter()
+        .find(|&&(name, _)| name == feature)
+        .and_then(|&(_, version)| Version::parse(version))
+}
+
+/// Returns the minimum version of `rustc` that supports every language feature
+/// in `names` on stable: the latest version in which any of them was
+/// stabilized, according to [`stabilized_in()`].
+///
+/// This is a pure computation that never invokes `rustc`, useful for deriving
+/// a crate's minimum supported Rust version from the features it uses. If any
+/// feature is not in the table, returns `None`. If `names` is empty, returns
+/// `1.0.0`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+/// use version_check::Version;
+///
+/// let msrv = rustc::min_version_for_features(&["question_mark", "let_else", "dyn_trait"]);
+/// assert_eq!(msrv, Version::parse("1.65.0"));
+///
+/// assert_eq!(rustc::min_version_for_features(&["let_else", "never_type"]), None);
+/// ```
+pub fn min_version_for_features(names: &[&str]) -> Option<Version> {
+    let mut min_version = Version::from_mmp(1, 0, 0);
+    for name in names {
+        match stabilized_in(name) {
+            Some(version) if version > min_version => min_version = version,
+            Some(_) => continue,
+            None => return None
+        }
+    }
+
+    Some(min_version)
+}
+
+/// Checks whether the running or installed `rustc` supports the language
+/// feature `feature` on stable, as determined by [`stabilized_in()`].
+///
+/// Unlike [`supports_feature()`], this requires no feature flags and compiles
+/// no probes: the version of `rustc` is compared against the version in which
+/// `feature` was stabilized. Pre-release tags are ignored, as with
+/// [`is_min_version()`].
+///
+/// If `feature` is not in the built-in table or the version cannot be
+/// determined, returns `None`. Otherwise returns `true` if `rustc` is at least
+/// the version `feature` was stabilized in and `false` otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::has_stable_feature("let_else") {
+///     println!("cargo:rustc-cfg=has_let_else");
+/// }
+/// ```
+pub fn has_stable_feature(feature: &str) -> Option<bool> {
+    match (Version::read(), stabilized_in(feature)) {
+        (Some(rustc_ver), Some(stable_ver)) => Some(rustc_ver.stripped() >= stable_ver),
+        _ => None
+    }
+}
+
+/// Checks whether the running or installed `rustc` supports closures that
+/// capture disjoint fields, as opposed to entire variables.
+///
+/// Disjoint captures were stabilized in `1.56.0` as part of the 2021 edition
+/// and apply only to crates compiled with edition 2021 or later. This checks
+/// whether the compiler supports them, as in
+/// [`has_stable_feature("capture_disjoint_fields")`](has_stable_feature()),
+/// not whether the crate being built uses a suitable edition.
+///
+/// If the version cannot be determined, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::supports_disjoint_closure_captures() {
+///     println!("cargo:rustc-cfg=has_disjoint_captures");
+/// }
+/// ```
+pub fn supports_disjoint_closure_captures() -> Option<bool> {
+    has_stable_feature("capture_disjoint_fields")
+}
+
+/// A selection of stable `rustc` releases and the date of each release.
+const RELEASE_DATES: &'static [(&'static str, &'static str)] = &[
+    ("1.0.0", "2015-05-15"),
+    ("1.13.0", "2016-11-10"),
+    ("1.18.0", "2017-06-08"),
+    ("1.26.0", "2018-05-10"),
+    ("1.31.0", "2018-12-06"),
+    ("1.39.0", "2019-11-07"),
+    ("1.45.0", "2020-07-16"),
+    ("1.51.0", "2021-03-25"),
+    ("1.56.0", "2021-10-21"),
+    ("1.60.0", "2022-04-07"),
+    ("1.65.0", "2022-11-03"),
+    ("1.70.0", "2023-06-01"),
+    ("1.75.0", "2023-12-28"),
+    ("1.80.0", "2024-07-25"),
+    ("1.85.0", "2025-02-20"),
+    ("1.88.0", "2025-06-26"),
+    ("1.90.0", "2025-09-18"),
+    ("1.95.0", "2026-04-16"),
+];
+
+/// Checks whether the date reported by the running or installed `rustc` is
+/// plausible for its version, according to a small built-in table of stable
+/// release dates.
+///
+/// This is a diagnostic for relabeled or repackaged toolchains. Releases ship
+/// every six weeks: a `stable` or `beta` build of a version is expected to be
+/// dated in the seven weeks before that version's release, and a `nightly`
+/// build between thirteen and five weeks before it. Returns `false` if the
+/// date falls outside of the expected window.
+///
+/// Returns `None` if the version, channel, or date cannot be determined, if
+/// the channel is `dev`, or if the version is not in the table. Only `x.y.0`
+/// releases are listed, so point releases like `1.70.1` return `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(false) = rustc::date_matches_version() {
+///     println!("cargo:warning=rustc's date does not match its version");
+/// }
+/// ```
+pub fn date_matches_version() -> Option<bool> {
+    triple().and_then(|(version, channel, date)| date_matches(&version, &channel, &date))
+}
+
+fn date_matches(version: &Version, channel: &Channel, date: &Date) -> Option<bool> {
+    let release = RELEASE_DATES.iter()
+        .find(|&&(v, _)| Version::parse(v) == Some(version.stripped()))
+        .and_then(|&(_, date)| Date::parse(date));
+
+    let release = match release {
+        Some(release) => release,
+        None => return None
+    };
+
+    let days_before = release.days_since(date);
+    if channel.is_stable() || channel.is_beta() {
+        Some(days_before >= 0 && days_before <= 49)
+    } else if channel.is_nightly() {
+        Some(days_before >= 35 && days_before <= 91)
+    } else {
+        None
+    }
+}
+
+/// Checks whether the running or installed `rustc` supports `feature`.
+///
+/// **Please see the note on [feature detection](crate#feature-detection).**
+///
+/// Returns _true_ _iff_ [`is_feature_flaggable()`] returns `true` _and_ the
+/// feature is not disabled via exclusion in `allow-features` via `RUSTFLAGS` or
+/// `CARGO_ENCODED_RUSTFLAGS`. If the version could not be determined, returns
+/// `None`. On the stable and beta channels, if the `VERSION_CHECK_WARN_FEATURES`
+/// environment variable is set to a non-empty value, a `cargo:warning` is
+/// printed nudging away from gating on unstable features.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::supports_feature("doc_cfg") {
+///    println!("cargo:rustc-cfg=has_doc_cfg");
+/// }
+/// ```
+pub fn supports_feature(feature: &str) -> Option<bool> {
+    match is_feature_flaggable() {
+        Some(true) => { /* continue */ }
+        Some(false) => {
+            warn_unflaggable(&[feature]);
+            return Some(false);
+        }
+        None => return None,
+    }
+
+    Some(is_allowed_by_rustflags(feature))
+}
+
+/// Prints a `cargo:warning` that `features` were queried on a channel that
+/// does not support feature flags if `VERSION_CHECK_WARN_FEATURES` is set to a
+/// non-empty value.
+fn warn_unflaggable(features: &[&str]) {
+    if !env::var_os("VERSION_CHECK_WARN_FEATURES").map_or(false, |v| &*v != OsStr::new("")) {
+        return;
+    }
+
+    let mut names = String::new();
+    for (i, feature) in features.iter().enumerate() {
+        if i > 0 {
+            names.push_str(", ");
+        }
+
+        names.push_str(&format!("`{}`", feature));
+    }
+
+    println!("cargo:warning=unstable features are unavailable on stable and beta rustc; \
+        consider gating {} behind an opt-in crate feature or cfg", names);
+}
+
+/// Returns `false` if `feature` is disabled via exclusion in `allow-features`
+/// via `RUSTFLAGS` or `CARGO_ENCODED_RUSTFLAGS` and `true` otherwise.
+fn is_allowed_by_rustflags(feature: &str) -> bool {
+    let env_flags = env::var_os("CARGO_ENCODED_RUSTFLAGS")
+        .map(|flags| (flags, '\x1f'))
+        .or_else(|| env::var_os("RUSTFLAGS").map(|flags| (flags, ' ')));
+
+    if let Some((flags, delim)) = env_flags {
+        const ALLOW_FEATURES: &'static str = "allow-features=";
+
+        let rustflags = flags.to_string_lossy();
+        let allow_features = rustflags.split(delim)
+            .map(|flag| flag.trim_left_matches("-Z").trim())
+            .filter(|flag| flag.starts_with(ALLOW_FEATURES))
+            .map(|flag| &flag[ALLOW_FEATURES.len()..]);
+
+        if let Some(allow_features) = allow_features.last() {
+            return allow_features.split(',').any(|f| f.trim() == feature);
+        }
+    }
+
+    // If there are no `RUSTFLAGS` or `CARGO_ENCODED_RUSTFLAGS` or they don't
+    // contain an `allow-features` flag, assume compiler allows all features.
+    true
+}
+
+/// Returns the output of `rustc --print cfg` for `target`, or for the host if
+/// `target` is `None`.
+///
+/// Each line of the output is a `cfg` that is set for the target, either as a
+/// bare name, like `unix`, or as a name-value pair, like
+/// `target_pointer_width="64"`. In build scripts, the target being compiled
+/// for is available in the `TARGET` environment variable.
+///
+/// If `rustc` cannot be executed or does not recognize `target`, returns
+/// `None`. Printing the `cfg` for a target does not require the target's
+/// standard library to be installed.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// let target = std::env::var("TARGET").ok();
+/// if let Some(cfg) = rustc::print_cfg(target.as_ref().map(|s| &**s)) {
+///     if cfg.lines().any(|line| line == "unix") {
+///         println!("cargo:rustc-cfg=unix_target");
+///     }
+/// }
+/// ```
+pub fn print_cfg(target: Option<&str>) -> Option<String> {
+    rustc_print("cfg", target)
+}
+
+/// Checks whether the running or installed `rustc` supports compiling for
+/// the target triple `target`.
+///
+/// A target is supported if it is listed in the output of `rustc --print
+/// target-list`. Note that a supported target's standard library may not be
+/// installed.
+///
+/// If `rustc` cannot be executed, returns `None`. Otherwise returns `true` if
+/// the target is supported and `false` otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::supports_target("wasm32-unknown-unknown") {
+///     println!("cargo:rustc-cfg=has_wasm_target");
+/// }
+/// ```
+pub fn supports_target(target: &str) -> Option<bool> {
+    rustc_print("target-list", None)
+        .map(|list| list.lines().any(|line| line.trim() == target))
+}
+
+/// Returns the names of the target features `target`, or the host if `target`
+/// is `None`, supports, as listed by `rustc --print target-features`.
+///
+/// Only the first list in the output is read: on current compilers, the
+/// features `rustc` itself supports, which are the features usable in
+/// `cfg(target_feature = "...")` and `#[target_feature(enable = "...")]`. The
+/// code-generation features LLVM additionally lists are excluded. A listed
+/// feature is supported by the compiler for the target; whether the machine
+/// running the code has it must still be checked at runtime or enabled via
+/// `-C target-feature`.
+///
+/// Returns `None` if `rustc` cannot be executed or fails, as it does for an
+/// unknown target.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// let target = std::env::var("TARGET").ok();
+/// let features = rustc::supported_target_features(target.as_ref().map(|s| &**s));
+/// if features.map_or(false, |features| features.iter().any(|f| f == "avx2")) {
+///     println!("cargo:rustc-cfg=avx2_available");
+/// }
+/// ```
+pub fn supported_target_features(target: Option<&str>) -> Option<Vec<String>> {
+    rustc_print("target-features", target).map(|s| target_features_from(&s))
+}
+
+/// Parses the names of the features in the first list of `rustc --print
+/// target-features` output: the first run of indented `name - description`
+/// lines.
+fn target_features_from(s: &str) -> Vec<String> {
+    s.lines()
+        .skip_while(|line| !line.starts_with(|c: char| c.is_whitespace()))
+        .take_while(|line| line.starts_with(|c: char| c.is_whitespace()))
+        .filter_map(|line| line.split(|c: char| c.is_whitespace()).find(|s| !s.is_empty()))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// Checks whether a precompiled standard library for `target`, or the host if
+/// `target` is `None`, is installed for the running or installed `rustc`.
+///
+/// The target's library directory is read from `rustc --print target-libdir`
+/// or, on compilers that don't support it, derived from `rustc --print
+/// sysroot` as `{sysroot}/lib/rustlib/{target}/lib`. The standard library is
+/// present if the directory contains a `libstd-*.rlib`. Targets without one,
+/// either because the target's components are not installed or because the
+/// target is `no_std`-only, require `-Z build-std` to use `std`.
+///
+/// If the library directory cannot be determined or read, returns `None`.
+/// Otherwise returns `true` if `libstd` is present and `false` otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// let target = std::env::var("TARGET").ok();
+/// if let Some(false) = rustc::has_prebuilt_std(target.as_ref().map(|s| &**s)) {
+///     println!("cargo:warning=no prebuilt std for this target; use -Z build-std");
+/// }
+/// ```
+pub fn has_prebuilt_std(target: Option<&str>) -> Option<bool> {
+    let libdir = rustc_print("target-libdir", target)
+        .map(|libdir| PathBuf::from(libdir.trim()))
+        .or_else(|| {
+            // Passing `--target` makes `rustc` reject unknown targets.
+            let target = match target {
+                Some(target) => target.to_string(),
+                None => match rustc_verbose_version().ok()
+                    .and_then(|s| verbose_field(&s, "host").map(|h| h.to_string()))
+                {
+                    Some(host) => host,
+                    None => return None
+                }
+            };
+
+            rustc_print("sysroot", Some(&target)).map(|sysroot| {
+                Path::new(sysroot.trim()).join("lib").join("rustlib").join(target).join("lib")
+            })
+        });
+
+    libdir.and_then(|libdir| has_std_rlib(&libdir))
+}
+
+/// Checks whether building for `target` requires rebuilding the standard
+/// library from source, as with Cargo's `-Z build-std`.
+///
+/// This combines [`supports_target()`] and [`has_prebuilt_std()`]: returns
+/// `false` if a precompiled standard library for `target` is installed and
+/// `true` if `rustc` supports `target` but no precompiled standard library
+/// for it is installed. If `rustc` does not support `target`, or either
+/// condition cannot be determined, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::needs_build_std("thumbv7em-none-eabihf") {
+///     println!("cargo:warning=thumbv7em-none-eabihf requires -Z build-std");
+/// }
+/// ```
+pub fn needs_build_std(target: &str) -> Option<bool> {
+    match supports_target(target) {
+        Some(true) => has_prebuilt_std(Some(target)).map(|prebuilt| !prebuilt),
+        _ => None
+    }
+}
+
+/// Returns whether the directory `dir` contains a `libstd-*.rlib`: `false` if
+/// `dir` does not exist and `None` if it cannot be read.
+fn has_std_rlib(dir: &Path) -> Option<bool> {
+    match fs::read_dir(dir) {
+        Ok(entries) => Some(entries.filter_map(|entry| entry.ok()).any(|entry| {
+            let path = entry.path();
+            let name = path.file_name().map_or("".into(), |name| name.to_string_lossy());
+            name.starts_with("libstd-") && name.ends_with(".rlib")
+        })),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Some(false),
+        Err(_) => None
+    }
+}
+
+/// Returns the default target triple of the running or installed `rustc`: the
+/// target it compiles for when no `--target` is given.
+///
+/// The triple is read from `rustc --print host-tuple`, supported by newer
+/// compilers. If that fails, it is read from the `host:` line of `rustc
+/// --verbose --version` instead. Note that in a build script, Cargo's `TARGET`
+/// environment variable holds the target actually being built for, which
+/// differs from the default target when cross-compiling.
+///
+/// If neither source is available, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// let target = std::env::var("TARGET").ok();
+/// if target.is_some() && target != rustc::default_target() {
+///     println!("cargo:warning=cross-compiling");
+/// }
+/// ```
+pub fn default_target() -> Option<String> {
+    let printed = rustc_print("host-tuple", None)
+        .map(|tuple| tuple.trim().to_string())
+        .and_then(|tuple| match tuple.is_empty() {
+            true => None,
+            false => Some(tuple)
+        });
+
+    printed.or_else(|| {
+        rustc_verbose_version().ok()
+            .and_then(|s| verbose_field(&s, "host").map(|host| host.to_string()))
+    })
+}
+
+/// Parses the version of the crate being built from the `CARGO_PKG_VERSION`
+/// environment variable, which Cargo sets when running build scripts.
+///
+/// This is a convenience for correlating the crate's version with that of
+/// `rustc` using the same [`Version`] type; it never invokes `rustc`. Crate
+/// versions are parsed as in [`Version::parse()`], which only knows `rustc`'s
+/// pre-release tags: a semver pre-release such as `0.5.0-rc.1` reads as the
+/// release `0.5.0`.
+///
+/// Returns `None` if `CARGO_PKG_VERSION` is not set or cannot be parsed.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let (Some(krate), Some(compiler)) = (rustc::crate_version(), rustc::Version::read()) {
+///     println!("cargo:warning=building {} with rustc {}", krate, compiler);
+/// }
+/// ```
+pub fn crate_version() -> Option<Version> {
+    env::var("CARGO_PKG_VERSION").ok().and_then(|version| Version::parse(&version))
+}
+
+/// Returns the pointer width, in bits, of `target`, or of the host if `target`
+/// is `None`.
+///
+/// The width is read from the output of [`print_cfg()`]. When invoked from a
+/// build script with `target` equal to Cargo's `TARGET`, the width is instead
+/// read from the `CARGO_CFG_TARGET_POINTER_WIDTH` environment variable set by
+/// Cargo, avoiding the invocation of `rustc`.
+///
+/// If the width cannot be determined, returns `None`. Otherwise returns the
+/// width, typically `32` or `64`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// let target = std::env::var("TARGET").ok();
+/// if let Some(64) = rustc::target_pointer_width(target.as_ref().map(|s| &**s)) {
+///     println!("cargo:rustc-cfg=target_is_64_bit");
+/// }
+/// ```
+pub fn target_pointer_width(target: Option<&str>) -> Option<u32> {
+    target_cfg_value(target, "target_pointer_width", "CARGO_CFG_TARGET_POINTER_WIDTH")
+        .and_then(|width| width.parse().ok())
+}
+
+/// Checks whether `target`, or the host if `target` is `None`, supports
+/// atomic operations of width `width`, as in `cfg(target_has_atomic = "64")`.
+///
+/// `width` is one of the values of `target_has_atomic`: `"8"`, `"16"`, `"32"`,
+/// `"64"`, `"128"`, or `"ptr"`. The values are read from the output of
+/// [`print_cfg()`]. When invoked from a build script with `target` equal to
+/// Cargo's `TARGET`, the values are instead read from the
+/// `CARGO_CFG_TARGET_HAS_ATOMIC` environment variable set by Cargo, avoiding
+/// the invocation of `rustc`.
+///
+/// `target_has_atomic` was stabilized in `1.60.0`; earlier stable and beta
+/// compilers do not print it. If the `cfg` cannot be read, or if it has no
+/// `target_has_atomic` values and `rustc` is older than `1.60.0`, returns
+/// `None`. Otherwise returns `true` if `width` is supported and `false`
+/// otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// let target = std::env::var("TARGET").ok();
+/// if let Some(true) = rustc::target_has_atomic("64", target.as_ref().map(|s| &**s)) {
+///     println!("cargo:rustc-cfg=has_atomic_u64");
+/// }
+/// ```
+pub fn target_has_atomic(width: &str, target: Option<&str>) -> Option<bool> {
+    // In build scripts, Cargo sets `CARGO_CFG_*` for `TARGET`: skip the spawn.
+    if let (Some(target), Ok(cargo_target)) = (target, env::var("TARGET")) {
+        if target == cargo_target {
+            if let Ok(widths) = env::var("CARGO_CFG_TARGET_HAS_ATOMIC") {
+                return Some(widths.split(',').any(|w| w.trim() == width));
+            }
+        }
+    }
+
+    let cfg = match print_cfg(target) {
+        Some(cfg) => cfg,
+        None => return None
+    };
+
+    let widths = cfg_values(&cfg, "target_has_atomic");
+    if widths.is_empty() && is_min_version("1.60.0") != Some(true) {
+        return None;
+    }
+
+    Some(widths.contains(&width))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::version_and_date_from_rustc_version;
+    use super::version_and_date_from_rustc_verbose_version;
+    use super::{cfg_value, cfg_values, verbose_field, is_trusted_binary};
+    use super::official_build_from_rustc_verbose_version;
+    use super::dev_build_from_rustc_verbose_version;
+    use super::{is_rustup_proxy, is_rustup_managed_at, wrapped_rustc, wrapped_program};
+    use super::toolchains_from_rustup_list;
+    use super::toolchain_name_parts;
+    use super::has_std_rlib;
+    use super::{RELEASE_DATES, date_matches};
+    use super::beta_counts;
+    use super::target_features_from;
+    use super::{is_apple_triple, is_windows_msvc_triple};
+    use super::{split_command_line, split_program, rustc_at, rustc_path_from};
+    use super::override_verbose_version;
+    use super::{summary_from_rustc_verbose_version, summary_json_from_rustc_verbose_version};
+    use super::fields_from_rustc_verbose_version;
+    use super::llvm_major_from_rustc_verbose_version;
+    use super::incremental_from;
+    use super::pre_release_tag;
+    use super::{STABILIZED, spec_satisfied_by, bootstrap_override};
+    use super::{rustc_print_at, rustc_print_all_at, rustc_output_with, print_args};
+    use RustcError;
+
+    macro_rules! check_parse {
+        (@ $f:expr, $s:expr => $v:expr, $d:expr) => ({
+            if let (Some(v), d) = $f(&$s) {
+                let e_d: Option<&str> = $d.into();
+                assert_eq!((v, d), ($v.to_string(), e_d.map(|s| s.into())));
+            } else {
+                panic!("{:?} didn't parse for version testing.", $s);
+            }
+        });
+        ($f:expr, $s:expr => $v:expr, $d:expr) => ({
+            let warn = "warning: invalid logging spec 'warning', ignoring it";
+            let warn2 = "warning: sorry, something went wrong :(sad)";
+            check_parse!(@ $f, $s => $v, $d);
+            check_parse!(@ $f, &format!("{}\n{}", warn, $s) => $v, $d);
+            check_parse!(@ $f, &format!("{}\n{}", warn2, $s) => $v, $d);
+            check_parse!(@ $f, &format!("{}\n{}\n{}", warn, warn2, $s) => $v, $d);
+            check_parse!(@ $f, &format!("{}\n{}\n{}", warn2, warn, $s) => $v, $d);
+        })
+    }
+
+    macro_rules! check_terse_parse {
+        ($($s:expr => $v:expr, $d:expr,)+) => {$(
+            check_parse!(version_and_date_from_rustc_version, $s => $v, $d);
+        )+}
+    }
+
+    macro_rules! check_verbose_parse {
+        ($($s:expr => $v:expr, $d:expr,)+) => {$(
+            check_parse!(version_and_date_from_rustc_verbose_version, $s => $v, $d);
+        )+}
+    }
+
+    #[test]
+    fn test_version_parse() {
+        check_terse_parse! {
+            "rustc 1.18.0" => "1.18.0", None,
+            "rustc 1.8.0" => "1.8.0", None,
+            "rustc 1.20.0-nightly" => "1.20.0-nightly", None,
+            "rustc 1.20" => "1.20", None,
+            "rustc 1.3" => "1.3", None,
+            "rustc 1" => "1", None,
+            "rustc 1.5.1-beta" => "1.5.1-beta", None,
+            "rustc 1.20.0 (2017-07-09)" => "1.20.0", Some("2017-07-09"),
+            "rustc 1.20.0-dev (2017-07-09)" => "1.20.0-dev", Some("2017-07-09"),
+            "rustc 1.20.0-nightly (d84693b93 2017-07-09)" => "1.20.0-nightly", Some("2017-07-09"),
+            "rustc 1.20.0 (d84693b93 2017-07-09)" => "1.20.0", Some("2017-07-09"),
+            "rustc 1.30.0-nightly (3bc2ca7e4 2018-09-20)" => "1.30.0-nightly", Some("2018-09-20"),
+        };
+    }
+
+    #[test]
+    fn test_built_date_parse() {
+        check_terse_parse! {
+            "rustc 1.0.0 (a59de37e9 2015-05-13) (built 2015-05-14)" => "1.0.0", Some("2015-05-13"),
+            "rustc 1.0.0 (built 2015-05-14) (a59de37e9 2015-05-13)" => "1.0.0", Some("2015-05-13"),
+            "rustc 1.0.0 (built 2015-05-14)" => "1.0.0", None,
+        };
+
+        check_verbose_parse! {
+            // Without a `commit-date`, the header's commit date is used.
+            "rustc 1.0.0 (a59de37e9 2015-05-13) (built 2015-05-14)\n\
+                build-date: 2015-05-14\n\
+                release: 1.0.0" => "1.0.0", Some("2015-05-13"),
+
+            // The `release` line wins over the header's version.
+            "rustc 1.0.0 (a59de37e9 2015-05-13) (built 2015-05-14)\n\
+                commit-date: 2015-05-13\n\
+                build-date: 2015-05-14\n\
+                release: 1.0.1" => "1.0.1", Some("2015-05-13"),
+
+            // An unknown commit date is never replaced by the build date.
+            "rustc 1.0.0 (built 2015-05-14)\n\
+                commit-date: unknown\n\
+                build-date: 2015-05-14\n\
+                release: 1.0.0" => "1.0.0", None,
+        };
+    }
+
+    #[test]
+    fn test_verbose_version_parse() {
+        check_verbose_parse! {
+            // Warnings interleaved by a wrapper are skipped wherever they appear.
+            "rustc 1.70.0 (90c541806 2023-05-31)\n\
+                binary: rustc\n\
+                commit-date: 2023-05-31\n\
+                host: x86_64-unknown-linux-gnu\n\
+                warning: the `sccache` cache is full\n\
+                  = note: entries will be evicted\n\
+                release: 1.70.0" => "1.70.0", Some("2023-05-31"),
+
+            "warning: using a custom toolchain\n\
+                rustc 1.70.0-nightly (90c541806 2023-04-20)\n\
+                warning: rustc 1.60.0 is deprecated\n\
+                commit-date: 2023-04-20\n\
+                warning: commit-date: 1999-01-01 is bogus\n\
+                release: 1.70.0-nightly\n\
+                warning: done" => "1.70.0-nightly", Some("2023-04-20"),
+
+            // A hash-less header: the date is the header's only parenthetical.
+            "rustc 1.20.0 (2017-07-09)\n\
+                binary: rustc\n\
+                host: x86_64-unknown-linux-gnu\n\
+                release: 1.20.0" => "1.20.0", Some("2017-07-09"),
+
+            "rustc 1.20.0-nightly (2017-07-09)\n\
+                binary: rustc\n\
+                commit-date: 2017-07-09\n\
+                host: x86_64-unknown-linux-gnu\n\
+                release: 1.20.0-nightly" => "1.20.0-nightly", Some("2017-07-09"),
+
+            "rustc 1.0.0 (a59de37e9 2015-05-13) (built 2015-05-14)\n\
+                binary: rustc\n\
+                commit-hash: a59de37e99060162a2674e3ff45409ac73595c0e\n\
+                commit-date: 2015-05-13\n\
+                build-date: 2015-05-14\n\
+                host: x86_64-unknown-linux-gnu\n\
+                release: 1.0.0" => "1.0.0", Some("2015-05-13"),
+
+            "rustc 1.0.0 (a59de37e9 2015-05-13) (built 2015-05-14)\n\
+                commit-hash: a59de37e99060162a2674e3ff45409ac73595c0e\n\
+                commit-date: 2015-05-13\n\
+                build-date: 2015-05-14\n\
+                host: x86_64-unknown-linux-gnu\n\
+                release: 1.0.0" => "1.0.0", Some("2015-05-13"),
+
+            "rustc 1.50.0 (cb75ad5db 2021-02-10)\n\
+                binary: rustc\n\
+                commit-hash: cb75ad5db02783e8b0222fee363c5f63f7e2cf5b\n\
+                commit-date: 2021-02-10\n\
+                host: x86_64-unknown-linux-gnu\n\
+                release: 1.50.0" => "1.50.0", Some("2021-02-10"),
+
+            "rustc 1.52.0-nightly (234781afe 2021-03-07)\n\
+                binary: rustc\n\
+                commit-hash: 234781afe33d3f339b002f85f948046d8476cfc9\n\
+                commit-date: 2021-03-07\n\
+                host: x86_64-unknown-linux-gnu\n\
+                release: 1.52.0-nightly\n\
+                LLVM version: 12.0.0" => "1.52.0-nightly", Some("2021-03-07"),
+
+            "rustc 1.41.1\n\
+                binary: rustc\n\
+                commit-hash: unknown\n\
+                commit-date: unknown\n\
+                host: x86_64-unknown-linux-gnu\n\
+                release: 1.41.1\n\
+                LLVM version: 7.0" => "1.41.1", None,
+
+            "rustc 1.49.0\n\
+                binary: rustc\n\
+                commit-hash: unknown\n\
+                commit-date: unknown\n\
+                host: x86_64-unknown-linux-gnu\n\
+                release: 1.49.0" => "1.49.0", None,
+
+            "rustc 1.50.0 (Fedora 1.50.0-1.fc33)\n\
+                binary: rustc\n\
+                commit-hash: unknown\n\
+                commit-date: unknown\n\
+                host: x86_64-unknown-linux-gnu\n\
+                release: 1.50.0" => "1.50.0", None,
+        };
+    }
+
+    #[test]
+    fn test_verbose_release_wins() {
+        // `release:` is authoritative for the version, even when the header
+        // carries a distribution suffix; `commit-date:` is for the date.
+        check_verbose_parse! {
+            "rustc 1.50.0+dfsg1 (Fedora 1.50.0-1.fc33 2021-02-11)\n\
+                binary: rustc\n\
+                commit-hash: unknown\n\
+                commit-date: unknown\n\
+                host: x86_64-unknown-linux-gnu\n\
+                release: 1.50.0" => "1.50.0", None,
+
+            "rustc 1.50.0-fedora (cb75ad5db 2021-02-10) (Fedora 1.50.0-1.fc33)\n\
+                binary: rustc\n\
+                commit-hash: cb75ad5db02783e8b0222fee363c5f63f7e2cf5b\n\
+                commit-date: 2021-02-10\n\
+                host: x86_64-unknown-linux-gnu\n\
+                release: 1.50.0" => "1.50.0", Some("2021-02-10"),
+
+            "rustc 1.50.0 (Ubuntu 1.50.0+dfsg0ubuntu1 2021-03-01)\n\
+                binary: rustc\n\
+                commit-hash: cb75ad5db02783e8b0222fee363c5f63f7e2cf5b\n\
+                commit-date: 2021-02-10\n\
+                host: x86_64-unknown-linux-gnu\n\
+                release: 1.50.0" => "1.50.0", Some("2021-02-10"),
+
+            "rustc 1.52.0-custom (234781afe 2021-03-07)\n\
+                binary: rustc\n\
+                commit-hash: 234781afe33d3f339b002f85f948046d8476cfc9\n\
+                commit-date: 2021-03-07\n\
                 host: x86_64-unknown-linux-gnu\n\
+                release: 1.52.0-nightly" => "1.52.0-nightly", Some("2021-03-07"),
+        };
+
+        // Without a `commit-date:` line, the header's date is used.
+        check_verbose_parse! {
+            "rustc 1.50.0-fedora (cb75ad5db 2021-02-10)\n\
                 release: 1.50.0" => "1.50.0", Some("2021-02-10"),
+        };
+    }
+
+    #[test]
+    fn test_verbose_field() {
+        let old = "rustc 1.0.0 (a59de37e9 2015-05-13) (built 2015-05-14)\n\
+            binary: rustc\n\
+            commit-hash: a59de37e99060162a2674e3ff45409ac73595c0e\n\
+            commit-date: 2015-05-13\n\
+            build-date: 2015-05-14\n\
+            host: x86_64-unknown-linux-gnu\n\
+            release: 1.0.0";
+
+        let new = "rustc 1.50.0 (cb75ad5db 2021-02-10)\n\
+            binary: rustc\n\
+            commit-hash: cb75ad5db02783e8b0222fee363c5f63f7e2cf5b\n\
+            commit-date: 2021-02-10\n\
+            host: x86_64-unknown-linux-gnu\n\
+            release: 1.50.0";
+
+        assert_eq!(verbose_field(old, "build-date"), Some("2015-05-14"));
+        assert_eq!(verbose_field(old, "commit-date"), Some("2015-05-13"));
+        assert_eq!(verbose_field(old, "host"), Some("x86_64-unknown-linux-gnu"));
+        assert_eq!(verbose_field(new, "build-date"), None);
+        assert_eq!(verbose_field(new, "release"), Some("1.50.0"));
+        assert_eq!(verbose_field("", "release"), None);
+    }
+
+    #[test]
+    fn test_official_build() {
+        let official = "rustc 1.50.0 (cb75ad5db 2021-02-10)\n\
+            binary: rustc\n\
+            commit-hash: cb75ad5db02783e8b0222fee363c5f63f7e2cf5b\n\
+            commit-date: 2021-02-10\n\
+            host: x86_64-unknown-linux-gnu\n\
+            release: 1.50.0";
+
+        let distro = "rustc 1.50.0 (Fedora 1.50.0-1.fc33)\n\
+            binary: rustc\n\
+            commit-hash: unknown\n\
+            commit-date: unknown\n\
+            host: x86_64-unknown-linux-gnu\n\
+            release: 1.50.0";
+
+        assert_eq!(official_build_from_rustc_verbose_version(official), Some(true));
+        assert_eq!(official_build_from_rustc_verbose_version(distro), Some(false));
+        assert_eq!(official_build_from_rustc_verbose_version("rustc 1.50.0"), None);
+    }
+
+    #[test]
+    fn test_dev_build() {
+        let local = "rustc 1.52.0-dev\n\
+            binary: rustc\n\
+            commit-hash: unknown\n\
+            commit-date: unknown\n\
+            host: x86_64-unknown-linux-gnu\n\
+            release: 1.52.0-dev";
+
+        let checkout = "rustc 1.52.0-dev (c1d3b2a4e 2021-03-01)\n\
+            binary: rustc\n\
+            commit-hash: c1d3b2a4e0a1e2f3b4c5d6e7f8a9b0c1d2e3f4a5\n\
+            commit-date: 2021-03-01\n\
+            host: x86_64-unknown-linux-gnu\n\
+            release: 1.52.0-dev";
+
+        let distro = "rustc 1.50.0 (Fedora 1.50.0-1.fc33)\n\
+            binary: rustc\n\
+            commit-hash: unknown\n\
+            commit-date: unknown\n\
+            host: x86_64-unknown-linux-gnu\n\
+            release: 1.50.0";
+
+        assert_eq!(dev_build_from_rustc_verbose_version(local), Some(true));
+        assert_eq!(dev_build_from_rustc_verbose_version(checkout), None);
+        assert_eq!(dev_build_from_rustc_verbose_version(distro), Some(false));
+        assert_eq!(dev_build_from_rustc_verbose_version("rustc 1.50.0-nightly"), Some(false));
+        assert_eq!(dev_build_from_rustc_verbose_version("rustc 1.52.0-dev"), None);
+        assert_eq!(dev_build_from_rustc_verbose_version("garbage"), None);
+    }
+
+    #[test]
+    fn test_target_features() {
+        let output = "Features supported by rustc for this target:\n\
+            \x20   adx                             - Support ADX instructions.\n\
+            \x20   avx2                            - Enable AVX2 instructions.\n\
+            \x20   crt-static                      - Enables C Run-time Libraries.\n\
+            \n\
+            Code-generation features supported by LLVM for this target:\n\
+            \x20   64bit                           - Support 64-bit instructions.\n\
+            \n\
+            Use +feature to enable a feature, or -feature to disable it.\n";
+
+        assert_eq!(target_features_from(output), ["adx", "avx2", "crt-static"]);
+        assert!(target_features_from("").is_empty());
+        assert!(target_features_from("Features supported by rustc for this target:\n").is_empty());
+
+        let features = ::supported_target_features(None).unwrap();
+        assert!(!features.is_empty() && features.iter().all(|f| !f.contains(' ')));
+        assert_eq!(::supported_target_features(Some("not-a-real-target")), None);
+    }
+
+    #[test]
+    fn test_cmp_triples() {
+        use std::cmp::Ordering::*;
+        use {Version, Channel, Date, cmp_triples};
+
+        let t = |v, c, d| (Version::parse(v).unwrap(), c, Date::parse(d).unwrap());
+        let (nightly, beta, stable) = (Channel::nightly(), Channel::beta(), Channel::stable());
+
+        let a = t("1.70.0", stable, "2023-05-31");
+        assert_eq!(cmp_triples(&a, &a), Equal);
+        assert_eq!(cmp_triples(&a, &t("1.69.0", stable, "2023-06-01")), Greater);
+        assert_eq!(cmp_triples(&a, &t("1.70.0-beta", beta, "2023-06-01")), Greater);
+        assert_eq!(cmp_triples(&a, &t("1.71.0-nightly", nightly, "2023-04-20")), Less);
+        assert_eq!(cmp_triples(&a, &t("1.70.0", stable, "2023-06-01")), Less);
+        assert_eq!(cmp_triples(&a, &t("1.70.0", stable, "2023-05-30")), Greater);
+        assert_eq!(cmp_triples(&a, &t("1.70.0", nightly, "2023-05-31")), Greater);
+        assert_eq!(cmp_triples(&t("1.70.0", Channel::dev(), "2023-05-31"), &a), Less);
+    }
+
+    #[test]
+    fn test_assert_build_script_min_version() {
+        ::assert_build_script_min_version("1.0.0");
+        ::assert_build_script_min_version("1.0.0-nightly");
+    }
+
+    #[test]
+    #[should_panic(expected = "or newer")]
+    fn test_assert_build_script_min_version_too_old() {
+        ::assert_build_script_min_version("65535.0.0");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid minimum rustc version")]
+    fn test_assert_build_script_min_version_invalid() {
+        ::assert_build_script_min_version("one.two");
+    }
+
+    #[test]
+    fn test_toolchain_name_parts() {
+        use {Version, Channel, Date};
+
+        let parts = |v: Option<&str>, c: Option<&str>, d: Option<&str>| {
+            (v.and_then(Version::parse), c.and_then(::channel_from_name), d.and_then(Date::parse))
+        };
+
+        assert_eq!(toolchain_name_parts("1.70.0-x86_64-unknown-linux-gnu"),
+            parts(Some("1.70.0"), Some("stable"), None));
+        assert_eq!(toolchain_name_parts("1.70"), parts(Some("1.70.0"), Some("stable"), None));
+        assert_eq!(toolchain_name_parts("nightly-2023-06-01-x86_64-unknown-linux-gnu"),
+            parts(None, Some("nightly"), Some("2023-06-01")));
+        assert_eq!(toolchain_name_parts("beta-2023-05-20"),
+            parts(None, Some("beta"), Some("2023-05-20")));
+        assert_eq!(toolchain_name_parts("nightly-x86_64-unknown-linux-gnu"),
+            parts(None, Some("nightly"), None));
+        assert_eq!(toolchain_name_parts("stable"), parts(None, Some("stable"), None));
+        assert_eq!(toolchain_name_parts("nightly-2023-13-01"), parts(None, Some("nightly"), None));
+        assert_eq!(toolchain_name_parts("stage1"), parts(None, None, None));
+        assert_eq!(toolchain_name_parts("1.x-foo"), parts(None, None, None));
+        assert_eq!(toolchain_name_parts(""), parts(None, None, None));
+        assert_eq!(Channel::from_toolchain_name("1.70.0"), Some(Channel::stable()));
+    }
+
+    #[test]
+    fn test_pre_release_tag() {
+        assert_eq!(pre_release_tag("1.70.0-nightly"), Some("nightly"));
+        assert_eq!(pre_release_tag("1.70.0-beta.2"), Some("beta.2"));
+        assert_eq!(pre_release_tag("1.70.0-custom-build"), Some("custom-build"));
+        assert_eq!(pre_release_tag(" 1.70.0-fedora (abc 2023-05-31)"), Some("fedora"));
+        assert_eq!(pre_release_tag("1.70.0"), None);
+        assert_eq!(pre_release_tag("1.70.0 (Fedora 1.70.0-1.fc38)"), None);
+        assert_eq!(pre_release_tag("1.70.0-"), None);
+        assert_eq!(pre_release_tag(""), None);
+
+        let channel = ::Channel::parse("1.70.0-custom-build");
+        assert_eq!(channel, None);
+        assert_eq!(channel.unwrap_or(::Channel::stable()), ::Channel::stable());
+        assert_eq!(::Version::parse("1.70.0-custom-build"), ::Version::parse("1.70.0"));
+    }
+
+    #[test]
+    fn test_incremental_from() {
+        assert_eq!(incremental_from(Some("0"), Some("debug")), Some(false));
+        assert_eq!(incremental_from(Some("1"), Some("release")), Some(true));
+        assert_eq!(incremental_from(Some(" 1\n"), None), Some(true));
+        assert_eq!(incremental_from(None, Some("debug")), Some(true));
+        assert_eq!(incremental_from(None, Some("release")), Some(false));
+        assert_eq!(incremental_from(Some("yes"), Some("release")), Some(false));
+        assert_eq!(incremental_from(Some(""), Some("debug")), Some(true));
+
+        assert_eq!(incremental_from(None, None), None);
+        assert_eq!(incremental_from(Some("2"), None), None);
+        assert_eq!(incremental_from(None, Some("bench")), None);
+    }
+
+    #[test]
+    fn test_llvm_major() {
+        let llvm = |version: &str| {
+            let s = format!("rustc 1.70.0 (90c541806 2023-05-31)\n\
+                binary: rustc\n\
+                host: x86_64-unknown-linux-gnu\n\
+                release: 1.70.0\n\
+                LLVM version: {}", version);
+
+            llvm_major_from_rustc_verbose_version(&s)
+        };
+
+        assert_eq!(llvm("12.0.0"), Some(12));
+        assert_eq!(llvm("7.0"), Some(7));
+        assert_eq!(llvm("16"), Some(16));
+        assert_eq!(llvm("17.0.2-rust-1.74.0-stable"), Some(17));
+        assert_eq!(llvm("18git"), Some(18));
+        assert_eq!(llvm(""), None);
+        assert_eq!(llvm("unknown"), None);
+        assert_eq!(llvm_major_from_rustc_verbose_version("rustc 1.20.0 (2017-07-09)"), None);
+
+        assert!(::llvm_major().is_some());
+        assert_eq!(::llvm_at_least(0), Some(true));
+        assert_eq!(::llvm_at_least(::std::u64::MAX), Some(false));
+    }
+
+    #[test]
+    fn test_beta_counts() {
+        use Version;
+
+        let check = |rustc: &str, min: &str| {
+            beta_counts(&Version::parse(rustc).unwrap(), &Version::parse(min).unwrap())
+        };
+
+        assert!(check("1.70.0-beta", "1.70.0"));
+        assert!(check("1.70.0-beta.2", "1.70.0"));
+        assert!(check("1.70.0-beta.2", "1.70.0-beta.3"));
+        assert!(check("1.71.0-beta", "1.70.0"));
+        assert!(check("1.70.0", "1.70.0"));
+        assert!(check("1.70.1", "1.70.0"));
+        assert!(check("1.71.0-nightly", "1.70.0"));
+        assert!(check("1.70.0-nightly", "1.70.0-nightly"));
+
+        assert!(!check("1.70.0-nightly", "1.70.0"));
+        assert!(!check("1.70.0-dev", "1.70.0"));
+        assert!(!check("1.70.0-beta", "1.70.1"));
+        assert!(!check("1.69.0", "1.70.0"));
+    }
+
+    #[test]
+    fn test_date_matches() {
+        use {Version, Channel, Date};
+
+        let check = |version: &str, date: &str| {
+            let channel = Channel::parse(version).unwrap();
+            let version = Version::parse(version).unwrap();
+            date_matches(&version, &channel, &Date::parse(date).unwrap())
+        };
+
+        assert_eq!(check("1.70.0", "2023-05-31"), Some(true));
+        assert_eq!(check("1.0.0", "2015-05-13"), Some(true));
+        assert_eq!(check("1.95.0", "2026-04-14"), Some(true));
+        assert_eq!(check("1.70.0-beta.2", "2023-04-25"), Some(true));
+        assert_eq!(check("1.70.0-nightly", "2023-04-20"), Some(true));
+        assert_eq!(check("1.70.0-nightly", "2023-03-09"), Some(true));
+
+        assert_eq!(check("1.70.0", "2023-06-02"), Some(false));
+        assert_eq!(check("1.70.0", "2021-05-31"), Some(false));
+        assert_eq!(check("1.70.0-beta", "2023-03-09"), Some(false));
+        assert_eq!(check("1.70.0-nightly", "2023-05-31"), Some(false));
+        assert_eq!(check("1.70.0-nightly", "2023-01-01"), Some(false));
+
+        assert_eq!(check("1.70.1", "2023-06-01"), None);
+        assert_eq!(check("1.71.0", "2023-07-13"), None);
+        assert_eq!(check("1.70.0-dev", "2023-05-31"), None);
+
+        for &(version, date) in RELEASE_DATES {
+            assert!(Version::parse(version).is_some());
+            assert!(Date::parse(date).is_some());
+        }
+    }
+
+    #[test]
+    fn test_prebuilt_std() {
+        let dir = env::temp_dir().join("version_check_test_prebuilt_std");
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(has_std_rlib(&dir), Some(false));
+
+        fs::create_dir(&dir).unwrap();
+        fs::File::create(dir.join("libcore-0123abcd.rlib")).unwrap();
+        fs::File::create(dir.join("libstd_detect-0123abcd.rlib")).unwrap();
+        fs::File::create(dir.join("libstd-0123abcd.rmeta")).unwrap();
+        assert_eq!(has_std_rlib(&dir), Some(false));
+
+        fs::File::create(dir.join("libstd-0123abcd.rlib")).unwrap();
+        assert_eq!(has_std_rlib(&dir), Some(true));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(::has_prebuilt_std(None), Some(true));
+        assert_eq!(::has_prebuilt_std(Some("not-a-real-target")), None);
+
+        let host = ::default_target().unwrap();
+        assert_eq!(::needs_build_std(&host), Some(false));
+        assert_eq!(::needs_build_std("not-a-real-target"), None);
+    }
+
+    #[test]
+    fn test_dev_channel() {
+        use {Version, Channel};
+
+        let local = "rustc 1.52.0-dev\n\
+            binary: rustc\n\
+            commit-hash: unknown\n\
+            commit-date: unknown\n\
+            host: x86_64-unknown-linux-gnu\n\
+            release: 1.52.0-dev";
+
+        let headers = ["rustc 1.20.0-dev", "rustc 1.20.0-dev (2017-07-09)"];
+        for header in headers.iter() {
+            let (version, _) = version_and_date_from_rustc_version(header);
+            let version = version.unwrap();
+            assert_eq!(Version::parse(&version).map(|v| v.channel()), Some(Channel::dev()));
+
+            let channel = Channel::parse(&version).unwrap();
+            assert!(channel.is_dev());
+            assert!(channel.supports_features());
+        }
+
+        let (version, date) = version_and_date_from_rustc_verbose_version(local);
+        assert_eq!(date, None);
+        let channel = version.and_then(|v| Channel::parse(&v)).unwrap();
+        assert!(channel.is_dev() && channel.supports_features());
+
+        assert_eq!(Channel::parse("1.20.0-dev (d84693b93 2017-07-09)"), Some(Channel::dev()));
+        assert_eq!(Channel::parse("1.20.0-dev"), Channel::parse("dev"));
+        assert!(!Channel::parse("1.20.0-dev").unwrap().is_nightly());
+    }
+
+    #[test]
+    fn test_override() {
+        let parse = |s| override_verbose_version(s).map(|s| {
+            version_and_date_from_rustc_verbose_version(&s)
+        });
+
+        let verbose = "rustc 1.50.0 (cb75ad5db 2021-02-10)\n\
+            binary: rustc\n\
+            commit-hash: cb75ad5db02783e8b0222fee363c5f63f7e2cf5b\n\
+            commit-date: 2021-02-10\n\
+            host: x86_64-unknown-linux-gnu\n\
+            release: 1.50.0";
+
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(parse(verbose), Ok((some("1.50.0"), some("2021-02-10"))));
+        assert_eq!(parse("rustc 1.50.0 (cb75ad5db 2021-02-10)"),
+            Ok((some("1.50.0"), some("2021-02-10"))));
+        assert_eq!(parse("1.70.0"), Ok((some("1.70.0"), None)));
+        assert_eq!(parse(" 1.72.0-nightly\n"), Ok((some("1.72.0-nightly"), None)));
+        assert_eq!(parse("one.two"), Err(RustcError::InvalidVersion("one.two".into())));
+        assert_eq!(parse(""), Err(RustcError::InvalidVersion("".into())));
+    }
+
+    #[test]
+    fn test_host_platform() {
+        for triple in &["aarch64-apple-darwin", "x86_64-apple-ios", "arm64_32-apple-watchos"] {
+            assert!(is_apple_triple(triple));
+            assert!(!is_windows_msvc_triple(triple));
+        }
+
+        let msvc = ["x86_64-pc-windows-msvc", "aarch64-pc-windows-msvc", "x86_64-uwp-windows-msvc"];
+        for triple in &msvc {
+            assert!(is_windows_msvc_triple(triple));
+            assert!(!is_apple_triple(triple));
+        }
+
+        let others = [
+            "x86_64-pc-windows-gnu", "x86_64-unknown-linux-gnu", "wasm32-unknown-unknown"
+        ];
+        for triple in &others {
+            assert!(!is_windows_msvc_triple(triple));
+            assert!(!is_apple_triple(triple));
+        }
+
+        assert!(!is_apple_triple(""));
+        assert!(!is_windows_msvc_triple(""));
+    }
+
+    #[test]
+    fn test_blank_output() {
+        for blank in &["", "\n", "\n\n", "   ", " \t \n \r\n"] {
+            assert_eq!(version_and_date_from_rustc_version(blank), (None, None));
+            assert_eq!(version_and_date_from_rustc_verbose_version(blank), (None, None));
+            assert_eq!(summary_json_from_rustc_verbose_version(blank), None);
+        }
+
+        let expected = (Some("1.70.0".to_string()), Some("2023-05-31".to_string()));
+        for output in &[
+            "rustc 1.70.0 (90c541806 2023-05-31)\n\n",
+            "\n  rustc  1.70.0  (90c541806\t2023-05-31)  \n",
+            "warning: something\nrustc 1.70.0 (90c541806 2023-05-31)\n \n",
+        ] {
+            assert_eq!(version_and_date_from_rustc_version(output), expected);
+        }
+    }
+
+    #[test]
+    fn test_version_cfg() {
+        version_cfg!("1.0.0" => "version_check_test");
+        version_cfg!("1.0.0" => "version_check_a", "65535.0.0" => "version_check_b",);
+
+        let pairs = [("version_check_a", "1.0.0"), ("version_check_b", "65535.0.0")];
+        assert_eq!(::plan_declared_version_cfgs(&pairs), vec![
+            "cargo:rustc-check-cfg=cfg(version_check_a)",
+            "cargo:rustc-check-cfg=cfg(version_check_b)",
+            "cargo:rustc-cfg=version_check_a",
+        ]);
+
+        assert_eq!(::plan_declared_version_cfgs(&[("version_check_c", "one")]),
+            vec!["cargo:rustc-check-cfg=cfg(version_check_c)"]);
+    }
 
-            "rustc 1.52.0-nightly (234781afe 2021-03-07)\n\
-                binary: rustc\n\
-                commit-hash: 234781afe33d3f339b002f85f948046d8476cfc9\n\
-                commit-date: 2021-03-07\n\
-                host: x86_64-unknown-linux-gnu\n\
-                release: 1.52.0-nightly\n\
-                LLVM version: 12.0.0" => "1.52.0-nightly", Some("2021-03-07"),
+    #[test]
+    fn test_summary() {
+        let official = "rustc 1.52.0-nightly (234781afe 2021-03-07)\n\
+            binary: rustc\n\
+            commit-hash: 234781afe33d3f339b002f85f948046d8476cfc9\n\
+            commit-date: 2021-03-07\n\
+            host: x86_64-unknown-linux-gnu\n\
+            release: 1.52.0-nightly\n\
+            LLVM version: 12.0.0";
 
-            "rustc 1.41.1\n\
-                binary: rustc\n\
-                commit-hash: unknown\n\
-                commit-date: unknown\n\
-                host: x86_64-unknown-linux-gnu\n\
-                release: 1.41.1\n\
-                LLVM version: 7.0" => "1.41.1", None,
+        let distro = "rustc 1.50.0 (Fedora 1.50.0-1.fc33)\n\
+            binary: rustc\n\
+            commit-hash: unknown\n\
+            commit-date: unknown\n\
+            host: x86_64-unknown-linux-gnu\n\
+            release: 1.50.0";
 
-            "rustc 1.49.0\n\
-                binary: rustc\n\
-                commit-hash: unknown\n\
-                commit-date: unknown\n\
-                host: x86_64-unknown-linux-gnu\n\
-                release: 1.49.0" => "1.49.0", None,
+        assert_eq!(summary_from_rustc_verbose_version(official),
+            "version=1.52.0-nightly channel=nightly date=2021-03-07 \
+            host=x86_64-unknown-linux-gnu");
+        assert_eq!(summary_from_rustc_verbose_version(distro),
+            "version=1.50.0 channel=stable host=x86_64-unknown-linux-gnu");
+        assert_eq!(summary_from_rustc_verbose_version("rustc 1.20.0 (2017-07-09)"),
+            "version=1.20.0 channel=stable date=2017-07-09");
+        assert_eq!(summary_from_rustc_verbose_version(""), "");
 
-            "rustc 1.50.0 (Fedora 1.50.0-1.fc33)\n\
+        assert_eq!(summary_json_from_rustc_verbose_version(official).unwrap(),
+            "{\"version\":\"1.52.0-nightly\",\"channel\":\"nightly\",\
+            \"date\":\"2021-03-07\",\"host\":\"x86_64-unknown-linux-gnu\"}");
+        assert_eq!(summary_json_from_rustc_verbose_version(distro).unwrap(),
+            "{\"version\":\"1.50.0\",\"channel\":\"stable\",\"date\":null,\
+            \"host\":\"x86_64-unknown-linux-gnu\"}");
+        assert_eq!(summary_json_from_rustc_verbose_version("rustc 1.2.0\nhost: a\"b\\c\u{1}"),
+            Some("{\"version\":\"1.2.0\",\"channel\":\"stable\",\"date\":null,\
+            \"host\":\"a\\\"b\\\\c\\u0001\"}".to_string()));
+        assert_eq!(summary_json_from_rustc_verbose_version(""), None);
+
+        let fields = fields_from_rustc_verbose_version(official);
+        let keys: Vec<_> = fields.keys().map(|k| &**k).collect();
+        assert_eq!(keys, ["binary", "channel", "commit_date", "commit_hash", "date", "host",
+            "llvm_version", "release", "version"]);
+        assert_eq!(fields["version"], "1.52.0-nightly");
+        assert_eq!(fields["channel"], "nightly");
+        assert_eq!(fields["commit_hash"], "234781afe33d3f339b002f85f948046d8476cfc9");
+        assert_eq!(fields["llvm_version"], "12.0.0");
+
+        let fields = fields_from_rustc_verbose_version(distro);
+        let keys: Vec<_> = fields.keys().map(|k| &**k).collect();
+        assert_eq!(keys, ["binary", "channel", "host", "release", "version"]);
+        assert!(fields_from_rustc_verbose_version("").is_empty());
+    }
+
+    #[test]
+    fn test_rustup_proxy() {
+        use std::io;
+
+        // Created exclusively, as in `Probe`, so concurrent runs never collide.
+        let mut n = 0;
+        let dir;
+        loop {
+            let candidate = env::temp_dir().join(format!("version_check-proxy-{}", n));
+            match fs::create_dir(&candidate) {
+                Ok(()) => { dir = candidate; break; }
+                Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => n += 1,
+                Err(e) => panic!("failed to create {:?}: {}", candidate, e),
+            }
+        }
+
+        let (proxy_dir, plain_dir) = (dir.join("proxy"), dir.join("plain"));
+        fs::create_dir_all(&proxy_dir).unwrap();
+        fs::create_dir_all(&plain_dir).unwrap();
+        for path in &[proxy_dir.join("rustc"), proxy_dir.join("rustup"), plain_dir.join("rustc")] {
+            fs::File::create(path).unwrap();
+        }
+
+        assert!(is_rustup_proxy(&proxy_dir.join("rustc")));
+        assert!(!is_rustup_proxy(&plain_dir.join("rustc")));
+        assert!(!is_rustup_proxy(&dir.join("missing").join("rustc")));
+
+        let toolchain_dir = dir.join(".rustup").join("toolchains").join("stable").join("bin");
+        fs::create_dir_all(&toolchain_dir).unwrap();
+        fs::File::create(toolchain_dir.join("rustc")).unwrap();
+        assert_eq!(is_rustup_managed_at(&proxy_dir.join("rustc")), Some(true));
+        assert_eq!(is_rustup_managed_at(&toolchain_dir.join("rustc")), Some(true));
+        assert_eq!(is_rustup_managed_at(&plain_dir.join("rustc")), Some(false));
+        assert_eq!(is_rustup_managed_at(&dir.join("missing").join("rustc")), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_is_trusted_binary() {
+        assert!(is_trusted_binary("rustc 1.70.0 (90c541806 2023-05-31)\nbinary: rustc\n"));
+        assert!(is_trusted_binary("rustc 1.0.0 (a59de37e9 2015-05-13) (built 2015-05-14)"));
+        assert!(!is_trusted_binary("rustc 1.70.0 (90c541806 2023-05-31)\nbinary: clippy\n"));
+        assert!(!is_trusted_binary("binary: sccache"));
+    }
+
+    #[test]
+    fn test_toolchains_from_rustup_list() {
+        let list = "stable-x86_64-unknown-linux-gnu (active, default)\n\
+            nightly-2023-05-01-x86_64-unknown-linux-gnu\n\
+            \n\
+            1.70.0-x86_64-unknown-linux-gnu (override)\n";
+
+        assert_eq!(toolchains_from_rustup_list(list), vec![
+            "stable-x86_64-unknown-linux-gnu",
+            "nightly-2023-05-01-x86_64-unknown-linux-gnu",
+            "1.70.0-x86_64-unknown-linux-gnu",
+        ]);
+
+        assert!(toolchains_from_rustup_list("no installed toolchains\n").is_empty());
+        assert!(toolchains_from_rustup_list("").is_empty());
+    }
+
+    #[test]
+    fn test_rustc_hook() {
+        use std::io;
+        use std::path::Path;
+
+        let hooked = Path::new("/version_check/hooked/rustc");
+        let verbose = rustc_output_with(hooked, &["--verbose", "--version"], |path, args| {
+            assert_eq!((path, args), (hooked, &["--verbose", "--version"][..]));
+            Some(Some("rustc 1.70.0 (90c541806 2023-05-31)".into()))
+        });
+
+        assert_eq!(verbose.ok(), Some((true, "rustc 1.70.0 (90c541806 2023-05-31)".into())));
+        assert_eq!(print_args("sysroot", None), vec!["--print", "sysroot"]);
+        assert_eq!(print_args("cfg", Some("wasm32-unknown-unknown")),
+            vec!["--print", "cfg", "--target", "wasm32-unknown-unknown"]);
+
+        let failed = rustc_output_with(hooked, &["--print", "sysroot"], |_, _| Some(None));
+        assert_eq!(failed.err().map(|e| e.kind()), Some(io::ErrorKind::Other));
+
+        // No unit test sets the process-wide hook, so `rustc` is spawned.
+        assert!(::hook::call(hooked, &["--verbose", "--version"]).is_none());
+        match rustc_output_with(hooked, &["--verbose", "--version"], |_, _| None) {
+            Err(e) => assert!(e.kind() != io::ErrorKind::Other),
+            Ok(_) => panic!("expected a spawn error"),
+        }
+    }
+
+    #[test]
+    fn test_rustc_path() {
+        use std::path::Path;
+
+        let rustc = r"/home/user/.rustup/toolchains/1.70.0-x86_64-unknown-linux-gnu/bin/rustc";
+        assert_eq!(rustc_path_from(Some(rustc.into())), Path::new(rustc));
+        assert_eq!(rustc_path_from(Some("rustc".into())), Path::new("rustc"));
+        assert_eq!(rustc_path_from(Some("".into())), Path::new("rustc"));
+        assert_eq!(rustc_path_from(None), Path::new("rustc"));
+    }
+
+    #[test]
+    fn test_split_command_line() {
+        use std::path::Path;
+
+        assert_eq!(split_command_line("ccache rustc"), vec!["ccache", "rustc"]);
+        assert_eq!(split_command_line("  rustc  "), vec!["rustc"]);
+        assert_eq!(split_command_line("a 'b c' \"d e\"f"), vec!["a", "b c", "d ef"]);
+        assert_eq!(split_command_line("a '' b"), vec!["a", "", "b"]);
+        assert_eq!(split_command_line("a 'b c"), vec!["a", "b c"]);
+        assert!(split_command_line("   ").is_empty());
+
+        let (program, args) = split_program(Path::new("ccache rustc --cap-lints=warn"));
+        assert_eq!(program, Path::new("ccache"));
+        assert_eq!(args, vec!["rustc", "--cap-lints=warn"]);
+
+        for path in &["rustc", "/usr/bin/rustc"] {
+            assert_eq!(split_program(Path::new(path)), (Path::new(path).to_path_buf(), vec![]));
+        }
+
+        let dir = ::std::env::temp_dir().join("version_check split program");
+        let _ = fs::create_dir(&dir);
+        let rustc = dir.join("rustc");
+        fs::File::create(&rustc).unwrap();
+        assert_eq!(split_program(&rustc), (rustc.clone(), vec![]));
+        fs::remove_dir_all(&dir).unwrap();
+
+        #[cfg(unix)] {
+            let (program, args) = split_program(Path::new("env rustc"));
+            assert_eq!((program, args), (Path::new("env").to_path_buf(), vec!["rustc".into()]));
+            let output = rustc_at(Path::new("env rustc")).arg("--version").output().unwrap();
+            assert!(String::from_utf8_lossy(&output.stdout).starts_with("rustc "));
+        }
+    }
+
+    #[test]
+    fn test_wrapped_rustc() {
+        use std::ffi::OsString;
+        use std::path::Path;
+
+        let rustc = Path::new(r"C:\Program Files\Rust\bin\rustc.exe");
+        let wrapper = r"C:\Program Files\sccache\sccache.exe";
+        let (program, args) = wrapped_program(Some(wrapper.into()), rustc);
+        assert_eq!(program, OsString::from(wrapper));
+        assert_eq!(args, vec![rustc.as_os_str().to_owned()]);
+
+        for wrapper in vec![None, Some("".into())] {
+            assert_eq!(wrapped_program(wrapper, rustc), (rustc.as_os_str().to_owned(), vec![]));
+        }
+
+        #[cfg(unix)] {
+            let output = wrapped_rustc(Some("env".into()), Path::new("rustc"))
+                .arg("--version")
+                .output()
+                .unwrap();
+
+            assert!(output.status.success());
+            assert!(String::from_utf8_lossy(&output.stdout).starts_with("rustc "));
+        }
+    }
+
+    #[test]
+    fn test_bootstrap_override() {
+        assert_eq!(bootstrap_override(None, Some("foo")), None);
+        assert_eq!(bootstrap_override(Some("1"), None), Some(true));
+        assert_eq!(bootstrap_override(Some("-1"), Some("foo")), Some(false));
+        assert_eq!(bootstrap_override(Some("0"), Some("foo")), None);
+        assert_eq!(bootstrap_override(Some(""), Some("foo")), None);
+        assert_eq!(bootstrap_override(Some("foo,bar_baz"), Some("bar-baz")), Some(true));
+        assert_eq!(bootstrap_override(Some("foo, bar"), Some("bar")), Some(true));
+        assert_eq!(bootstrap_override(Some("foo,bar"), Some("baz")), None);
+        assert_eq!(bootstrap_override(Some("foo,bar"), None), None);
+    }
+
+    #[test]
+    fn test_stabilized_table() {
+        for &(feature, version) in STABILIZED {
+            assert!(::Version::parse(version).is_some());
+            assert_eq!(::stabilized_in(feature), ::Version::parse(version));
+        }
+
+        for pair in STABILIZED.windows(2) {
+            assert!(::Version::parse(pair[0].1) <= ::Version::parse(pair[1].1));
+        }
+
+        assert_eq!(::stabilized_in(""), None);
+        assert_eq!(::stabilized_in("LET_ELSE"), None);
+
+        let msrv = |names: &[&str]| ::min_version_for_features(names);
+        assert_eq!(msrv(&[]), ::Version::parse("1.0.0"));
+        assert_eq!(msrv(&["question_mark"]), ::Version::parse("1.13.0"));
+        assert_eq!(msrv(&["let_chains", "asm"]), ::Version::parse("1.88.0"));
+        assert_eq!(msrv(&["asm", "let_chains", "asm"]), ::Version::parse("1.88.0"));
+        assert_eq!(msrv(&["asm", "unknown"]), None);
+    }
+
+    #[test]
+    fn test_spec() {
+        use {Version, Date};
+
+        let nightly = Version::parse("1.72.0-nightly").unwrap();
+        let stable = Version::parse("1.72.0").unwrap();
+        let date = Date::parse("2023-06-15");
+        let spec = |spec, version| spec_satisfied_by(spec, version, date);
+
+        assert_eq!(spec(">=1.70.0 nightly 2023-01-01..2024-01-01", nightly), Some(true));
+        assert_eq!(spec(">=1.70.0 nightly 2023-01-01..2024-01-01", stable), Some(false));
+        assert_eq!(spec(">=1.70.0 stable", stable), Some(true));
+        assert_eq!(spec(">=1.72.0 <1.73.0", nightly), Some(false));
+        assert_eq!(spec(">=1.72.0-nightly <1.73.0", nightly), Some(true));
+        assert_eq!(spec("  >=1.70.0\tstable\n", stable), Some(true));
+        assert_eq!(spec("^1.56 stable", stable), Some(true));
+        assert_eq!(spec("~1.69", stable), Some(false));
+        assert_eq!(spec("", stable), Some(true));
+
+        assert_eq!(spec("2023-06-15..", stable), Some(true));
+        assert_eq!(spec("..2023-06-15", stable), Some(false));
+        assert_eq!(spec("..2023-06-16", stable), Some(true));
+        assert_eq!(spec("2023-06-16..2024-01-01", stable), Some(false));
+
+        assert_eq!(spec("..", stable), None);
+        assert_eq!(spec("2023-13-01..", stable), None);
+        assert_eq!(spec(">=one.two", stable), None);
+        assert_eq!(spec("1.70.0", stable), None);
+        assert_eq!(spec("Stable", stable), None);
+        assert_eq!(spec(">=1.70.0 stable unknown", stable), None);
+        assert_eq!(spec_satisfied_by("2023-01-01..", stable, None), None);
+        assert_eq!(spec_satisfied_by(">=1.70.0", stable, None), Some(true));
+    }
+
+    #[test]
+    fn test_rustc_print_all() {
+        use std::path::Path;
+
+        let rustc = Path::new("rustc");
+        let outputs = rustc_print_all_at(rustc, &["sysroot", "cfg"]);
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0].as_ref().map(|s| s.trim()),
+            rustc_print_at(rustc, "sysroot", None).as_ref().map(|s| s.trim()));
+        assert_eq!(outputs[1], rustc_print_at(rustc, "cfg", None));
+
+        let outputs = rustc_print_all_at(rustc, &["sysroot", "not-a-print-request"]);
+        assert!(outputs[0].is_some());
+        assert!(outputs[1].is_none());
+    }
+
+    #[test]
+    fn test_cfg_value() {
+        let cfg = "debug_assertions\n\
+            panic=\"unwind\"\n\
+            target_arch=\"x86_64\"\n\
+            target_feature=\"sse2\"\n\
+            target_pointer_width=\"64\"\n\
+            unix\n";
+
+        assert_eq!(cfg_value(cfg, "target_pointer_width"), Some("64"));
+        assert_eq!(cfg_value(cfg, "target_arch"), Some("x86_64"));
+        assert_eq!(cfg_value(cfg, "panic"), Some("unwind"));
+        assert_eq!(cfg_value(cfg, "target_os"), None);
+        assert_eq!(cfg_value(cfg, "unix"), None);
+        assert_eq!(cfg_value("", "target_pointer_width"), None);
+
+        let cfg = "target_has_atomic\n\
+            target_has_atomic=\"16\"\n\
+            target_has_atomic=\"32\"\n\
+            target_has_atomic=\"8\"\n\
+            target_has_atomic=\"ptr\"\n\
+            target_has_atomic_load_store=\"64\"\n";
+
+        assert_eq!(cfg_values(cfg, "target_has_atomic"), ["16", "32", "8", "ptr"]);
+        assert_eq!(cfg_values(cfg, "target_has_atomic_load_store"), ["64"]);
+        assert!(cfg_values(cfg, "target_os").is_empty());
+
+        assert_eq!(::target_has_atomic("8", None), Some(true));
+        assert_eq!(::target_has_atomic("ptr", None), Some(true));
+        assert_eq!(::target_has_atomic("7", None), Some(false));
+        assert_eq!(::target_has_atomic("64", Some("not-a-real-target")), None);
+    }
+
+    #[test]
+    fn test_beta_ordinal_parse() {
+        check_verbose_parse! {
+            "rustc 1.71.0-beta.3 (5a8a44196 2023-06-09)\n\
                 binary: rustc\n\
-                commit-hash: unknown\n\
-                commit-date: unknown\n\
+                commit-hash: 5a8a441962b5b8ae7a8e94ce6ca94f6dc6d1d1fe\n\
+                commit-date: 2023-06-09\n\
                 host: x86_64-unknown-linux-gnu\n\
-                release: 1.50.0" => "1.50.0", None,
+                release: 1.71.0-beta.3\n\
+                LLVM version: 16.0.5" => "1.71.0-beta.3", Some("2023-06-09"),
         };
+
+        let version = ::Version::parse("1.71.0-beta.3").unwrap();
+        assert_eq!(version.to_string(), "1.71.0-beta.3");
+        assert_eq!(::Channel::parse("1.71.0-beta.3"), Some(::Channel::beta()));
+    }
+
+    #[test]
+    fn test_non_utf8_parse() {
+        let output = b"warning: \xff\xfe invalid logging spec\n\
+            rustc 1.50.0 (cb75ad5db 2021-02-10)\n\
+            binary: rustc\n\
+            commit-hash: cb75ad5db02783e8b0222fee363c5f63f7e2cf5b\n\
+            commit-date: 2021-02-10\n\
+            host: x86_64-unknown-linux-gnu\n\
+            release: 1.50.0";
+
+        assert!(String::from_utf8(output.to_vec()).is_err());
+
+        // Run a fake `rustc` that prints `output`, split from `sh {script}`.
+        #[cfg(unix)] {
+            use std::io::Write;
+            use std::path::Path;
+
+            let dir = env::temp_dir().join("version_check_test_non_utf8");
+            let _ = fs::create_dir(&dir);
+            let (script, stdout) = (dir.join("rustc.sh"), dir.join("stdout"));
+            fs::File::create(&stdout).and_then(|mut f| f.write_all(output)).unwrap();
+            fs::File::create(&script)
+                .and_then(|mut f| writeln!(f, "cat '{}'", stdout.display()))
+                .unwrap();
+
+            let rustc = format!("sh {}", script.display());
+            let output = ::rustc_verbose_version_at(Path::new(&rustc));
+            fs::remove_dir_all(&dir).unwrap();
+            let output = output.unwrap();
+            check_verbose_parse!(output => "1.50.0", Some("2021-02-10"),);
+        }
     }
 
     fn read_static(verbose: bool, channel: &str, minor: usize) -> String {
@@ -525,4 +4585,285 @@ mod tests {
             assert_eq!(channel, ::Channel::parse(&known_channel));
         }
     }
+
+    #[test]
+    fn test_target_pointer_width_current() {
+        assert!(::target_pointer_width(None).is_some());
+    }
+
+    #[test]
+    fn test_read_result_current() {
+        assert_eq!(::Channel::read_result().ok(), ::Channel::read());
+    }
+
+    #[test]
+    fn test_is_official_build_current() {
+        assert!(::is_official_build().is_some());
+    }
+
+    #[test]
+    fn test_supports_target_current() {
+        assert_eq!(::supports_target("bogus-target"), Some(false));
+    }
+
+    #[test]
+    fn test_summary_current() {
+        assert!(::summary().is_some());
+    }
+
+    #[test]
+    fn test_compare_current() {
+        assert_eq!(::compare("rustc", "rustc"), Some(::std::cmp::Ordering::Equal));
+        assert_eq!(::compare("rustc", "/version_check/missing/rustc"), None);
+    }
+
+    #[test]
+    fn test_version_comparisons_current() {
+        let version = ::Version::read().unwrap().stripped().to_string();
+        assert_eq!(::is_min_version(&version), Some(true));
+        assert_eq!(::is_max_version(&version), Some(true));
+        assert_eq!(::is_greater_version(&version), Some(false));
+        assert_eq!(::is_less_version(&version), Some(false));
+        assert_eq!(::is_greater_version("1.0.0"), Some(true));
+        assert_eq!(::is_less_version("65535.0.0"), Some(true));
+        assert_eq!(::is_less_version("one.two"), None);
+    }
+
+    #[test]
+    fn test_preload_current() {
+        assert_eq!(::preload(), Some(()));
+    }
+
+    #[test]
+    fn test_nightly_date_current() {
+        if ::Channel::read().map_or(false, |c| !c.supports_features()) {
+            assert_eq!(::nightly_date(), None);
+        }
+    }
+
+    #[test]
+    fn test_triple_partial_current() {
+        let (version, channel, _) = ::triple_partial();
+        assert_eq!(version, ::Version::read());
+        assert_eq!(channel, ::Channel::read());
+    }
+
+    #[test]
+    fn test_is_version_in_range_current() {
+        assert_eq!(::is_version_in_range(">=1.0.0, <65535.0.0"), Some(true));
+        assert_eq!(::is_version_in_range(">=one.two"), None);
+    }
+
+    #[test]
+    fn test_is_min_version_on_channel_current() {
+        let version = ::Version::read().unwrap().stripped().to_string();
+        let name = ::Channel::read().unwrap().to_string();
+        assert_eq!(::is_min_version_on_channel(&version, &name), Some(true));
+        assert_eq!(::is_min_version_on_channel("65535.0.0", &name), Some(false));
+        assert_eq!(::is_min_version_on_channel(&version, "Stable"), None);
+        assert_eq!(::is_min_version_on_channel("one.two", &name), None);
+    }
+
+    #[test]
+    fn test_default_edition_current() {
+        assert_eq!(::default_edition(), Some("2015"));
+    }
+
+    #[test]
+    fn test_matches_current() {
+        if let Some((v, c, d)) = ::triple() {
+            assert_eq!(::matches(|&v2, &c2, &d2| (v2, c2, d2) == (v, c, d)), Some(true));
+        }
+    }
+
+    #[test]
+    fn test_binary_name_current() {
+        assert_eq!(::binary_name(), Some("rustc".to_string()));
+    }
+
+    #[test]
+    fn test_is_min_max_exact_current() {
+        let version = ::Version::read().unwrap().stripped();
+        assert_eq!(::is_min(&version), Some(true));
+        assert_eq!(::is_max(&version), Some(true));
+        assert_eq!(::is_exact(&version), Some(true));
+        assert_eq!(::is_min(&version.next_minor()), Some(false));
+        assert_eq!(::is_exact(&::Version::from_mmp(1, 0, 0)), Some(false));
+    }
+
+    #[test]
+    fn test_is_rustup_managed_current() {
+        assert!(::is_rustup_managed().is_some());
+    }
+
+    #[test]
+    fn test_compare_version_current() {
+        let version = ::Version::read().unwrap().stripped().to_string();
+        assert_eq!(::compare_version(&version), Some(::std::cmp::Ordering::Equal));
+        assert_eq!(::compare_version("1.0.0"), Some(::std::cmp::Ordering::Greater));
+        assert_eq!(::compare_version("65535.0.0"), Some(::std::cmp::Ordering::Less));
+        assert_eq!(::compare_version("one.two"), None);
+    }
+
+    #[test]
+    fn test_compare_date_current() {
+        if let Some(date) = ::Date::read() {
+            assert_eq!(::compare_date(&date.to_string()), Some(::std::cmp::Ordering::Equal));
+            assert_eq!(::compare_date("2015-05-14"), Some(::std::cmp::Ordering::Greater));
+        }
+
+        assert_eq!(::compare_date("May 14, 2015"), None);
+    }
+
+    #[test]
+    fn test_has_stable_feature_current() {
+        assert_eq!(::has_stable_feature("question_mark"), Some(true));
+        assert_eq!(::has_stable_feature("never_type"), None);
+    }
+
+    #[test]
+    fn test_satisfies_spec_current() {
+        let version = ::Version::read().unwrap().stripped().to_string();
+        assert_eq!(::satisfies_spec(&format!(">={} <65535.0.0", version)), Some(true));
+        assert_eq!(::satisfies_spec("bogus"), None);
+    }
+
+    #[test]
+    fn test_supports_cfg_version_current() {
+        let channel = ::Channel::read();
+        assert_eq!(::supports_cfg_version(), channel.map(|c| c.supports_features()));
+    }
+
+    #[test]
+    fn test_accept_stable_or_nightly_current() {
+        assert_eq!(::accept_stable_or_nightly("1.0.0", "1970-01-01"), Some(true));
+        assert_eq!(::accept_stable_or_nightly("65535.0.0", "9999-12-31"), Some(false));
+        assert_eq!(::accept_stable_or_nightly("one.two", "1970-01-01"), None);
+    }
+
+    #[test]
+    fn test_is_dev_build_current() {
+        if ::Channel::read().map_or(false, |c| !c.is_dev()) {
+            assert_eq!(::is_dev_build(), Some(false));
+        }
+    }
+
+    #[test]
+    fn test_installed_toolchains_current() {
+        assert!(::installed_toolchains().map_or(true, |toolchains| !toolchains.is_empty()));
+    }
+
+    #[test]
+    fn test_defaults_current() {
+        let version = ::Version::read().unwrap().stripped().to_string();
+        assert!(::is_min_version_or(&version, false));
+        assert!(::is_max_version_or(&version, false));
+        assert!(!::is_min_version_or("65535.0.0", true));
+        assert!(::is_min_version_or("one.two", true));
+        assert!(!::is_max_version_or("one.two", false));
+        assert_eq!(::is_feature_flaggable_or(false), ::is_feature_flaggable().unwrap());
+        assert!(::is_min_date_or("bad", true));
+        assert!(!::is_max_date_or("bad", false));
+    }
+
+    #[test]
+    fn test_read_for_path_current() {
+        if env::var_os("VERSION_CHECK_OVERRIDE").is_none() {
+            assert_eq!(::Version::read_for_path(::rustc_path()), ::Version::read());
+            assert_eq!(::Channel::read_for_path(::rustc_path()), ::Channel::read());
+            assert_eq!(::Date::read_for_path(::rustc_path()), ::Date::read());
+        }
+    }
+
+    #[test]
+    fn test_summary_json_current() {
+        assert!(::summary_json().map_or(false, |json| json.starts_with("{\"version\":\"")));
+    }
+
+    #[test]
+    fn test_target_platform_current() {
+        assert_eq!(::is_apple(), Some(cfg!(target_vendor = "apple")));
+        assert_eq!(::is_windows_msvc(), Some(cfg!(all(windows, target_env = "msvc"))));
+    }
+
+    #[test]
+    fn test_check_current() {
+        let version = ::Version::read().unwrap().stripped().to_string();
+        let channel = ::Channel::read();
+        assert_eq!(::check_min_version(&version), ::Version::read().map(|v| (true, v)));
+        assert_eq!(::check_min_version("65535.0.0").map(|c| c.0), Some(false));
+        assert_eq!(::check_min_version("one.two"), None);
+        assert_eq!(::check_channel(channel.unwrap().as_ref()), channel.map(|c| (true, c)));
+        assert_eq!(::check_channel("nope"), None);
+        assert_eq!(::check_min_date("bad"), None);
+        if let Some(date) = ::Date::read() {
+            assert_eq!(::check_min_date("1970-01-01"), Some((true, date)));
+            assert_eq!(::check_min_date("9999-12-31"), Some((false, date)));
+        }
+    }
+
+    #[test]
+    fn test_default_target_current() {
+        let rustc = ::Rustc::from_env().unwrap();
+        assert_eq!(::default_target().as_ref().map(|t| &**t), rustc.host());
+    }
+
+    #[test]
+    fn test_supports_disjoint_closure_captures_current() {
+        assert_eq!(::supports_disjoint_closure_captures(), ::is_min_version("1.56.0"));
+    }
+
+    #[test]
+    fn test_crate_version() {
+        assert_eq!(::crate_version(), ::Version::parse(env!("CARGO_PKG_VERSION")));
+    }
+
+    #[test]
+    fn test_enable_cfg_if_nightly_since_current() {
+        let nightly = ::nightly_date().is_some();
+        assert_eq!(::enable_cfg_if_nightly_since("version_check_test", "1970-01-01"), nightly);
+        assert!(!::enable_cfg_if_nightly_since("version_check_test", "9999-12-31"));
+        assert!(!::enable_cfg_if_nightly_since("version_check_test", "bad"));
+    }
+
+    #[test]
+    fn test_read_async_current() {
+        assert_eq!(::read_async().join().ok(), Some(::triple()));
+    }
+
+    #[test]
+    fn test_fields_current() {
+        assert!(::fields().map_or(false, |f| f.contains_key("version")));
+    }
+
+    #[test]
+    fn test_is_at_least_stable_current() {
+        let channel = ::Channel::read();
+        assert_eq!(::is_at_least_stable("1.0.0"), channel.map(|c| c.is_stable()));
+        assert_eq!(::is_at_least_stable("65535.0.0"), Some(false));
+        assert_eq!(::is_at_least_stable("one"), None);
+    }
+
+    #[test]
+    fn test_read_uncached_current() {
+        assert_eq!(::read_uncached(), ::triple());
+    }
+
+    #[test]
+    fn test_toolchains_current() {
+        assert!(::host_toolchain().is_some());
+        assert_eq!(::target_toolchain().is_some(), env::var_os("RUSTC").is_some());
+    }
+
+    #[test]
+    fn test_read_or_current() {
+        let channel = ::Channel::read().unwrap();
+        assert_eq!(::Channel::read_or(::Channel::dev()), channel);
+        assert_eq!(::raw_channel().is_some(), !channel.is_stable());
+    }
+
+    #[test]
+    fn test_is_cross_compiling_current() {
+        assert_eq!(::is_cross_compiling().is_some(), env::var_os("TARGET").is_some());
+    }
 }
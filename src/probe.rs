@@ -0,0 +1,633 @@
+use std::{env, fs, io};
+use std::ffi::{OsStr, OsString};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+
+use ProbeError;
+
+/// Counter used to give each probe in this process a distinct directory.
+static PROBE_COUNT: AtomicUsize = ATOMIC_USIZE_INIT;
+
+/// The type of crate a feature probe is compiled as.
+///
+/// Probes are compiled as libraries by default, emitting only metadata, so no
+/// `main` function is required. Features that are only valid in a binary can
+/// be probed as [`CrateType::Bin`], for which an empty `main` is supplied.
+/// Probes compiled as [`CrateType::ProcMacro`] are linked against the host's
+/// `proc_macro` crate, which must be declared with `extern crate proc_macro`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CrateType {
+    /// A library crate: `--crate-type=lib`.
+    Lib,
+    /// A binary crate: `--crate-type=bin`.
+    Bin,
+    /// A procedural macro crate: `--crate-type=proc-macro`.
+    ProcMacro,
+}
+
+impl Default for CrateType {
+    fn default() -> CrateType {
+        CrateType::Lib
+    }
+}
+
+/// A configurable feature probe: a small crate compiled with the running or
+/// installed `rustc` to determine whether it supports some feature.
+///
+/// The free functions [`supports_all_features()`],
+/// [`supports_feature_with_edition()`], and [`supports_feature_detailed()`]
+/// run probes with the default configuration, where `rustc` inherits the
+/// environment of the current process. A `Probe` additionally controls the
+/// environment `rustc` is run in, so that probe results match the actual
+/// compilation environment, and the directory probe files are written to.
+///
+/// Each probe writes its source and output files to a fresh, uniquely named
+/// subdirectory of the probe directory, so concurrent probes, whether in this
+/// or another process, never collide. The subdirectory and its files are
+/// removed after each probe.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check::Probe;
+///
+/// let mut probe = Probe::new();
+/// probe.env("RUSTC_BOOTSTRAP", "0");
+/// if let Some(true) = probe.supports_all_features(&["doc_cfg"]) {
+///     println!("cargo:rustc-cfg=has_doc_cfg");
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Probe {
+    env_clear: bool,
+    vars: Vec<(OsString, OsString)>,
+    dir: Option<PathBuf>,
+    crate_type: CrateType,
+}
+
+impl Probe {
+    /// Returns a new `Probe` that runs `rustc` in the environment of the
+    /// current process.
+    pub fn new() -> Probe {
+        Probe { env_clear: false, vars: vec![], dir: None, crate_type: CrateType::Lib }
+    }
+
+    /// Sets the type of crate probes are compiled as. Defaults to
+    /// [`CrateType::Lib`].
+    pub fn crate_type(&mut self, crate_type: CrateType) -> &mut Probe {
+        self.crate_type = crate_type;
+        self
+    }
+
+    /// Sets the writable directory in which probe files are created. By
+    /// default, this is `OUT_DIR` when it is set, as it is for build scripts,
+    /// and the system temporary directory otherwise.
+    pub fn dir<P: AsRef<Path>>(&mut self, dir: P) -> &mut Probe {
+        self.dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Creates and returns a fresh subdirectory of the probe directory. The
+    /// directory is created exclusively: a name already taken by another
+    /// probe, possibly in another process, is skipped.
+    fn create_work_dir(&self) -> io::Result<PathBuf> {
+        let root = match self.dir {
+            Some(ref dir) => dir.clone(),
+            None => env::var_os("OUT_DIR").map(PathBuf::from).unwrap_or_else(env::temp_dir),
+        };
+
+        loop {
+            let n = PROBE_COUNT.fetch_add(1, Ordering::SeqCst);
+            let dir = root.join(format!("version_check_probe_{}", n));
+            match fs::create_dir(&dir) {
+                Ok(()) => return Ok(dir),
+                Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Sets the environment variable `key` to `val` for `rustc`.
+    ///
+    /// Setting `RUSTC_BOOTSTRAP` this way does not lift the channel check of
+    /// the feature probing methods: on the stable and beta channels, they
+    /// still return `false` without running a probe.
+    pub fn env<K: AsRef<OsStr>, V: AsRef<OsStr>>(&mut self, key: K, val: V) -> &mut Probe {
+        self.vars.push((key.as_ref().to_os_string(), val.as_ref().to_os_string()));
+        self
+    }
+
+    /// Clears the environment of `rustc`, including variables previously set
+    /// with [`Probe::env()`], so that it inherits no variables from the current
+    /// process. The path to `rustc` is resolved before the environment is
+    /// cleared. Note that a `rustup` proxy may require variables such as `HOME`
+    /// or `RUSTUP_HOME` to be set again.
+    pub fn env_clear(&mut self) -> &mut Probe {
+        self.env_clear = true;
+        self.vars.clear();
+        self
+    }
+
+    /// Like [`supports_all_features()`] but using this probe's configuration.
+    pub fn supports_all_features(&self, features: &[&str]) -> Option<bool> {
+        if features.is_empty() {
+            return Some(true);
+        }
+
+        if is_unflaggable(features) {
+            return Some(false);
+        }
+
+        if !features.iter().all(|f| ::is_allowed_by_rustflags(f)) {
+            return Some(false);
+        }
+
+        match feature_probe_source(features) {
+            Some(source) => self.run(&source, None).map(|(success, _, _)| success),
+            None => Some(false)
+        }
+    }
+
+    /// Like [`supports_feature_with_edition()`] but using this probe's
+    /// configuration.
+    pub fn supports_feature_with_edition(&self, feature: &str, edition: &str) -> Option<bool> {
+        if is_unflaggable(&[feature]) || !::is_allowed_by_rustflags(feature) {
+            return Some(false);
+        }
+
+        let source = match feature_probe_source(&[feature]) {
+            Some(source) => source,
+            None => return Some(false)
+        };
+
+        match self.run(&source, Some(edition)) {
+            Some((false, _, ref stderr)) if is_edition_error(stderr, edition) => None,
+            Some((success, _, _)) => Some(success),
+            None => None,
+        }
+    }
+
+    /// Like [`supports_feature_detailed()`] but using this probe's
+    /// configuration.
+    pub fn supports_feature_detailed(&self, feature: &str) -> Result<bool, ProbeError> {
+        if is_unflaggable(&[feature]) || !::is_allowed_by_rustflags(feature) {
+            return Ok(false);
+        }
+
+        let source = match feature_probe_source(&[feature]) {
+            Some(source) => source,
+            None => return Ok(false)
+        };
+
+        match self.run(&source, None) {
+            Some((true, _, _)) => Ok(true),
+            Some((false, _, ref stderr)) if is_feature_rejection(stderr) => Ok(false),
+            Some((false, code, stderr)) => Err(ProbeError::Failed(code, stderr)),
+            None => Err(ProbeError::Unavailable),
+        }
+    }
+
+    /// Compiles `source` as a crate of this probe's crate type with the running
+    /// or installed `rustc`, emitting only metadata, using `edition` if it is
+    /// `Some`. An empty `main` is appended to the source of a binary. The
+    /// source and output files are written to a fresh subdirectory of the
+    /// probe directory, which is removed afterwards.
+    ///
+    /// Returns `None` if the source file could not be written or `rustc` could
+    /// not be executed. Otherwise returns whether `source` compiled
+    /// successfully, the compiler's exit code, if any, and its standard error.
+    fn run(&self, source: &str, edition: Option<&str>) -> Option<(bool, Option<i32>, String)> {
+        if ::is_disabled() {
+            return None;
+        }
+
+        let dir = match self.create_work_dir() {
+            Ok(dir) => dir,
+            Err(_) => return None,
+        };
+
+        let (crate_type, main) = match self.crate_type {
+            CrateType::Lib => ("--crate-type=lib", ""),
+            CrateType::Bin => ("--crate-type=bin", "fn main() {}\n"),
+            CrateType::ProcMacro => ("--crate-type=proc-macro", ""),
+        };
+
+        let src = dir.join("probe.rs");
+        let written = fs::File::create(&src).and_then(|mut f| {
+            f.write_all(source.as_bytes()).and_then(|_| f.write_all(main.as_bytes()))
+        });
+        if written.is_err() {
+            let _ = fs::remove_dir_all(&dir);
+            return None;
+        }
+
+        let mut cmd = match self.env_clear {
+            true => {
+                let rustc = ::rustc_path();
+                let mut cmd = ::rustc_at(&::resolve_program(&rustc).unwrap_or(rustc));
+                cmd.env_clear();
+                cmd
+            }
+            false => ::rustc()
+        };
+
+        for var in &self.vars {
+            cmd.env(&var.0, &var.1);
+        }
+
+        cmd.arg(crate_type)
+            .arg("--emit=metadata")
+            .arg("--crate-name").arg("version_check_probe")
+            .arg("--out-dir").arg(&dir);
+
+        if let Some(edition) = edition {
+            cmd.arg("--edition").arg(edition);
+        }
+
+        ::record_spawn();
+        let output = cmd.arg(&src).output();
+        let _ = fs::remove_dir_all(&dir);
+        output.ok().map(|output| {
+            let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+            (output.status.success(), output.status.code(), stderr)
+        })
+    }
+}
+
+/// Returns `true` if the running or installed `rustc` is known to be on a
+/// channel that does not support feature flags, in which case no probe for
+/// `features` should be run: a probe could still succeed on stable or beta
+/// under `RUSTC_BOOTSTRAP`, enabling features that the build then cannot rely
+/// on. Warns as in [`supports_feature()`](crate::supports_feature()).
+fn is_unflaggable(features: &[&str]) -> bool {
+    match ::is_feature_flaggable() {
+        Some(false) => {
+            ::warn_unflaggable(features);
+            true
+        }
+        _ => false
+    }
+}
+
+/// Returns the source of a crate enabling all of `features`, or `None` if any
+/// feature is not a valid feature name.
+fn feature_probe_source(features: &[&str]) -> Option<String> {
+    let mut source = String::new();
+    for feature in features {
+        if !is_feature_name(feature) {
+            return None;
+        }
+
+        source.push_str(&format!("#![feature({})]\n", feature));
+    }
+
+    Some(source)
+}
+
+/// Returns `true` if `feature` is a valid feature name: a non-empty string of
+/// ASCII alphanumerics and underscores.
+fn is_feature_name(feature: &str) -> bool {
+    let is_valid = |c: char| c == '_' || ((c as u32) < 0x80 && c.is_alphanumeric());
+    !feature.is_empty() && feature.chars().all(is_valid)
+}
+
+/// Checks whether the running or installed `rustc` accepts all of `features`
+/// enabled together.
+///
+/// **Please see the note on [feature detection](crate#feature-detection).**
+///
+/// A single probe crate enabling every feature in `features` via
+/// `#![feature(...)]` is compiled with `--emit=metadata`. Compilation succeeds
+/// only if the channel supports feature flags, every feature is known to the
+/// compiler, and no feature is disabled via `-Z allow-features`. Probing the
+/// features together is cheaper than probing each separately and matches how
+/// features that are only used together should be gated.
+///
+/// On the stable and beta channels, no probe is run and `false` is returned
+/// immediately, even if `RUSTC_BOOTSTRAP` would allow the probe to compile.
+/// The same holds for every probing function and for [`Probe`]. As with
+/// [`supports_feature()`](crate::supports_feature()), a `cargo:warning` is
+/// printed if `VERSION_CHECK_WARN_FEATURES` is set to a non-empty value.
+///
+/// If the probe could not be run, returns `None`. Otherwise returns `true` if
+/// the probe compiled and `false` otherwise, including when any name in
+/// `features` is not a valid feature name. If `features` is empty, returns
+/// `true` without probing.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::supports_all_features(&["doc_cfg", "never_type"]) {
+///     println!("cargo:rustc-cfg=nightly_docs");
+/// }
+/// ```
+pub fn supports_all_features(features: &[&str]) -> Option<bool> {
+    Probe::new().supports_all_features(features)
+}
+
+/// Checks whether the running or installed `rustc` supports `feature` in a
+/// crate of type `crate_type`.
+///
+/// **Please see the note on [feature detection](crate#feature-detection).**
+///
+/// Like [`supports_all_features()`] with the single feature `feature`, but the
+/// probe crate is compiled as `crate_type`. Probing features that are only
+/// valid in a binary, or only in a library, in the right mode avoids false
+/// negatives.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check::{self as rustc, CrateType};
+///
+/// if let Some(true) = rustc::supports_feature_in("start", CrateType::Bin) {
+///     println!("cargo:rustc-cfg=has_start");
+/// }
+/// ```
+pub fn supports_feature_in(feature: &str, crate_type: CrateType) -> Option<bool> {
+    Probe::new().crate_type(crate_type).supports_all_features(&[feature])
+}
+
+/// Checks whether the running or installed `rustc` supports `feature` when
+/// compiling for `edition`, such as `"2021"`.
+///
+/// **Please see the note on [feature detection](crate#feature-detection).**
+///
+/// Like [`supports_all_features()`], a probe crate enabling `feature` is
+/// compiled, here with `--edition {edition}`. Some features only exist or
+/// behave as expected under a specific edition; probing with that edition
+/// avoids false negatives. To probe with the compiler's default edition, use
+/// [`supports_all_features()`] with a single feature.
+///
+/// If the probe could not be run, or if `edition` is not an edition supported
+/// by the compiler, returns `None`. Otherwise returns `true` if the probe
+/// compiled and `false` otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::supports_feature_with_edition("async_closure", "2021") {
+///     println!("cargo:rustc-cfg=has_async_closure");
+/// }
+/// ```
+pub fn supports_feature_with_edition(feature: &str, edition: &str) -> Option<bool> {
+    Probe::new().supports_feature_with_edition(feature, edition)
+}
+
+/// Checks whether the running or installed `rustc` supports `feature`,
+/// distinguishing a rejected feature from a probe that failed for another
+/// reason.
+///
+/// **Please see the note on [feature detection](crate#feature-detection).**
+///
+/// Like [`supports_all_features()`] with the single feature `feature`, a
+/// probe crate enabling `feature` is compiled. A failure is a clean rejection
+/// if `rustc` reports that the feature is unknown (`E0635`), that feature
+/// flags can't be used on its release channel (`E0554`), or that the feature
+/// is not allowed by `-Z allow-features` (`E0725`).
+///
+/// Returns `Ok(true)` if the probe compiled and `Ok(false)` if the feature was
+/// cleanly rejected, is disabled via `allow-features` in `RUSTFLAGS` or
+/// `CARGO_ENCODED_RUSTFLAGS`, or `feature` is not a valid feature name. If the
+/// probe could not be run, returns [`ProbeError::Unavailable`]. If it failed
+/// for any other reason, returns [`ProbeError::Failed`] with the exit code and
+/// standard error of `rustc`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// match rustc::supports_feature_detailed("doc_cfg") {
+///     Ok(true) => println!("cargo:rustc-cfg=has_doc_cfg"),
+///     Ok(false) => { /* the feature is not available */ }
+///     Err(e) => println!("cargo:warning=probing for `doc_cfg` failed: {}", e),
+/// }
+/// ```
+pub fn supports_feature_detailed(feature: &str) -> Result<bool, ProbeError> {
+    Probe::new().supports_feature_detailed(feature)
+}
+
+/// A crate that enables `portable_simd` and uses `std::simd`.
+const PORTABLE_SIMD_PROBE: &'static str = "#![feature(portable_simd)]
+use std::simd::Simd;
+pub fn double(v: Simd<u32, 4>) -> Simd<u32, 4> { v + v }
+";
+
+/// Checks whether the running or installed `rustc` supports the portable SIMD
+/// API in `std::simd` via `#![feature(portable_simd)]`.
+///
+/// **Please see the note on [feature detection](crate#feature-detection).**
+///
+/// This combines channel gating with probing. On the stable and beta
+/// channels, `std::simd` is unavailable, and `false` is returned without
+/// running a probe. Otherwise, a probe crate that enables `portable_simd` and
+/// uses `std::simd::Simd` is compiled, so a nightly or dev compiler whose
+/// `std::simd` predates or differs from the API used is not mistaken for
+/// supporting it. `false` is also returned if the feature is disabled via
+/// `allow-features` in `RUSTFLAGS` or `CARGO_ENCODED_RUSTFLAGS`.
+///
+/// If the probe could not be run, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(true) = rustc::supports_portable_simd() {
+///     println!("cargo:rustc-cfg=has_portable_simd");
+/// }
+/// ```
+pub fn supports_portable_simd() -> Option<bool> {
+    let feature = "portable_simd";
+    if is_unflaggable(&[feature]) || !::is_allowed_by_rustflags(feature) {
+        return Some(false);
+    }
+
+    Probe::new().run(PORTABLE_SIMD_PROBE, None).map(|(success, _, _)| success)
+}
+
+/// A procedural macro crate defining a derive, the oldest stable kind of
+/// procedural macro.
+const PROC_MACRO_PROBE: &'static str = "extern crate proc_macro;
+use proc_macro::TokenStream;
+#[proc_macro_derive(VersionCheckProbe)]
+pub fn derive(_: TokenStream) -> TokenStream { \"\".parse().unwrap() }
+";
+
+/// Checks whether the running or installed `rustc` can compile procedural
+/// macro crates.
+///
+/// A probe crate of type `proc-macro` that defines a derive macro is compiled
+/// with `--emit=metadata`. This succeeds with any `rustc` since `1.15.0` whose
+/// sysroot provides the `proc_macro` crate for the host, which is the case for
+/// every official toolchain but may not be for minimal or cross-compilation
+/// toolchains.
+///
+/// If the probe could not be run, returns `None`. Otherwise returns whether
+/// the probe compiled.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(false) = rustc::supports_proc_macro() {
+///     println!("cargo:warning=this toolchain cannot build procedural macros");
+/// }
+/// ```
+pub fn supports_proc_macro() -> Option<bool> {
+    let mut probe = Probe::new();
+    probe.crate_type(CrateType::ProcMacro);
+    probe.run(PROC_MACRO_PROBE, None).map(|(success, _, _)| success)
+}
+
+/// Returns `true` if `stderr` reports that a feature was rejected: it is
+/// unknown, feature flags are unavailable, or it is not allowed.
+fn is_feature_rejection(stderr: &str) -> bool {
+    ["E0635", "E0554", "E0725"].iter().any(|code| stderr.contains(&format!("[{}]", code)))
+}
+
+/// Returns `true` if `stderr` reports that `edition` is invalid or unstable.
+fn is_edition_error(stderr: &str, edition: &str) -> bool {
+    stderr.contains("`--edition`")
+        || stderr.contains(&format!("edition {} is unstable", edition))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Probe, CrateType, is_feature_name, is_feature_rejection, is_edition_error};
+    use super::{supports_feature_with_edition, supports_feature_detailed};
+    use super::{supports_portable_simd, PORTABLE_SIMD_PROBE, supports_proc_macro};
+
+    #[test]
+    fn test_probe() {
+        let probe = Probe::new();
+        let success = |source, edition| probe.run(source, edition).map(|(success, _, _)| success);
+
+        assert_eq!(success("pub fn f() -> u8 { 1 }", None), Some(true));
+        assert_eq!(success("pub fn f() -> u8 { \"\" }", None), Some(false));
+        assert_eq!(success("#![feature(not_a_real_feature_at_all)]", None), Some(false));
+        assert_eq!(success("pub fn async() {}", Some("2015")), Some(true));
+        assert_eq!(success("pub fn async() {}", Some("2018")), Some(false));
+
+        #[cfg(feature = "metrics")] {
+            let spawned = ::spawn_count();
+            assert_eq!(success("pub fn f() {}", None), Some(true));
+            assert!(::spawn_count() > spawned);
+        }
+        assert!(probe.run("", Some("1999"))
+            .map_or(false, |(_, _, e)| is_edition_error(&e, "1999")));
+        if ::is_feature_flaggable() == Some(true) {
+            assert_eq!(supports_feature_with_edition("doc_cfg", "1999"), None);
+        }
+    }
+
+    #[test]
+    fn test_probe_env() {
+        let mut probe = Probe::new();
+        probe.env_clear();
+        assert_eq!(probe.run("pub fn f() -> u8 { 1 }", None).map(|(s, _, _)| s), Some(true));
+
+        let mut probe = Probe::new();
+        probe.env("RUSTC_BOOTSTRAP", "1");
+        let success = |source| probe.run(source, None).map(|(s, _, _)| s);
+        assert_eq!(success("#![feature(rustc_attrs)]"), Some(true));
+        assert_eq!(success("#![feature(not_a_real_feature_at_all)]"), Some(false));
+
+        // Probes are never run on stable or beta, even under `RUSTC_BOOTSTRAP`.
+        let flaggable = ::is_feature_flaggable();
+        assert_eq!(probe.supports_all_features(&["rustc_attrs"]), flaggable);
+        assert_eq!(probe.supports_feature_detailed("rustc_attrs").ok(), flaggable);
+        assert_eq!(probe.supports_all_features(&["not_a_real_feature_at_all"]), Some(false));
+    }
+
+    #[test]
+    fn test_portable_simd() {
+        // `std::simd::Simd` exists, behind the feature, on every toolchain since `1.60.0`.
+        let has_simd = ::is_min_version("1.60.0") == Some(true);
+        let mut probe = Probe::new();
+        probe.env("RUSTC_BOOTSTRAP", "1");
+        if has_simd {
+            assert_eq!(probe.run(PORTABLE_SIMD_PROBE, None).map(|(s, _, _)| s), Some(true));
+        }
+
+        match ::is_feature_flaggable() {
+            Some(true) if has_simd => assert_eq!(supports_portable_simd(), Some(true)),
+            Some(true) => assert!(supports_portable_simd().is_some()),
+            _ => assert_eq!(supports_portable_simd(), Some(false)),
+        }
+    }
+
+    #[test]
+    fn test_proc_macro() {
+        // The `proc-macro` crate type was stabilized in `1.15.0`.
+        if ::is_min_version("1.15.0") == Some(true) {
+            assert_eq!(supports_proc_macro(), Some(true));
+        }
+    }
+
+    #[test]
+    fn test_crate_type() {
+        let run = |crate_type, source| {
+            let mut probe = Probe::new();
+            probe.crate_type(crate_type);
+            probe.run(source, None).map(|(success, _, _)| success)
+        };
+
+        assert_eq!(run(CrateType::Lib, "pub fn f() {}"), Some(true));
+        assert_eq!(run(CrateType::Bin, "pub fn f() {}"), Some(true));
+        assert_eq!(run(CrateType::Lib, "#![no_main]"), Some(true));
+        assert_eq!(run(CrateType::Bin, "fn main() {}"), Some(false));
+        if ::is_min_version("1.15.0") == Some(true) {
+            assert_eq!(run(CrateType::ProcMacro, "extern crate proc_macro;"), Some(true));
+            assert_eq!(run(CrateType::ProcMacro, "fn f() {}"), Some(true));
+            assert_eq!(run(CrateType::ProcMacro, "pub fn f() {}"), Some(false));
+        }
+        assert_eq!(CrateType::default(), CrateType::Lib);
+    }
+
+    #[test]
+    fn test_probe_dir() {
+        let dir = ::std::env::temp_dir().join("version_check_test_probe_dir");
+        let _ = ::std::fs::remove_dir_all(&dir);
+        ::std::fs::create_dir(&dir).unwrap();
+
+        let mut probe = Probe::new();
+        probe.dir(&dir);
+        assert_eq!(probe.run("pub fn f() {}", None).map(|(s, _, _)| s), Some(true));
+        assert_eq!(probe.run("pub fn f() {}", None).map(|(s, _, _)| s), Some(true));
+        assert_eq!(::std::fs::read_dir(&dir).unwrap().count(), 0);
+        ::std::fs::remove_dir(&dir).unwrap();
+
+        probe.dir(dir.join("missing"));
+        assert_eq!(probe.run("pub fn f() {}", None), None);
+    }
+
+    #[test]
+    fn test_feature_detailed() {
+        assert_eq!(supports_feature_detailed("not_a_real_feature_at_all"), Ok(false));
+        assert_eq!(supports_feature_detailed("doc cfg"), Ok(false));
+        assert!(is_feature_rejection("error[E0635]: unknown feature `x`"));
+        assert!(is_feature_rejection("error[E0554]: `#![feature]` may not be used on the ..."));
+        assert!(is_feature_rejection("error[E0725]: the feature `x` is not in the list of ..."));
+        assert!(!is_feature_rejection("error: could not write output to /tmp: Permission denied"));
+        assert!(!is_feature_rejection(""));
+    }
+
+    #[test]
+    fn test_feature_name() {
+        assert!(is_feature_name("doc_cfg"));
+        assert!(is_feature_name("never_type"));
+        assert!(is_feature_name("f16"));
+        assert!(!is_feature_name(""));
+        assert!(!is_feature_name("doc cfg"));
+        assert!(!is_feature_name("a)] fn f() {} #![feature(b"));
+        assert!(!is_feature_name("fé"));
+    }
+}
@@ -0,0 +1,86 @@
+use std::env;
+
+/// The Cargo profile a build script is being run for, as returned by
+/// [`profile()`].
+///
+/// Unlike the rest of this crate, a `Profile` is not read from `rustc` but
+/// from the environment variables Cargo sets for build scripts. It describes
+/// how the crate being built will be compiled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    /// The optimization level, from `OPT_LEVEL`: `0`, `1`, `2`, `3`, `s`, or
+    /// `z`.
+    pub opt_level: String,
+    /// Whether debug information is generated, from `DEBUG`.
+    pub debug: bool,
+    /// The base profile, from `PROFILE`: `debug` or `release`. Custom profiles
+    /// are reported as the profile they inherit from.
+    pub name: String,
+}
+
+/// Reads the [`Profile`] the running build script was invoked for from the
+/// `PROFILE`, `OPT_LEVEL`, and `DEBUG` environment variables set by Cargo.
+///
+/// `DEBUG` is considered `false` if it is `false`, `0`, or `none`, as Cargo
+/// has reported it both as a boolean and as a debug information level, and
+/// `true` otherwise. If any of the three variables is unset, as outside of a
+/// build script, returns `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+///
+/// if let Some(profile) = rustc::profile() {
+///     if profile.opt_level != "0" && !profile.debug {
+///         println!("cargo:rustc-cfg=optimized");
+///     }
+/// }
+/// ```
+pub fn profile() -> Option<Profile> {
+    profile_from(env::var("PROFILE").ok(), env::var("OPT_LEVEL").ok(), env::var("DEBUG").ok())
+}
+
+fn profile_from(name: Option<String>, opt_level: Option<String>, debug: Option<String>)
+    -> Option<Profile>
+{
+    match (name, opt_level, debug) {
+        (Some(name), Some(opt_level), Some(debug)) => {
+            let debug = match debug.trim() {
+                "false" | "0" | "none" => false,
+                _ => true,
+            };
+
+            Some(Profile { opt_level: opt_level, debug: debug, name: name })
+        }
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Profile, profile_from};
+
+    #[test]
+    fn test_profile_from() {
+        let profile = |name: &str, opt_level: &str, debug: &str| {
+            profile_from(Some(name.into()), Some(opt_level.into()), Some(debug.into()))
+        };
+
+        let release = Profile { opt_level: "3".into(), debug: false, name: "release".into() };
+        assert_eq!(profile("release", "3", "false"), Some(release));
+
+        let dev = profile("debug", "0", "true").unwrap();
+        assert_eq!((&*dev.name, &*dev.opt_level, dev.debug), ("debug", "0", true));
+
+        assert_eq!(profile("release", "z", "0").map(|p| p.debug), Some(false));
+        assert_eq!(profile("release", "s", "none").map(|p| p.debug), Some(false));
+        assert_eq!(profile("debug", "1", "2").map(|p| p.debug), Some(true));
+        assert_eq!(profile("debug", "1", "full").map(|p| p.debug), Some(true));
+        assert_eq!(profile("debug", "1", "line-tables-only").map(|p| p.debug), Some(true));
+
+        assert_eq!(profile_from(None, Some("0".into()), Some("true".into())), None);
+        assert_eq!(profile_from(Some("debug".into()), None, Some("true".into())), None);
+        assert_eq!(profile_from(Some("debug".into()), Some("0".into()), None), None);
+    }
+}
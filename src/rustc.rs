@@ -0,0 +1,595 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use {Version, Channel, Date};
+
+/// The version information of a `rustc`, read once and queried many times.
+///
+/// Unlike [`Version::read()`], [`Channel::read()`], and [`Date::read()`], each
+/// of which invokes `rustc`, a `Rustc` invokes `rustc` at most once, when it
+/// is constructed.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check::Rustc;
+///
+/// if let Some(rustc) = Rustc::from_env() {
+///     if rustc.version().map_or(false, |v| v.at_least("1.56.0")) {
+///         println!("cargo:rustc-cfg=edition_2021");
+///     }
+///
+///     if rustc.channel().map_or(false, |c| c.supports_features()) {
+///         println!("cargo:rustc-cfg=nightly");
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rustc {
+    path: Option<PathBuf>,
+    output: String,
+}
+
+impl Rustc {
+    /// Reads the version information of the running or installed `rustc`.
+    ///
+    /// The information is determined from the first of the following sources
+    /// that is available:
+    ///
+    ///   1. The `VERSION_CHECK_OVERRIDE` environment variable, without
+    ///      invoking `rustc`. See the [top-level documentation](crate).
+    ///   2. The output of `$RUSTC --verbose --version`, or `rustc --verbose
+    ///      --version` if `RUSTC` is not set.
+    ///
+    /// Cargo does not currently export the compiler's version to build
+    /// scripts; were it to, it would be consulted before invoking `rustc`.
+    ///
+    /// If `rustc` cannot be read, returns `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Rustc;
+    ///
+    /// match Rustc::from_env() {
+    ///     Some(rustc) => format!("rustc version: {:?}", rustc.version()),
+    ///     None => format!("Failed to read rustc.")
+    /// };
+    /// ```
+    pub fn from_env() -> Option<Rustc> {
+        ::rustc_verbose_version().ok()
+            .map(|output| Rustc::from_verbose_version(Some(&::rustc_path()), output))
+    }
+
+    /// Reads the version information from the file at `path`, which must
+    /// contain the output of `rustc --verbose --version`, exactly as printed
+    /// by `rustc -vV`. This allows output captured once, for instance by a CI
+    /// system, to be reused without invoking `rustc`.
+    ///
+    /// If the file cannot be read or does not contain `rustc` version output,
+    /// returns `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Rustc;
+    ///
+    /// match Rustc::from_file("target/rustc-vV.txt") {
+    ///     Some(rustc) => format!("rustc version: {:?}", rustc.version()),
+    ///     None => format!("Failed to read the captured rustc output.")
+    /// };
+    /// ```
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Option<Rustc> {
+        let mut bytes = vec![];
+        let read = File::open(path.as_ref()).and_then(|mut f| f.read_to_end(&mut bytes));
+        if read.is_err() {
+            return None;
+        }
+
+        let output = String::from_utf8_lossy(&bytes).into_owned();
+        if ::version_and_date_from_rustc_verbose_version(&output).0.is_none() {
+            return None;
+        }
+
+        Some(Rustc::from_verbose_version(None, output))
+    }
+
+    /// Creates a `Rustc` from the `rustc --verbose --version` output `output`
+    /// of the `rustc` at `path`, if there is one to invoke.
+    fn from_verbose_version(path: Option<&Path>, output: String) -> Rustc {
+        Rustc { path: path.map(|path| path.to_path_buf()), output: output }
+    }
+
+    /// Returns the version of this `rustc`, if it could be determined.
+    pub fn version(&self) -> Option<Version> {
+        ::version_and_date_from_rustc_verbose_version(&self.output).0
+            .and_then(|version| Version::parse(&version))
+    }
+
+    /// Returns the release channel of this `rustc`, if it could be determined.
+    pub fn channel(&self) -> Option<Channel> {
+        ::version_and_date_from_rustc_verbose_version(&self.output).0
+            .and_then(|version| Channel::parse(&version))
+    }
+
+    /// Returns the release date of this `rustc`, if it could be determined.
+    pub fn date(&self) -> Option<Date> {
+        ::version_and_date_from_rustc_verbose_version(&self.output).1
+            .and_then(|date| Date::parse(&date))
+    }
+
+    /// Returns the host target triple of this `rustc`, if it could be
+    /// determined.
+    pub fn host(&self) -> Option<&str> {
+        ::verbose_field(&self.output, "host")
+    }
+
+    /// Gathers the [`Capabilities`] of this `rustc`.
+    ///
+    /// Everything but the sysroot and the host's pointer width is determined
+    /// from the version information already read. Those two are read with a
+    /// single invocation of `rustc` that prints both the sysroot and the host's
+    /// `cfg` values. For a `Rustc` read with [`Rustc::from_file()`], there is no
+    /// `rustc` to invoke, so both are `None`.
+    ///
+    /// If the version of this `rustc` could not be determined, returns `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Rustc;
+    ///
+    /// if let Some(caps) = Rustc::from_env().and_then(|r| r.capabilities()) {
+    ///     if caps.edition_max() >= 2021 {
+    ///         println!("cargo:rustc-cfg=edition_2021");
+    ///     }
+    ///
+    ///     if caps.pointer_width() == Some(64) {
+    ///         println!("cargo:rustc-cfg=wide_pointers");
+    ///     }
+    /// }
+    /// ```
+    pub fn capabilities(&self) -> Option<Capabilities> {
+        let version = match self.version() {
+            Some(version) => version,
+            None => return None
+        };
+
+        let prints = match self.path {
+            Some(ref path) => ::rustc_print_all_at(path, &["sysroot", "cfg"]),
+            None => vec![],
+        };
+
+        let mut prints = prints.into_iter();
+        let sysroot = prints.next().and_then(|sysroot| sysroot)
+            .map(|sysroot| PathBuf::from(sysroot.trim()));
+
+        let pointer_width = prints.next().and_then(|cfg| cfg)
+            .and_then(|cfg| ::cfg_value(&cfg, "target_pointer_width").and_then(|w| w.parse().ok()));
+
+        Some(Capabilities {
+            version: version,
+            channel: self.channel(),
+            date: self.date(),
+            host: self.host().map(|host| host.to_string()),
+            edition_max: edition_max(version),
+            pointer_width: pointer_width,
+            sysroot: sysroot,
+        })
+    }
+}
+
+/// Editions and the first version of `rustc` to support each, newest first.
+const EDITIONS: &'static [(u16, &'static str)] = &[
+    (2024, "1.85.0"),
+    (2021, "1.56.0"),
+    (2018, "1.31.0"),
+];
+
+/// Returns the newest edition stably supported by `version`.
+fn edition_max(version: Version) -> u16 {
+    for &(edition, min_version) in EDITIONS {
+        if version.at_least(min_version) {
+            return edition;
+        }
+    }
+
+    2015
+}
+
+/// Returns the first stable version of `rustc` to support `edition`, such as
+/// `"2021"`, or `None` if `edition` is not a known edition.
+///
+/// This is a pure lookup in a table embedded in this crate that never invokes
+/// `rustc`. It is useful for deriving a crate's minimum supported Rust version
+/// from its edition. Every version of `rustc` supports the `2015` edition.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check as rustc;
+/// use version_check::Version;
+///
+/// assert_eq!(rustc::edition_min_version("2018"), Version::parse("1.31.0"));
+/// assert_eq!(rustc::edition_min_version("2021"), Version::parse("1.56.0"));
+/// assert_eq!(rustc::edition_min_version("2027"), None);
+/// ```
+pub fn edition_min_version(edition: &str) -> Option<Version> {
+    if edition == "2015" {
+        return Some(Version::from_mmp(1, 0, 0));
+    }
+
+    EDITIONS.iter()
+        .find(|&&(known, _)| edition.parse() == Ok(known))
+        .and_then(|&(_, min_version)| Version::parse(min_version))
+}
+
+/// A summary of what a `rustc` can do, as gathered by
+/// [`Rustc::capabilities()`].
+///
+/// Attributes that could not be determined are `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    version: Version,
+    channel: Option<Channel>,
+    date: Option<Date>,
+    host: Option<String>,
+    edition_max: u16,
+    pointer_width: Option<u32>,
+    sysroot: Option<PathBuf>,
+}
+
+impl Capabilities {
+    /// Returns the version of the `rustc`.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns the release channel of the `rustc`, if it could be determined.
+    pub fn channel(&self) -> Option<Channel> {
+        self.channel
+    }
+
+    /// Returns the release date of the `rustc`, if it could be determined.
+    pub fn date(&self) -> Option<Date> {
+        self.date
+    }
+
+    /// Returns the host target triple of the `rustc`, if it could be
+    /// determined.
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_ref().map(|host| &**host)
+    }
+
+    /// Returns whether the `rustc` supports feature flags, as determined by
+    /// [`Channel::supports_features()`], if the channel could be determined.
+    ///
+    /// **Please see the note on [feature detection](crate#feature-detection).**
+    pub fn supports_features(&self) -> Option<bool> {
+        self.channel.map(|channel| channel.supports_features())
+    }
+
+    /// Returns the newest edition the `rustc` supports without unstable flags,
+    /// such as `2021`, as determined by its version.
+    pub fn edition_max(&self) -> u16 {
+        self.edition_max
+    }
+
+    /// Returns the pointer width of the host target in bits, if it could be
+    /// determined.
+    pub fn pointer_width(&self) -> Option<u32> {
+        self.pointer_width
+    }
+
+    /// Returns the sysroot of the `rustc`, the directory containing its
+    /// libraries, if it could be determined.
+    pub fn sysroot(&self) -> Option<&Path> {
+        self.sysroot.as_ref().map(|sysroot| &**sysroot)
+    }
+
+    /// Serializes `self` into a single line that can be written to a file and
+    /// later reloaded with [`Capabilities::from_cache_string()`], avoiding
+    /// invocations of `rustc` on subsequent builds.
+    ///
+    /// The line begins with a tag identifying the version of the format so
+    /// that caches written by an incompatible version of this crate are
+    /// rejected rather than misread. The returned string does not end in a
+    /// newline. A sysroot that is not valid UTF-8 or that contains a line
+    /// break cannot be represented and is serialized as unknown.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::{Rustc, Capabilities};
+    ///
+    /// if let Some(caps) = Rustc::from_env().and_then(|r| r.capabilities()) {
+    ///     let line = caps.to_cache_string();
+    ///     assert_eq!(Capabilities::from_cache_string(&line), Some(caps));
+    /// }
+    /// ```
+    pub fn to_cache_string(&self) -> String {
+        fn field<T: ToString>(value: Option<T>) -> String {
+            value.map_or_else(|| "-".to_string(), |value| value.to_string())
+        }
+
+        let sysroot = self.sysroot()
+            .and_then(|sysroot| sysroot.to_str())
+            .and_then(|sysroot| match sysroot.contains('\n') || sysroot.contains('\r') {
+                true => None,
+                false => Some(sysroot),
+            });
+
+        let fields = [
+            self.version.to_string(),
+            field(self.channel),
+            field(self.date),
+            field(self.host()),
+            self.edition_max.to_string(),
+            field(self.pointer_width),
+            field(sysroot),
+        ];
+
+        let mut line = CACHE_FORMAT.to_string();
+        for field in fields.iter() {
+            line.push('\t');
+            line.push_str(field);
+        }
+
+        line
+    }
+
+    /// Deserializes `Capabilities` from a line written by
+    /// [`Capabilities::to_cache_string()`]. A single trailing newline is
+    /// permitted.
+    ///
+    /// If `cache` was written in a different version of the format, was
+    /// truncated, or is otherwise malformed, returns `None`. Callers should
+    /// then gather the capabilities anew with [`Rustc::capabilities()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::{Rustc, Capabilities};
+    ///
+    /// let cached = "version_check-capabilities/0\t1.70.0";
+    /// let caps = Capabilities::from_cache_string(cached)
+    ///     .or_else(|| Rustc::from_env().and_then(|r| r.capabilities()));
+    ///
+    /// if let Some(caps) = caps {
+    ///     println!("rustc {} supports the {} edition", caps.version(), caps.edition_max());
+    /// }
+    /// ```
+    pub fn from_cache_string(cache: &str) -> Option<Capabilities> {
+        let mut lines = cache.lines();
+        let line = match (lines.next(), lines.next()) {
+            (Some(line), None) => line,
+            _ => return None
+        };
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 8 || fields[0] != CACHE_FORMAT {
+            return None;
+        }
+
+        fn field<T, F: FnOnce(&str) -> Option<T>>(value: &str, parse: F) -> Option<Option<T>> {
+            match value {
+                "-" => Some(None),
+                value => parse(value).map(Some),
+            }
+        }
+
+        let version = Version::parse(fields[1]);
+        let channel = field(fields[2], ::channel_from_name);
+        let date = field(fields[3], Date::parse);
+        let host = field(fields[4], |host| Some(host.to_string()));
+        let edition_max = fields[5].parse().ok();
+        let pointer_width = field(fields[6], |width| width.parse().ok());
+        let sysroot = field(fields[7], |sysroot| Some(PathBuf::from(sysroot)));
+
+        match (version, channel, date, host, edition_max, pointer_width, sysroot) {
+            (Some(version), Some(channel), Some(date), Some(host), Some(edition_max),
+                Some(pointer_width), Some(sysroot)) => Some(Capabilities {
+                version: version,
+                channel: channel,
+                date: date,
+                host: host,
+                edition_max: edition_max,
+                pointer_width: pointer_width,
+                sysroot: sysroot,
+            }),
+            _ => None
+        }
+    }
+}
+
+/// The tag, and thus version, of the format written by
+/// [`Capabilities::to_cache_string()`]. Bump whenever the format changes.
+const CACHE_FORMAT: &'static str = "version_check-capabilities/1";
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{Rustc, Capabilities, edition_max, edition_min_version};
+    use {Version, Channel, Date};
+
+    #[test]
+    fn test_accessors() {
+        let rustc = Rustc::from_verbose_version(Some(Path::new("rustc")), "\
+            rustc 1.52.0-nightly (234781afe 2021-03-07)\n\
+            binary: rustc\n\
+            commit-hash: 234781afe33d3f339b002f85f948046d8476cfc9\n\
+            commit-date: 2021-03-07\n\
+            host: x86_64-unknown-linux-gnu\n\
+            release: 1.52.0-nightly\n\
+            LLVM version: 12.0.0".into());
+
+        assert_eq!(rustc.version(), Version::parse("1.52.0-nightly"));
+        assert_eq!(rustc.channel(), Some(Channel::nightly()));
+        assert_eq!(rustc.date(), Date::parse("2021-03-07"));
+        assert_eq!(rustc.host(), Some("x86_64-unknown-linux-gnu"));
+
+        let rustc = Rustc::from_verbose_version(Some(Path::new("rustc")), "\
+            rustc 1.50.0 (Fedora 1.50.0-1.fc33)\n\
+            commit-hash: unknown\n\
+            commit-date: unknown\n\
+            release: 1.50.0".into());
+
+        assert_eq!(rustc.version(), Version::parse("1.50.0"));
+        assert_eq!(rustc.channel(), Some(Channel::stable()));
+        assert_eq!(rustc.date(), None);
+        assert_eq!(rustc.host(), None);
+    }
+
+    #[test]
+    fn test_from_env() {
+        let rustc = Rustc::from_env().unwrap();
+        assert_eq!(rustc.version(), Version::read());
+        assert_eq!(rustc.channel(), Channel::read());
+        assert!(rustc.host().is_some());
+    }
+
+    #[test]
+    fn test_from_file() {
+        use std::env;
+        use std::fs::{self, File};
+        use std::io::Write;
+
+        let path = env::temp_dir().join("version_check_test_from_file.txt");
+        File::create(&path).and_then(|mut f| f.write_all(b"\
+            rustc 1.70.0 (90c541806 2023-05-31)\n\
+            binary: rustc\n\
+            commit-hash: 90c541806f23a127002de5b4038be731ba1458ca\n\
+            commit-date: 2023-05-31\n\
+            host: x86_64-unknown-linux-gnu\n\
+            release: 1.70.0\n\
+            LLVM version: 16.0.2\n")).unwrap();
+
+        let rustc = Rustc::from_file(&path);
+        File::create(&path).and_then(|mut f| f.write_all(b"not rustc output")).unwrap();
+        let invalid = Rustc::from_file(&path);
+        let _ = fs::remove_file(&path);
+
+        let rustc = rustc.unwrap();
+        assert_eq!(rustc.version(), Version::parse("1.70.0"));
+        assert_eq!(rustc.channel(), Some(Channel::stable()));
+        assert_eq!(rustc.date(), Date::parse("2023-05-31"));
+        assert_eq!(rustc.host(), Some("x86_64-unknown-linux-gnu"));
+        assert_eq!(invalid, None);
+
+        // There is no `rustc` to read the sysroot and pointer width from.
+        let caps = rustc.capabilities().unwrap();
+        assert_eq!((caps.sysroot(), caps.pointer_width()), (None, None));
+        assert_eq!(Rustc::from_file("/version_check/missing/rustc-vV.txt"), None);
+    }
+
+    #[test]
+    fn test_edition_max() {
+        let edition = |v| edition_max(Version::parse(v).unwrap());
+        assert_eq!(edition("1.0.0"), 2015);
+        assert_eq!(edition("1.30.1"), 2015);
+        assert_eq!(edition("1.31.0"), 2018);
+        assert_eq!(edition("1.55.0"), 2018);
+        assert_eq!(edition("1.56.0-beta"), 2021);
+        assert_eq!(edition("1.84.1"), 2021);
+        assert_eq!(edition("1.85.0"), 2024);
+    }
+
+    #[test]
+    fn test_edition_min_version() {
+        let version = |v| Version::parse(v);
+        assert_eq!(edition_min_version("2015"), version("1.0.0"));
+        assert_eq!(edition_min_version("2018"), version("1.31.0"));
+        assert_eq!(edition_min_version("2021"), version("1.56.0"));
+        assert_eq!(edition_min_version("2024"), version("1.85.0"));
+        assert_eq!(edition_min_version("2027"), None);
+        assert_eq!(edition_min_version("2016"), None);
+        assert_eq!(edition_min_version("e2021"), None);
+        assert_eq!(edition_min_version(""), None);
+
+        for &(edition, _) in super::EDITIONS {
+            let min_version = edition_min_version(&edition.to_string()).unwrap();
+            assert_eq!(edition_max(min_version), edition);
+        }
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let rustc = Rustc::from_verbose_version(Some(Path::new("rustc")), "\
+            rustc 1.52.0-nightly (234781afe 2021-03-07)\n\
+            host: x86_64-unknown-linux-gnu\n\
+            release: 1.52.0-nightly".into());
+
+        let caps = rustc.capabilities().unwrap();
+        assert_eq!(caps.version(), Version::parse("1.52.0-nightly").unwrap());
+        assert_eq!(caps.channel(), Some(Channel::nightly()));
+        assert_eq!(caps.date(), Date::parse("2021-03-07"));
+        assert_eq!(caps.host(), Some("x86_64-unknown-linux-gnu"));
+        assert_eq!(caps.supports_features(), Some(true));
+        assert_eq!(caps.edition_max(), 2018);
+        assert!(caps.sysroot().map_or(false, |sysroot| sysroot.is_dir()));
+        assert!(caps.pointer_width().is_some());
+
+        let rustc = Rustc::from_verbose_version(Some(Path::new("rustc")), "binary: rustc".into());
+        assert_eq!(rustc.capabilities(), None);
+    }
+
+    #[test]
+    fn test_cache_string() {
+        let rustc = Rustc::from_verbose_version(Some(Path::new("rustc")), "\
+            rustc 1.52.0-nightly (234781afe 2021-03-07)\n\
+            host: x86_64-unknown-linux-gnu\n\
+            release: 1.52.0-nightly".into());
+
+        let caps = rustc.capabilities().unwrap();
+        let line = caps.to_cache_string();
+        assert!(line.starts_with("version_check-capabilities/1\t1.52.0-nightly\tnightly\t"));
+        assert!(!line.contains('\n'));
+        assert_eq!(Capabilities::from_cache_string(&line), Some(caps.clone()));
+        assert_eq!(Capabilities::from_cache_string(&(line.clone() + "\n")), Some(caps));
+
+        let unknown = Capabilities {
+            version: Version::from_mmp(1, 50, 0),
+            channel: None,
+            date: None,
+            host: None,
+            edition_max: 2018,
+            pointer_width: None,
+            sysroot: None,
+        };
+
+        let line = unknown.to_cache_string();
+        assert_eq!(line, "version_check-capabilities/1\t1.50.0\t-\t-\t-\t2018\t-\t-");
+        assert_eq!(Capabilities::from_cache_string(&line), Some(unknown.clone()));
+
+        let spaced = Capabilities { sysroot: Some("/opt/my rust".into()), ..unknown.clone() };
+        let line = spaced.to_cache_string();
+        assert_eq!(Capabilities::from_cache_string(&line), Some(spaced));
+
+        let broken = Capabilities { sysroot: Some("/opt/rust\nlib".into()), ..unknown.clone() };
+        let line = broken.to_cache_string();
+        assert_eq!(Capabilities::from_cache_string(&line), Some(unknown));
+
+        let invalid = [
+            "",
+            "version_check-capabilities/1",
+            "version_check-capabilities/0\t1.50.0\t-\t-\t-\t2018\t-\t-",
+            "version_check-capabilities/2\t1.50.0\t-\t-\t-\t2018\t-\t-",
+            "version_check-capabilities/1\t1.50.0\t-\t-\t-\t2018\t-",
+            "version_check-capabilities/1\t1.50.0\t-\t-\t-\t2018\t-\t-\t-",
+            "version_check-capabilities/1\t-\t-\t-\t-\t2018\t-\t-",
+            "version_check-capabilities/1\t1.50.0\tweekly\t-\t-\t2018\t-\t-",
+            "version_check-capabilities/1\t1.50.0\t-\t2021-13-01\t-\t2018\t-\t-",
+            "version_check-capabilities/1\t1.50.0\t-\t-\t-\t-\t-\t-",
+            "version_check-capabilities/1\t1.50.0\t-\t-\t-\t2018\twide\t-",
+            "version_check-capabilities/1\t1.50.0\t-\t-\t-\t2018\t-\t-\nextra",
+        ];
+
+        for line in invalid.iter() {
+            if let Some(caps) = Capabilities::from_cache_string(line) {
+                panic!("{:?} was parsed as {:?}", line, caps);
+            }
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use {Version, Channel, Date};
+
+/// A version, channel, and date triple where any attribute may be unknown, as
+/// returned by [`triple_partial()`](crate::triple_partial()).
+///
+/// A `PartialTriple` doubles as a set of constraints for
+/// [`PartialTriple::matches()`]: attributes that are `None` in the constraints
+/// are not compared. This keeps toolchains that cannot report every attribute,
+/// like distribution builds with an `unknown` release date, usable whenever the
+/// unknown attribute is irrelevant.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check::{PartialTriple, Version, Channel};
+///
+/// let stable_1_70 = PartialTriple {
+///     version: Version::parse("1.70.0"),
+///     channel: Channel::parse("1.70.0"),
+///     date: None,
+/// };
+///
+/// if PartialTriple::read().matches(&stable_1_70) {
+///     println!("cargo:rustc-cfg=stable_1_70");
+/// }
+/// ```
+///
+/// A `PartialTriple` also supports the checks performed by functions like
+/// [`is_min_version()`](crate::is_min_version()) against the attributes it
+/// holds, without invoking `rustc`. A complete triple, as returned by
+/// [`triple()`](crate::triple()), converts into a `PartialTriple` via `From`:
+///
+/// ```rust
+/// use version_check::{self as rustc, PartialTriple};
+///
+/// if let Some(triple) = rustc::triple() {
+///     let triple = PartialTriple::from(triple);
+///     let (min, stable) = (triple.is_min_version("1.70.0"), triple.is_channel("stable"));
+///     if let (Some(true), Some(true)) = (min, stable) {
+///         println!("cargo:rustc-cfg=stable_1_70");
+///     }
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct PartialTriple {
+    /// The version, if known.
+    pub version: Option<Version>,
+    /// The release channel, if known.
+    pub channel: Option<Channel>,
+    /// The release date, if known.
+    pub date: Option<Date>,
+}
+
+impl PartialTriple {
+    /// Reads the version, channel, and date of the running or installed
+    /// `rustc` as in [`triple_partial()`](crate::triple_partial()).
+    pub fn read() -> PartialTriple {
+        PartialTriple::from(::triple_partial())
+    }
+
+    /// Returns `true` if every attribute present in `constraints` is known in
+    /// `self` and equal to it. Attributes that are `None` in `constraints` are
+    /// ignored, so empty constraints match any triple.
+    ///
+    /// An attribute that is known in `constraints` but unknown in `self` does
+    /// not match, as it cannot be confirmed. Versions are compared exactly,
+    /// including any pre-release tag.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::{PartialTriple, Version, Channel, Date};
+    ///
+    /// // A distribution build without a known release date.
+    /// let distro = PartialTriple {
+    ///     version: Version::parse("1.70.0"),
+    ///     channel: Channel::parse("1.70.0"),
+    ///     date: None,
+    /// };
+    ///
+    /// let stable = PartialTriple { channel: Channel::parse("stable"), ..Default::default() };
+    /// assert!(distro.matches(&stable));
+    /// assert!(distro.matches(&PartialTriple::default()));
+    ///
+    /// let dated = PartialTriple { date: Date::parse("2023-05-31"), ..stable };
+    /// assert!(!distro.matches(&dated));
+    /// ```
+    pub fn matches(&self, constraints: &PartialTriple) -> bool {
+        fn field_matches<T: PartialEq>(value: Option<T>, constraint: Option<T>) -> bool {
+            constraint.map_or(true, |c| value == Some(c))
+        }
+
+        field_matches(self.version, constraints.version)
+            && field_matches(self.channel, constraints.channel)
+            && field_matches(self.date, constraints.date)
+    }
+
+    /// Checks that the version is **at least** `min_version`, as in
+    /// [`is_min_version()`](crate::is_min_version()). Pre-release tags are
+    /// ignored.
+    ///
+    /// If the version is unknown or `min_version` could not be parsed, returns
+    /// `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::{PartialTriple, Version};
+    ///
+    /// let triple = PartialTriple { version: Version::parse("1.70.0"), ..Default::default() };
+    /// assert_eq!(triple.is_min_version("1.56.0"), Some(true));
+    /// assert_eq!(triple.is_min_version("1.71.0"), Some(false));
+    /// assert_eq!(PartialTriple::default().is_min_version("1.56.0"), None);
+    /// ```
+    pub fn is_min_version(&self, min_version: &str) -> Option<bool> {
+        match (self.version, Version::parse(min_version)) {
+            (Some(version), Some(min)) => Some(version.stripped() >= min.stripped()),
+            _ => None
+        }
+    }
+
+    /// Checks that the version is **at most** `max_version`, as in
+    /// [`is_max_version()`](crate::is_max_version()). Pre-release tags are
+    /// ignored.
+    ///
+    /// If the version is unknown or `max_version` could not be parsed, returns
+    /// `None`.
+    pub fn is_max_version(&self, max_version: &str) -> Option<bool> {
+        match (self.version, Version::parse(max_version)) {
+            (Some(version), Some(max)) => Some(version.stripped() <= max.stripped()),
+            _ => None
+        }
+    }
+
+    /// Checks that the version is **exactly** `version`, as in
+    /// [`is_exact_version()`](crate::is_exact_version()). Pre-release tags are
+    /// ignored.
+    ///
+    /// If the version is unknown or `version` could not be parsed, returns
+    /// `None`.
+    pub fn is_exact_version(&self, version: &str) -> Option<bool> {
+        match (self.version, Version::parse(version)) {
+            (Some(self_version), Some(version)) => {
+                Some(self_version.stripped() == version.stripped())
+            }
+            _ => None
+        }
+    }
+
+    /// Checks that the release date is **on or after** `min_date`, as in
+    /// [`is_min_date()`](crate::is_min_date()).
+    ///
+    /// If the date is unknown or `min_date` could not be parsed, returns
+    /// `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::{PartialTriple, Date};
+    ///
+    /// let triple = PartialTriple { date: Date::parse("2023-05-31"), ..Default::default() };
+    /// assert_eq!(triple.is_min_date("2023-01-01"), Some(true));
+    /// assert_eq!(triple.is_max_date("2023-01-01"), Some(false));
+    /// assert_eq!(triple.is_exact_date("2023-05-31"), Some(true));
+    /// ```
+    pub fn is_min_date(&self, min_date: &str) -> Option<bool> {
+        match (self.date, Date::parse(min_date)) {
+            (Some(date), Some(min)) => Some(date >= min),
+            _ => None
+        }
+    }
+
+    /// Checks that the release date is **on or before** `max_date`, as in
+    /// [`is_max_date()`](crate::is_max_date()).
+    ///
+    /// If the date is unknown or `max_date` could not be parsed, returns
+    /// `None`.
+    pub fn is_max_date(&self, max_date: &str) -> Option<bool> {
+        match (self.date, Date::parse(max_date)) {
+            (Some(date), Some(max)) => Some(date <= max),
+            _ => None
+        }
+    }
+
+    /// Checks that the release date is **exactly** `date`, as in
+    /// [`is_exact_date()`](crate::is_exact_date()).
+    ///
+    /// If the date is unknown or `date` could not be parsed, returns `None`.
+    pub fn is_exact_date(&self, date: &str) -> Option<bool> {
+        match (self.date, Date::parse(date)) {
+            (Some(self_date), Some(date)) => Some(self_date == date),
+            _ => None
+        }
+    }
+
+    /// Checks that the release channel is the one named `channel`: one of
+    /// `"dev"`, `"nightly"`, `"beta"`, or `"stable"`.
+    ///
+    /// If the channel is unknown or `channel` is not a known channel name,
+    /// returns `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::{PartialTriple, Channel};
+    ///
+    /// let triple = PartialTriple { channel: Channel::parse("nightly"), ..Default::default() };
+    /// assert_eq!(triple.is_channel("nightly"), Some(true));
+    /// assert_eq!(triple.is_channel("stable"), Some(false));
+    /// assert_eq!(triple.is_channel("Nightly"), None);
+    /// ```
+    pub fn is_channel(&self, channel: &str) -> Option<bool> {
+        match (self.channel, ::channel_from_name(channel)) {
+            (Some(self_channel), Some(channel)) => Some(self_channel == channel),
+            _ => None
+        }
+    }
+
+    /// Checks whether the release channel supports feature flags, as in
+    /// [`is_feature_flaggable()`](crate::is_feature_flaggable()).
+    ///
+    /// If the channel is unknown, returns `None`.
+    pub fn is_feature_flaggable(&self) -> Option<bool> {
+        self.channel.map(|channel| channel.supports_features())
+    }
+}
+
+impl From<(Version, Channel, Date)> for PartialTriple {
+    fn from(triple: (Version, Channel, Date)) -> PartialTriple {
+        PartialTriple { version: Some(triple.0), channel: Some(triple.1), date: Some(triple.2) }
+    }
+}
+
+impl From<(Option<Version>, Option<Channel>, Option<Date>)> for PartialTriple {
+    fn from(triple: (Option<Version>, Option<Channel>, Option<Date>)) -> PartialTriple {
+        PartialTriple { version: triple.0, channel: triple.1, date: triple.2 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PartialTriple;
+    use {Version, Channel, Date};
+
+    #[test]
+    fn test_matches() {
+        let triple = |v, c, d| PartialTriple::from((Version::parse(v), Channel::parse(c),
+            Date::parse(d)));
+
+        let full = triple("1.70.0-nightly", "nightly", "2023-04-20");
+        let dateless = triple("1.70.0", "1.70.0", "unknown");
+        let any = PartialTriple::default();
+
+        assert!(full.matches(&any));
+        assert!(dateless.matches(&any));
+        assert!(any.matches(&any));
+        assert!(full.matches(&full));
+        assert!(!dateless.matches(&full));
+
+        let nightly = PartialTriple { channel: Channel::parse("nightly"), ..any };
+        assert!(full.matches(&nightly));
+        assert!(!dateless.matches(&nightly));
+        assert!(!any.matches(&nightly));
+
+        let version = PartialTriple { version: Version::parse("1.70.0"), ..any };
+        assert!(dateless.matches(&version));
+        assert!(!full.matches(&version));
+
+        let date = PartialTriple { date: Date::parse("2023-04-20"), ..nightly };
+        assert!(full.matches(&date));
+        assert!(!dateless.matches(&date));
+    }
+
+    #[test]
+    fn test_checks() {
+        let full = PartialTriple::from((Version::parse("1.70.0-nightly").unwrap(),
+            Channel::parse("nightly").unwrap(), Date::parse("2023-04-20").unwrap()));
+        let dateless = PartialTriple::from((Version::parse("1.70.0"), Channel::parse("1.70.0"),
+            None));
+        let any = PartialTriple::default();
+
+        assert_eq!(full.is_min_version("1.70.0"), Some(true));
+        assert_eq!(full.is_min_version("1.71.0"), Some(false));
+        assert_eq!(full.is_max_version("1.70.0"), Some(true));
+        assert_eq!(full.is_max_version("1.69.0"), Some(false));
+        assert_eq!(full.is_exact_version("1.70.0"), Some(true));
+        assert_eq!(full.is_exact_version("1.70.1"), Some(false));
+        assert_eq!(full.is_min_date("2023-04-20"), Some(true));
+        assert_eq!(full.is_min_date("2023-04-21"), Some(false));
+        assert_eq!(full.is_max_date("2023-04-20"), Some(true));
+        assert_eq!(full.is_max_date("2023-04-19"), Some(false));
+        assert_eq!(full.is_exact_date("2023-04-20"), Some(true));
+        assert_eq!(full.is_exact_date("2023-04-21"), Some(false));
+        assert_eq!(full.is_channel("nightly"), Some(true));
+        assert_eq!(full.is_channel("stable"), Some(false));
+        assert_eq!(full.is_feature_flaggable(), Some(true));
+
+        assert_eq!(dateless.is_min_version("1.56"), Some(true));
+        assert_eq!(dateless.is_min_date("2023-01-01"), None);
+        assert_eq!(dateless.is_channel("stable"), Some(true));
+        assert_eq!(dateless.is_feature_flaggable(), Some(false));
+
+        assert_eq!(any.is_min_version("1.0.0"), None);
+        assert_eq!(any.is_exact_date("2023-04-20"), None);
+        assert_eq!(any.is_channel("stable"), None);
+        assert_eq!(any.is_feature_flaggable(), None);
+
+        assert_eq!(full.is_min_version("one"), None);
+        assert_eq!(full.is_max_date("2023-13-01"), None);
+        assert_eq!(full.is_channel("Nightly"), None);
+    }
+}
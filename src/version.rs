@@ -1,13 +1,200 @@
-use std::fmt;
+use std::{error, fmt};
+use std::cmp::Ordering;
+use std::str::FromStr;
+use std::path::Path;
 
-/// Version number: `major.minor.patch`, ignoring release channel.
+/// Version number: `major.minor.patch` with an optional `dev`, `nightly`, or
+/// `beta[.N]` pre-release tag.
+///
+/// Versions are ordered first by `major.minor.patch` and then by pre-release
+/// tag, following the release train: `dev` and `nightly` pre-releases order
+/// before `beta` pre-releases which order before the release itself. Betas
+/// are ordered by ordinal: `beta` < `beta.1` < `beta.2`. The
+/// comparison methods [`Version::at_least()`], [`Version::at_most()`], and
+/// [`Version::exactly()`], on the other hand, ignore pre-release tags. Use
+/// [`Version::stripped()`] to do the same with `Ord`.
+///
+/// Because `Ord` follows the release train, [`std::cmp::max()`] and
+/// [`std::cmp::min()`] pick the genuinely newer and older version.
+///
+/// # Example
+///
+/// ```rust
+/// use std::cmp;
+/// use version_check::Version;
+///
+/// let nightly = Version::parse("1.71.0-nightly").unwrap();
+/// let stable = Version::parse("1.70.0").unwrap();
+/// let beta = Version::parse("1.71.0-beta.2").unwrap();
+///
+/// assert_eq!(cmp::max(nightly, stable), nightly);
+/// assert_eq!(cmp::max(nightly, beta), beta);
+/// assert_eq!(cmp::min(stable, beta), stable);
+/// ```
 #[derive(PartialEq, Eq, Copy, Clone, PartialOrd, Ord)]
-pub struct Version(u64);
+pub struct Version(u64, Pre);
+
+/// Pre-release tag. The variant order is the ordering between tags. A `beta`
+/// may carry an ordinal, as in `beta.3`; `beta` orders before any `beta.N`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, PartialOrd, Ord)]
+enum Pre {
+    Dev,
+    Nightly,
+    Beta(Option<u16>),
+    Release,
+}
+
+/// Parses the `N` in a `-beta.N` version string, if there is one.
+fn beta_ordinal(version: &str) -> Option<u16> {
+    const BETA: &'static str = "-beta.";
+
+    version.find(BETA).and_then(|i| {
+        let rest = &version[(i + BETA.len())..];
+        let end = rest.find(|c: char| !c.is_digit(10)).unwrap_or(rest.len());
+        rest[..end].parse().ok()
+    })
+}
+
+impl fmt::Display for Pre {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Pre::Dev => write!(f, "dev"),
+            Pre::Nightly => write!(f, "nightly"),
+            Pre::Beta(None) => write!(f, "beta"),
+            Pre::Beta(Some(n)) => write!(f, "beta.{}", n),
+            Pre::Release => Ok(()),
+        }
+    }
+}
+
+/// The pre-release tag of a [`Version`]: a `dev`, `nightly`, or `beta`
+/// channel and, for betas, an optional ordinal, as in `beta.2`.
+///
+/// Pre-release tags are ordered as their versions are: `dev` < `nightly` <
+/// `beta` < `beta.1` < `beta.2`.
+///
+/// `rustc` has no release candidate tag: every prerelease build promoted
+/// toward a stable release, including the last one, is reported as a beta,
+/// as in `1.70.0-beta.7`. A final beta cannot be distinguished from earlier
+/// ones, but later betas have larger ordinals. Tags `rustc` does not emit,
+/// such as `rc.1`, are not recognized, and versions carrying them parse as
+/// releases.
+///
+/// # Example
+///
+/// ```rust
+/// use version_check::{Version, Channel};
+///
+/// let pre = Version::parse("1.71.0-beta.2").unwrap().pre_release().unwrap();
+/// assert_eq!(pre.channel(), Channel::beta());
+/// assert_eq!(pre.ordinal(), Some(2));
+/// assert_eq!(pre.to_string(), "beta.2");
+/// ```
+#[derive(Debug, PartialEq, Eq, Copy, Clone, PartialOrd, Ord)]
+pub struct PreRelease(Pre);
+
+impl PreRelease {
+    /// Returns the `dev` pre-release tag.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::{Version, PreRelease};
+    ///
+    /// let version = Version::parse("1.3.0-dev").unwrap();
+    /// assert_eq!(version.pre_release(), Some(PreRelease::dev()));
+    /// ```
+    pub fn dev() -> PreRelease {
+        PreRelease(Pre::Dev)
+    }
+
+    /// Returns the `nightly` pre-release tag.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::{Version, PreRelease};
+    ///
+    /// let version = Version::parse("1.70.0-nightly").unwrap();
+    /// assert_eq!(version.pre_release(), Some(PreRelease::nightly()));
+    /// ```
+    pub fn nightly() -> PreRelease {
+        PreRelease(Pre::Nightly)
+    }
+
+    /// Returns the `beta` pre-release tag with the ordinal `ordinal`, if any,
+    /// as in `beta.2`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::{Version, PreRelease};
+    ///
+    /// let version = Version::parse("1.70.0-beta.2").unwrap();
+    /// assert_eq!(version.pre_release(), Some(PreRelease::beta(Some(2))));
+    ///
+    /// let version = Version::parse("1.70.0-beta").unwrap();
+    /// assert_eq!(version.pre_release(), Some(PreRelease::beta(None)));
+    /// ```
+    pub fn beta(ordinal: Option<u16>) -> PreRelease {
+        PreRelease(Pre::Beta(ordinal))
+    }
+
+    /// Returns the release channel of this pre-release: `dev`, `nightly`, or
+    /// `beta`. Never `stable`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::{Version, Channel};
+    ///
+    /// let version = Version::parse("1.52.0-nightly").unwrap();
+    /// assert_eq!(version.pre_release().unwrap().channel(), Channel::nightly());
+    /// ```
+    pub fn channel(&self) -> ::Channel {
+        match self.0 {
+            Pre::Dev => ::Channel::dev(),
+            Pre::Nightly => ::Channel::nightly(),
+            Pre::Beta(_) => ::Channel::beta(),
+            Pre::Release => ::Channel::stable(),
+        }
+    }
+
+    /// Returns the ordinal of this pre-release, as the `2` in `beta.2`, if
+    /// there is one. Only betas carry ordinals.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// let pre = |v| Version::parse(v).unwrap().pre_release().unwrap();
+    /// assert_eq!(pre("1.71.0-beta.2").ordinal(), Some(2));
+    /// assert_eq!(pre("1.71.0-beta").ordinal(), None);
+    /// assert_eq!(pre("1.71.0-nightly").ordinal(), None);
+    /// ```
+    pub fn ordinal(&self) -> Option<u16> {
+        match self.0 {
+            Pre::Beta(n) => n,
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PreRelease {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
 
 impl Version {
     /// Reads the version of the running compiler. If it cannot be determined
     /// (see the [top-level documentation](crate)), returns `None`.
     ///
+    /// If `VERSION_CHECK_FAKE_VERSION` is set, its value is parsed and
+    /// returned instead, without invoking the compiler. See
+    /// [testing](crate#testing).
+    ///
     /// # Example
     ///
     /// ```rust
@@ -19,15 +206,122 @@ impl Version {
     /// };
     /// ```
     pub fn read() -> Option<Version> {
+        if let Some(version) = ::fake_value("VERSION_CHECK_FAKE_VERSION") {
+            return Version::parse(&version);
+        }
+
         ::get_version_and_date()
             .and_then(|(version, _)| version)
             .and_then(|version| Version::parse(&version))
     }
 
 
+    /// Checks that the version of the running compiler is at least `msrv`,
+    /// returning a diagnostic suitable for display to users if it is not.
+    ///
+    /// The compiler is read once, as in [`Version::read()`], and compared as
+    /// in [`Version::at_least()`], ignoring pre-release tags. The error reads
+    /// `requires rustc >= {msrv} but found {version}` if the version is too
+    /// old and `could not determine rustc version` if it cannot be
+    /// determined. If `msrv` cannot be parsed, the error says so.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// if let Err(message) = Version::compatible_with_msrv("1.31.0") {
+    ///     println!("cargo:warning={}", message);
+    /// }
+    /// ```
+    pub fn compatible_with_msrv(msrv: &str) -> Result<(), String> {
+        let msrv = match Version::parse(msrv) {
+            Some(msrv) => msrv,
+            None => return Err(format!("invalid minimum rustc version `{}`", msrv)),
+        };
+
+        match Version::read() {
+            Some(version) => version.check_msrv(msrv),
+            None => Err("could not determine rustc version".to_string()),
+        }
+    }
+
+    /// Returns `Ok` if `self` is at least `msrv`, ignoring pre-release tags,
+    /// and the diagnostic of [`Version::compatible_with_msrv()`] otherwise.
+    fn check_msrv(&self, msrv: Version) -> Result<(), String> {
+        match self.stripped() >= msrv.stripped() {
+            true => Ok(()),
+            false => Err(format!("requires rustc >= {} but found {}", msrv, self)),
+        }
+    }
+
+    /// Reads the version of the `rustc` at `path`, which may also be a program
+    /// name to look up in `PATH`. Unlike [`Version::read()`],
+    /// `VERSION_CHECK_OVERRIDE` is not consulted. If the version cannot be
+    /// determined, returns `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// match Version::read_for_path("/usr/local/bin/rustc") {
+    ///     Some(v) => format!("Version is: {}", v),
+    ///     None => format!("Failed to read the version.")
+    /// };
+    ///
+    /// assert!(Version::read_for_path("/nonexistent/rustc").is_none());
+    /// ```
+    pub fn read_for_path<P: AsRef<Path>>(path: P) -> Option<Version> {
+        ::get_version_and_date_at(path.as_ref())
+            .and_then(|(version, _)| version)
+            .and_then(|version| Version::parse(&version))
+    }
+
+    /// Heuristically extracts the version encoded in the `rustup` toolchain name
+    /// `name`, such as the value of `RUSTUP_TOOLCHAIN`, without invoking
+    /// `rustc`.
+    ///
+    /// Names of toolchains pinned to a version, like
+    /// `1.70.0-x86_64-unknown-linux-gnu` or `1.70`, yield that version. Names
+    /// of channels, like `nightly-2023-06-01-x86_64-unknown-linux-gnu` or
+    /// `stable`, do not encode a version and yield `None`; see
+    /// [`Channel::from_toolchain_name()`](::Channel::from_toolchain_name()) and
+    /// [`Date::from_toolchain_name()`](::Date::from_toolchain_name()) for the
+    /// channel and date. So do custom toolchain names.
+    ///
+    /// This is only a hint: a toolchain name need not reflect the compiler that
+    /// is actually run, so it should not replace [`Version::read()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// let version = Version::from_toolchain_name("1.70.0-x86_64-unknown-linux-gnu");
+    /// assert_eq!(version, Version::parse("1.70.0"));
+    ///
+    /// assert_eq!(Version::from_toolchain_name("nightly-2023-06-01"), None);
+    /// assert_eq!(Version::from_toolchain_name("my-custom-toolchain"), None);
+    /// ```
+    pub fn from_toolchain_name(name: &str) -> Option<Version> {
+        ::toolchain_name_parts(name).0
+    }
+
     /// Parse a Rust release version (of the form
-    /// `major[.minor[.patch[-channel]]]`), ignoring the release channel, if
-    /// any. Returns `None` if `version` is not a valid Rust version string.
+    /// `major[.minor[.patch[-channel]]]`). A `dev`, `nightly`, or `beta`
+    /// channel is kept as the pre-release tag; any other channel is ignored, as
+    /// are numeric components past the third, as in `1.70.0.1`, and build
+    /// metadata, as in `1.70.0+abc123`. Returns `None`
+    /// if `version` is not a valid Rust version string, including when `major`,
+    /// `minor`, or `patch` exceeds `65535`. Input is never partially parsed:
+    /// each component must consist solely of ASCII digits, so input that does
+    /// not begin with a digit, like `rustc` or `v.1.2`, or that has an empty
+    /// component, like `1..2`, returns `None`. In particular, `_` digit
+    /// separators as in Rust literals are rejected rather than ignored, so
+    /// `1_000.0.0` and `1.7_0.0` are not versions. Never panics and never
+    /// allocates, so it is suitable for use in loops, such as when evaluating
+    /// a table of version thresholds.
     ///
     /// # Example
     ///
@@ -52,25 +346,138 @@ impl Version {
     /// assert!(Version::parse("1. 2").is_none());
     /// assert!(Version::parse("").is_none());
     /// assert!(Version::parse("1.").is_none());
-    /// assert!(Version::parse("1.2.3.4").is_none());
+    /// assert!(Version::parse("rustc").is_none());
+    /// assert!(Version::parse("1..2").is_none());
+    /// assert!(Version::parse("1.7_0.0").is_none());
+    ///
+    /// // Components past the third are ignored.
+    /// assert_eq!(Version::parse("1.70.0.1"), Version::parse("1.70.0"));
+    ///
+    /// // So is build metadata.
+    /// assert_eq!(Version::parse("1.70.0+abc123"), Version::parse("1.70.0"));
+    /// assert_eq!(Version::parse("1.70.0-nightly+a"), Version::parse("1.70.0-nightly+b"));
     /// ```
     pub fn parse(version: &str) -> Option<Version> {
-        let splits = version.split('-')
-            .nth(0)
-            .unwrap_or("")
-            .split('.')
-            .map(|s| s.parse::<u16>());
+        Version::from_str(version).ok()
+    }
 
+    /// Like [`Version::parse()`] but additionally accepts a single leading `v`
+    /// or `V`, as in the `v1.56.0` form common in tags.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// assert_eq!(Version::parse_lenient("v1.56.0"), Version::parse("1.56.0"));
+    /// assert_eq!(Version::parse_lenient("V1.70.0-beta.2"), Version::parse("1.70.0-beta.2"));
+    /// assert_eq!(Version::parse_lenient("1.56.0"), Version::parse("1.56.0"));
+    ///
+    /// assert!(Version::parse_lenient("vv1.56.0").is_none());
+    /// assert!(Version::parse_lenient("v").is_none());
+    /// ```
+    pub fn parse_lenient(version: &str) -> Option<Version> {
+        match version.chars().next() {
+            Some('v') | Some('V') => Version::parse(&version[1..]),
+            _ => Version::parse(version),
+        }
+    }
+
+    /// Parses `version` as in [`Version::parse()`], returning the kind of the
+    /// first error, if any, as a [`VersionParseKind`].
+    ///
+    /// This is intended for validating user-provided version strings, such as
+    /// those in configuration files, where feedback on _why_ a string is not a
+    /// version is useful. To also obtain the location of the error, parse via
+    /// `FromStr` and see [`ParseVersionError::kind()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::{Version, VersionParseKind};
+    ///
+    /// assert_eq!(Version::parse_detailed("1.70.0"), Ok(Version::from_mmp(1, 70, 0)));
+    /// assert_eq!(Version::parse_detailed(""), Err(VersionParseKind::Empty));
+    /// assert_eq!(Version::parse_detailed("1."), Err(VersionParseKind::MissingMinor));
+    /// assert_eq!(Version::parse_detailed("1.x"), Err(VersionParseKind::BadComponent));
+    /// ```
+    pub fn parse_detailed(version: &str) -> Result<Version, VersionParseKind> {
+        Version::parse_with_error(version).map_err(|e| e.kind())
+    }
+
+    /// Parses `version` as in [`Version::parse()`], returning the location of
+    /// and reason for the first error, if any.
+    ///
+    /// Components are parsed in place from lazy splits of `version`; nothing
+    /// is collected or copied into a `String`.
+    fn parse_with_error(version: &str) -> Result<Version, ParseVersionError> {
+        // Build metadata, as in `1.70.0+abc123`, is checked but ignored.
+        let version = match version.find('+') {
+            Some(i) => {
+                let metadata = &version[(i + 1)..];
+                let is_valid = |c: char| c.is_digit(36) || c == '.' || c == '-';
+                let kind = VersionParseKind::BadMetadata;
+                if metadata.is_empty() {
+                    return Err(ParseVersionError::new(i + 1, "expected build metadata", kind));
+                } else if let Some((j, _)) = metadata.char_indices().find(|&(_, c)| !is_valid(c)) {
+                    return Err(ParseVersionError::new(i + 1 + j, "invalid build metadata", kind));
+                }
+
+                &version[..i]
+            }
+            None => version,
+        };
+
+        let numbers = version.split('-').nth(0).unwrap_or("");
         let mut mmp = [0u16; 3];
-        for (i, split) in splits.enumerate() {
-            mmp[i] = match (i, split) {
-                (3, _) | (_, Err(_)) => return None,
-                (_, Ok(v)) => v,
+        let mut start = 0;
+        for (i, split) in numbers.split('.').enumerate() {
+            let missing = match (i, version.is_empty()) {
+                (_, true) => VersionParseKind::Empty,
+                (0, _) => VersionParseKind::MissingMajor,
+                (1, _) => VersionParseKind::MissingMinor,
+                (2, _) => VersionParseKind::MissingPatch,
+                _ => VersionParseKind::BadComponent,
+            };
+
+            let bad = VersionParseKind::BadComponent;
+            let error = match split.char_indices().find(|&(_, c)| !c.is_digit(10)) {
+                // Digit separators are rejected explicitly, not skipped.
+                Some((j, '_')) => Some(ParseVersionError::new(start + j, "unexpected `_`", bad)),
+                Some((j, _)) => Some(ParseVersionError::new(start + j, "expected a digit", bad)),
+                None if split.is_empty() => {
+                    Some(ParseVersionError::new(start, "expected a number", missing))
+                }
+                None => None,
             };
+
+            if let Some(error) = error {
+                return Err(error);
+            }
+
+            // Components past the third, as in `1.70.0.1`, are checked but ignored.
+            if i < 3 {
+                mmp[i] = match split.parse::<u16>() {
+                    Ok(v) => v,
+                    Err(_) => {
+                        let kind = VersionParseKind::Overflow;
+                        return Err(ParseVersionError::new(start, "number exceeds 65535", kind));
+                    }
+                };
+            }
+
+            start += split.len() + 1;
         }
 
+        let pre = match ::Channel::parse(version) {
+            Some(c) if c.is_dev() => Pre::Dev,
+            Some(c) if c.is_nightly() => Pre::Nightly,
+            Some(c) if c.is_beta() => Pre::Beta(beta_ordinal(version)),
+            _ => Pre::Release,
+        };
+
         let (maj, min, patch) = (mmp[0], mmp[1], mmp[2]);
-        Some(Version::from_mmp(maj, min, patch))
+        Ok(Version(Version::from_mmp(maj, min, patch).0, pre))
     }
 
     /// Creates a `Version` from `(major, minor, patch)` version components.
@@ -85,33 +492,349 @@ impl Version {
     /// assert!(Version::from_mmp(1, 35, 1).exactly("1.35.1"));
     /// assert!(Version::from_mmp(1, 13, 2).exactly("1.13.2"));
     /// ```
-    pub fn from_mmp(major: u16, minor: u16, patch: u16) -> Version {
-        Version(((major as u64) << 32) | ((minor as u64) << 16) | patch as u64)
+    pub fn from_mmp(major: u16, minor: u16, patch: u16) -> Version {
+        Version(((major as u64) << 32) | ((minor as u64) << 16) | patch as u64, Pre::Release)
+    }
+
+    /// Returns `self` with its pre-release tag, if any, removed.
+    ///
+    /// `Ord` orders a pre-release before its release, so `1.50.0-nightly` is
+    /// less than `1.50.0`. Comparing stripped versions instead disregards
+    /// pre-release precedence entirely, considering only `major.minor.patch`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// let nightly = Version::parse("1.50.0-nightly").unwrap();
+    /// let stable = Version::parse("1.50.0").unwrap();
+    ///
+    /// assert!(nightly < stable);
+    /// assert_eq!(nightly.stripped(), stable);
+    /// assert_eq!(stable.stripped(), stable);
+    /// ```
+    pub fn stripped(&self) -> Version {
+        Version(self.0, Pre::Release)
+    }
+
+    /// Returns `true` if `self` and `other` are the same release, that is, if
+    /// they have the same `major.minor.patch`, regardless of pre-release tags.
+    ///
+    /// This is equivalent to comparing [`Version::stripped()`] versions. To
+    /// treat differing pre-release tags as distinct, compare with `==`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// let nightly = Version::parse("1.70.0-nightly").unwrap();
+    /// let beta = Version::parse("1.70.0-beta.2").unwrap();
+    ///
+    /// assert!(nightly.same_release(&beta));
+    /// assert!(nightly != beta);
+    /// assert!(!nightly.same_release(&Version::parse("1.70.1-nightly").unwrap()));
+    /// ```
+    pub fn same_release(&self, other: &Version) -> bool {
+        self.0 == other.0
+    }
+
+    /// Returns the pre-release tag of `self`, or `None` if `self` is a release.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::{Version, Channel};
+    ///
+    /// let pre = Version::parse("1.70.0-beta.3").unwrap().pre_release().unwrap();
+    /// assert_eq!(pre.channel(), Channel::beta());
+    /// assert_eq!(pre.ordinal(), Some(3));
+    ///
+    /// let pre = Version::parse("1.3.0-dev").unwrap().pre_release().unwrap();
+    /// assert_eq!(pre.channel(), Channel::dev());
+    /// assert_eq!(pre.ordinal(), None);
+    ///
+    /// assert!(Version::parse("1.52.0").unwrap().pre_release().is_none());
+    /// ```
+    pub fn pre_release(&self) -> Option<PreRelease> {
+        match self.1 {
+            Pre::Release => None,
+            pre => Some(PreRelease(pre)),
+        }
+    }
+
+    /// Returns `self` with its pre-release tag, if any, replaced by `pre`.
+    ///
+    /// The result orders before the release of the same `major.minor.patch`,
+    /// and after any earlier release, as described in [`Version`]. For
+    /// instance, `1.70.0` with a `nightly` tag is `1.70.0-nightly`, which is
+    /// less than `1.70.0` but greater than `1.69.0`, so comparing against it
+    /// with `>=` accepts the first nightly of `1.70.0` and anything newer. To
+    /// remove a tag instead, use [`Version::stripped()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::{Version, PreRelease};
+    ///
+    /// let first_nightly = Version::from_mmp(1, 70, 0).with_pre(PreRelease::nightly());
+    /// assert_eq!(first_nightly, Version::parse("1.70.0-nightly").unwrap());
+    /// assert!(first_nightly < Version::parse("1.70.0").unwrap());
+    /// assert!(first_nightly > Version::parse("1.69.0").unwrap());
+    /// assert!(Version::parse("1.70.0-beta").unwrap() >= first_nightly);
+    ///
+    /// let beta = first_nightly.with_pre(PreRelease::beta(Some(2)));
+    /// assert_eq!(beta.to_string(), "1.70.0-beta.2");
+    /// ```
+    pub fn with_pre(&self, pre: PreRelease) -> Version {
+        Version(self.0, pre.0)
+    }
+
+    /// Returns the release channel of `self` as determined by its pre-release
+    /// tag: `dev`, `nightly`, `beta`, or, if there is no tag, `stable`.
+    ///
+    /// Because the channel is derived from the version, a version read with
+    /// [`Version::read()`] yields the channel without invoking `rustc` again.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::{Version, Channel};
+    ///
+    /// let version = Version::parse("1.70.0-beta.3").unwrap();
+    /// assert_eq!(version.channel(), Channel::beta());
+    ///
+    /// assert_eq!(Version::parse("1.52.0-nightly").unwrap().channel(), Channel::nightly());
+    /// assert_eq!(Version::parse("1.3.0-dev").unwrap().channel(), Channel::dev());
+    /// assert_eq!(Version::parse("1.52.0").unwrap().channel(), Channel::stable());
+    ///
+    /// if let Some(version) = Version::read() {
+    ///     assert_eq!(Some(version.channel()), Channel::read());
+    /// }
+    /// ```
+    pub fn channel(&self) -> ::Channel {
+        match self.pre_release() {
+            Some(pre) => pre.channel(),
+            None => ::Channel::stable(),
+        }
+    }
+
+    /// Returns the `(major, minor, patch)` version components of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// assert_eq!(Version::parse("1.35.0").unwrap().to_mmp(), (1, 35, 0));
+    /// assert_eq!(Version::parse("1.33.0").unwrap().to_mmp(), (1, 33, 0));
+    /// assert_eq!(Version::parse("1.35.1").unwrap().to_mmp(), (1, 35, 1));
+    /// assert_eq!(Version::parse("1.13.2").unwrap().to_mmp(), (1, 13, 2));
+    /// ```
+    pub fn to_mmp(&self) -> (u16, u16, u16) {
+        let major = self.0 >> 32;
+        let minor = self.0 >> 16;
+        let patch = self.0;
+        (major as u16, minor as u16, patch as u16)
+    }
+
+    /// Returns the `major.minor.patch` components of `self` packed into a
+    /// `u64`, ignoring any pre-release tag.
+    ///
+    /// The packing is `major << 32 | minor << 16 | patch`: bits `32..48` hold
+    /// the major version, bits `16..32` the minor version, bits `0..16` the
+    /// patch version, and bits `48..64` are zero. This scheme is stable, so
+    /// packed versions can be persisted, and integer comparison of packed
+    /// versions agrees with comparison of their
+    /// [stripped](Version::stripped()) versions. See [`Version::from_u64()`]
+    /// for the inverse.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// assert_eq!(Version::parse("1.70.2").unwrap().to_u64(), 0x0001_0046_0002);
+    /// assert_eq!(Version::parse("1.70.2-nightly").unwrap().to_u64(), 0x0001_0046_0002);
+    /// ```
+    pub fn to_u64(&self) -> u64 {
+        self.0
+    }
+
+    /// Creates a release `Version` from `packed`, a `major.minor.patch` version
+    /// packed as by [`Version::to_u64()`]. Bits `48..64` of `packed` are
+    /// ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// assert_eq!(Version::from_u64(0x0001_0046_0002), Version::from_mmp(1, 70, 2));
+    ///
+    /// let version = Version::parse("1.56.1").unwrap();
+    /// assert_eq!(Version::from_u64(version.to_u64()), version);
+    /// ```
+    pub fn from_u64(packed: u64) -> Version {
+        Version::from_mmp((packed >> 32) as u16, (packed >> 16) as u16, packed as u16)
+    }
+
+    /// Returns `self` formatted as `major.minor.patch`, without any
+    /// pre-release tag, as expected by `rustc`'s `cfg(version("..."))`
+    /// predicate. This is suitable for embedding in a `--cfg` flag or a source
+    /// `cfg(version(...))` attribute.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// let version = Version::parse("1.70.0-nightly").unwrap();
+    /// assert_eq!(version.to_cfg_value(), "1.70.0");
+    /// assert_eq!(Version::parse("1.56").unwrap().to_cfg_value(), "1.56.0");
+    ///
+    /// let attr = format!("#[cfg(version(\"{}\"))]", version.to_cfg_value());
+    /// assert_eq!(attr, r#"#[cfg(version("1.70.0"))]"#);
+    /// ```
+    pub fn to_cfg_value(&self) -> String {
+        self.stripped().to_string()
+    }
+
+    /// Returns `self` formatted in shorthand, as in `1.70` for `1.70.0`: a zero
+    /// patch version is omitted, as is the minor version if it is zero, too.
+    /// A non-zero patch version keeps every component, and pre-release
+    /// versions are formatted in full, as by `Display`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// let v = |s| Version::parse(s).unwrap();
+    /// assert_eq!(v("1.70.0").display_short(), "1.70");
+    /// assert_eq!(v("1.70.1").display_short(), "1.70.1");
+    /// assert_eq!(v("2.0.0").display_short(), "2");
+    /// assert_eq!(v("1.0.3").display_short(), "1.0.3");
+    /// assert_eq!(v("1.70.0-nightly").display_short(), "1.70.0-nightly");
+    ///
+    /// // `Display` always keeps the full form.
+    /// assert_eq!(v("1.70.0").to_string(), "1.70.0");
+    /// ```
+    pub fn display_short(&self) -> String {
+        match (self.to_mmp(), self.1) {
+            ((major, 0, 0), Pre::Release) => major.to_string(),
+            ((major, minor, 0), Pre::Release) => format!("{}.{}", major, minor),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Returns the next minor version: `self` with the minor version
+    /// incremented, the patch version zeroed, and the pre-release tag, if any,
+    /// removed. If the minor version is already at its maximum, returns
+    /// [`Version::next_major()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// let version = Version::parse("1.50.3").unwrap();
+    /// assert_eq!(version.next_minor(), Version::parse("1.51.0").unwrap());
+    ///
+    /// let version = Version::parse("1.50.0-nightly").unwrap();
+    /// assert_eq!(version.next_minor(), Version::parse("1.51.0").unwrap());
+    /// ```
+    pub fn next_minor(&self) -> Version {
+        match self.to_mmp() {
+            (_, ::std::u16::MAX, _) => self.next_major(),
+            (major, minor, _) => Version::from_mmp(major, minor + 1, 0),
+        }
+    }
+
+    /// Returns the next major version: `self` with the major version
+    /// incremented, the minor and patch versions zeroed, and the pre-release
+    /// tag, if any, removed. The major version saturates at its maximum.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// let version = Version::parse("1.50.3").unwrap();
+    /// assert_eq!(version.next_major(), Version::parse("2.0.0").unwrap());
+    ///
+    /// // A caret-style compatibility check: `>= 1.50.3, < 2.0.0`.
+    /// let min = Version::parse("1.50.3").unwrap();
+    /// let version = Version::parse("1.62.1").unwrap();
+    /// assert!(version >= min && version < min.next_major());
+    /// ```
+    pub fn next_major(&self) -> Version {
+        let (major, _, _) = self.to_mmp();
+        Version::from_mmp(major.saturating_add(1), 0, 0)
+    }
+
+    /// Returns the version nearest to `self` within `min..=max`: `min` if `self`
+    /// is less than `min`, `max` if `self` is greater than `max`, and `self`
+    /// otherwise. If `min` is greater than `max`, returns `min`.
+    ///
+    /// Versions are compared as by `Ord`, so pre-release tags are respected:
+    /// `1.70.0-nightly` is less than `1.70.0-beta`, which is less than
+    /// `1.70.0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// let min = Version::parse("1.56.0").unwrap();
+    /// let max = Version::parse("1.70.0").unwrap();
+    ///
+    /// let v = |s| Version::parse(s).unwrap();
+    /// assert_eq!(v("1.31.0").clamp_to(&min, &max), min);
+    /// assert_eq!(v("1.65.0").clamp_to(&min, &max), v("1.65.0"));
+    /// assert_eq!(v("1.80.1").clamp_to(&min, &max), max);
+    /// assert_eq!(v("1.56.0-nightly").clamp_to(&min, &max), min);
+    /// ```
+    pub fn clamp_to(&self, min: &Version, max: &Version) -> Version {
+        if self < min {
+            *min
+        } else if self > max {
+            if max < min { *min } else { *max }
+        } else {
+            *self
+        }
     }
 
-    /// Returns the `(major, minor, patch)` version components of `self`.
+    /// Returns an iterator over minor releases from the minor release of `self`
+    /// up to, but excluding, `end`.
+    ///
+    /// The first version yielded is `self` with the patch version zeroed. Each
+    /// subsequent version is the [`next_minor()`](Version::next_minor()) of the
+    /// last. Pre-release tags on `self` and `end` are ignored. The computation
+    /// is purely numeric; it does not consult `rustc` or any list of releases.
     ///
     /// # Example
     ///
     /// ```rust
     /// use version_check::Version;
     ///
-    /// assert_eq!(Version::parse("1.35.0").unwrap().to_mmp(), (1, 35, 0));
-    /// assert_eq!(Version::parse("1.33.0").unwrap().to_mmp(), (1, 33, 0));
-    /// assert_eq!(Version::parse("1.35.1").unwrap().to_mmp(), (1, 35, 1));
-    /// assert_eq!(Version::parse("1.13.2").unwrap().to_mmp(), (1, 13, 2));
+    /// let start = Version::parse("1.50.2").unwrap();
+    /// let end = Version::parse("1.53.0").unwrap();
+    /// let versions: Vec<_> = start.minors_until(&end).map(|v| v.to_string()).collect();
+    /// assert_eq!(versions, ["1.50.0", "1.51.0", "1.52.0"]);
+    ///
+    /// assert_eq!(end.minors_until(&start).count(), 0);
     /// ```
-    pub fn to_mmp(&self) -> (u16, u16, u16) {
-        let major = self.0 >> 32;
-        let minor = self.0 >> 16;
-        let patch = self.0;
-        (major as u16, minor as u16, patch as u16)
+    pub fn minors_until(&self, end: &Version) -> Minors {
+        let (major, minor, _) = self.to_mmp();
+        Minors { next: Some(Version::from_mmp(major, minor, 0)), end: end.stripped() }
     }
 
     /// Returns `true` if `self` is greater than or equal to `version`.
     ///
     /// If `version` is greater than `self`, or if `version` is not a valid Rust
-    /// version string, returns `false`.
+    /// version string, returns `false`. Pre-release tags are ignored.
     ///
     /// # Example
     ///
@@ -134,14 +857,14 @@ impl Version {
     /// ```
     pub fn at_least(&self, version: &str) -> bool {
         Version::parse(version)
-            .map(|version| self >= &version)
+            .map(|version| self.stripped() >= version.stripped())
             .unwrap_or(false)
     }
 
     /// Returns `true` if `self` is less than or equal to `version`.
     ///
     /// If `version` is less than `self`, or if `version` is not a valid Rust
-    /// version string, returns `false`.
+    /// version string, returns `false`. Pre-release tags are ignored.
     ///
     /// # Example
     ///
@@ -159,14 +882,14 @@ impl Version {
     /// ```
     pub fn at_most(&self, version: &str) -> bool {
         Version::parse(version)
-            .map(|version| self <= &version)
+            .map(|version| self.stripped() <= version.stripped())
             .unwrap_or(false)
     }
 
     /// Returns `true` if `self` is exactly equal to `version`.
     ///
     /// If `version` is not equal to `self`, or if `version` is not a valid Rust
-    /// version string, returns `false`.
+    /// version string, returns `false`. Pre-release tags are ignored.
     ///
     /// # Example
     ///
@@ -183,28 +906,340 @@ impl Version {
     /// ```
     pub fn exactly(&self, version: &str) -> bool {
         Version::parse(version)
-            .map(|version| self == &version)
+            .map(|version| self.stripped() == version.stripped())
             .unwrap_or(false)
     }
+
+    /// Returns `true` if `self` satisfies every comparator in `range`.
+    ///
+    /// `range` is a comma-separated list of comparators, each an operator,
+    /// one of `>=`, `>`, `<=`, `<`, `=`, `^`, or `~`, followed by a version, as
+    /// in `>=1.70.0-beta.2, <1.70.0`. As in Cargo, a comparator without an
+    /// operator is a `^` comparator. Unlike [`Version::at_least()`] and
+    /// friends, comparators respect pre-release tags, including beta ordinals,
+    /// using the ordering of `Ord` for `Version`.
+    ///
+    /// Comparators are interpreted as requirements in Cargo, where a version
+    /// with fewer than three components stands for every version it is a
+    /// prefix of:
+    ///
+    ///   * `=1.70` is `>=1.70.0, <1.71.0`; `=1` is `>=1.0.0, <2.0.0`.
+    ///   * `>1.70` is `>=1.71.0`; `>1` is `>=2.0.0`.
+    ///   * `<=1.70` is `<1.71.0`; `<=1` is `<2.0.0`.
+    ///   * `>=1.70` and `<1.70` are `>=1.70.0` and `<1.70.0`.
+    ///   * `^1.70.3`, `^1.70`, and `^1` are below `2.0.0`; `^0.2.3` and `^0.2`
+    ///     are below `0.3.0`; `^0.0.3` is below `0.0.4`; `^0.0` is below
+    ///     `0.1.0`; and `^0` is below `1.0.0`. Each is at least the version
+    ///     given.
+    ///   * `~1.70.3` and `~1.70` are below `1.71.0`; `~1` is below `2.0.0`.
+    ///     Each is at least the version given.
+    ///
+    /// Wildcards, such as `1.70.*`, and other operators are not supported.
+    /// Returns `None` if `range` is empty or any comparator is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// let range = ">=1.70.0-beta.2, <1.70.0";
+    /// assert_eq!(Version::parse("1.70.0-beta.4").unwrap().in_range(range), Some(true));
+    /// assert_eq!(Version::parse("1.70.0-beta.1").unwrap().in_range(range), Some(false));
+    /// assert_eq!(Version::parse("1.70.0").unwrap().in_range(range), Some(false));
+    ///
+    /// let version = Version::parse("1.70.2").unwrap();
+    /// assert_eq!(version.in_range("^1.56"), Some(true));
+    /// assert_eq!(version.in_range("~1.70.1"), Some(true));
+    /// assert_eq!(version.in_range("~1.69"), Some(false));
+    /// assert_eq!(version.in_range("=1.70"), Some(true));
+    /// assert_eq!(version.in_range("1.56"), Some(true));
+    ///
+    /// assert_eq!(Version::parse("1.70.0").unwrap().in_range(">= one.two"), None);
+    /// assert_eq!(Version::parse("1.70.0").unwrap().in_range("!=1.69.0"), None);
+    /// ```
+    pub fn in_range(&self, range: &str) -> Option<bool> {
+        let mut satisfied = true;
+        for comparator in range.split(',') {
+            let comparator = comparator.trim();
+            let (op, version_str) = match ["<=", ">=", "<", ">", "=", "^", "~"].iter()
+                .find(|op| comparator.starts_with(**op))
+            {
+                Some(op) => (*op, comparator[op.len()..].trim()),
+                None => ("^", comparator),
+            };
+
+            let version = match Version::parse(version_str) {
+                Some(version) => version,
+                None => return None,
+            };
+
+            // The number of components given determines the bounds of partial
+            // versions and of caret and tilde comparators.
+            let components = version_str.split(|c| c == '-' || c == '+')
+                .next()
+                .map_or(0, |numbers| numbers.split('.').count());
+
+            let partial_bound = match components {
+                1 => Some(version.next_major()),
+                2 => Some(version.next_minor()),
+                _ => None,
+            };
+
+            satisfied = satisfied && match (op, partial_bound) {
+                ("<=", Some(bound)) => *self < bound,
+                ("<=", None) => *self <= version,
+                (">=", _) => *self >= version,
+                ("<", _) => *self < version,
+                (">", Some(bound)) => *self >= bound,
+                (">", None) => *self > version,
+                ("=", Some(bound)) => *self >= version && *self < bound,
+                ("=", None) => *self == version,
+                ("~", _) => *self >= version && *self < version.tilde_bound(components),
+                _ => *self >= version && *self < version.caret_bound(components),
+            };
+        }
+
+        Some(satisfied)
+    }
+}
+
+impl Version {
+    /// Returns the exclusive upper bound of the caret requirement `^self`
+    /// written with `components` components.
+    fn caret_bound(&self, components: usize) -> Version {
+        match (self.to_mmp(), components) {
+            ((0, 0, patch), 3) => Version::from_mmp(0, 0, patch.saturating_add(1)),
+            ((0, _, _), 2) | ((0, _, _), 3) => self.next_minor(),
+            _ => self.next_major(),
+        }
+    }
+
+    /// Returns the exclusive upper bound of the tilde requirement `~self`
+    /// written with `components` components.
+    fn tilde_bound(&self, components: usize) -> Version {
+        match components {
+            1 => self.next_major(),
+            _ => self.next_minor(),
+        }
+    }
+}
+
+/// An iterator over minor releases, as returned by [`Version::minors_until()`].
+#[derive(Debug, Clone)]
+pub struct Minors {
+    next: Option<Version>,
+    end: Version,
+}
+
+impl Iterator for Minors {
+    type Item = Version;
+
+    fn next(&mut self) -> Option<Version> {
+        let current = match self.next {
+            Some(version) if version < self.end => version,
+            _ => return None,
+        };
+
+        // `next_minor()` saturates; stop rather than yield the same version.
+        let next = current.next_minor();
+        self.next = if next > current { Some(next) } else { None };
+        Some(current)
+    }
 }
 
 impl fmt::Display for Version {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let (major, minor, patch) = self.to_mmp();
-        write!(f, "{}.{}.{}", major, minor, patch)
+        try!(write!(f, "{}.{}.{}", major, minor, patch));
+        match self.1 {
+            Pre::Release => Ok(()),
+            pre => write!(f, "-{}", pre),
+        }
     }
 }
 
 impl fmt::Debug for Version {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // We don't use `debug_*` because it's not available in `1.0.0`.
-        write!(f, "Version({:?}, {:?})", self.0, self.to_mmp())
+        let (major, minor, patch) = self.to_mmp();
+        try!(write!(f, "Version {{ major: {:?}, minor: {:?}, patch: {:?}, ", major, minor, patch));
+        match self.1 {
+            Pre::Release => write!(f, "pre: None }}"),
+            pre => write!(f, "pre: Some({:?}) }}", pre.to_string()),
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = ParseVersionError;
+
+    /// Parses a version as in [`Version::parse()`]. On failure, the error
+    /// reports the byte index in `s` at which parsing failed and why.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// let version: Version = "1.52.0-nightly".parse().unwrap();
+    /// assert_eq!(version, Version::parse("1.52.0-nightly").unwrap());
+    ///
+    /// let error = "1.x.0".parse::<Version>().unwrap_err();
+    /// assert_eq!(error.index(), 2);
+    /// assert_eq!(error.reason(), "expected a digit");
+    /// ```
+    fn from_str(s: &str) -> Result<Version, ParseVersionError> {
+        Version::parse_with_error(s)
+    }
+}
+
+impl PartialEq<str> for Version {
+    /// Parses `other` and compares. Returns `false` if `other` is not a valid
+    /// Rust version string.
+    fn eq(&self, other: &str) -> bool {
+        Version::parse(other).map(|other| *self == other).unwrap_or(false)
+    }
+}
+
+impl<'a> PartialEq<&'a str> for Version {
+    fn eq(&self, other: &&'a str) -> bool {
+        *self == **other
+    }
+}
+
+impl PartialEq<(u64, u64, u64)> for Version {
+    /// Compares `major.minor.patch` to `other`. A version with a pre-release
+    /// tag is never equal to a tuple.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::Version;
+    ///
+    /// assert_eq!(Version::parse("1.70.0").unwrap(), (1, 70, 0));
+    /// assert!(Version::parse("1.70.0-beta").unwrap() != (1, 70, 0));
+    /// ```
+    fn eq(&self, other: &(u64, u64, u64)) -> bool {
+        let (major, minor, patch) = self.to_mmp();
+        self.1 == Pre::Release && (major as u64, minor as u64, patch as u64) == *other
+    }
+}
+
+impl PartialOrd<str> for Version {
+    /// Parses `other` and compares. Returns `None` if `other` is not a valid
+    /// Rust version string.
+    fn partial_cmp(&self, other: &str) -> Option<Ordering> {
+        Version::parse(other).and_then(|other| self.partial_cmp(&other))
+    }
+}
+
+impl<'a> PartialOrd<&'a str> for Version {
+    fn partial_cmp(&self, other: &&'a str) -> Option<Ordering> {
+        self.partial_cmp(*other)
+    }
+}
+
+/// An error parsing a [`Version`] from a string.
+///
+/// Reports the byte index in the input at which parsing failed and a short
+/// description of what was expected there.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ParseVersionError {
+    index: usize,
+    reason: &'static str,
+    kind: VersionParseKind,
+}
+
+impl ParseVersionError {
+    fn new(index: usize, reason: &'static str, kind: VersionParseKind) -> ParseVersionError {
+        ParseVersionError { index: index, reason: reason, kind: kind }
+    }
+
+    /// Returns the kind of error, as returned by [`Version::parse_detailed()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use version_check::{Version, VersionParseKind};
+    ///
+    /// let error = "1.70.99999".parse::<Version>().unwrap_err();
+    /// assert_eq!(error.kind(), VersionParseKind::Overflow);
+    /// assert_eq!(error.index(), 5);
+    /// ```
+    pub fn kind(&self) -> VersionParseKind {
+        self.kind
+    }
+
+    /// Returns the byte index in the input at which parsing failed.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns a short description of why parsing failed, such as `expected a
+    /// digit`.
+    pub fn reason(&self) -> &'static str {
+        self.reason
+    }
+}
+
+impl fmt::Display for ParseVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid version at byte {}: {}", self.index, self.reason)
+    }
+}
+
+impl error::Error for ParseVersionError {
+    fn description(&self) -> &str {
+        self.reason
+    }
+}
+
+/// The kind of error encountered when parsing a [`Version`], as returned by
+/// [`Version::parse_detailed()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VersionParseKind {
+    /// The input is empty.
+    Empty,
+    /// The major version is empty, as in `.70.0` or `-nightly`.
+    MissingMajor,
+    /// The minor version is empty, as in `1.` or `1..0`.
+    MissingMinor,
+    /// The patch version is empty, as in `1.70.` or `1.70.-nightly`.
+    MissingPatch,
+    /// A component contains something other than ASCII digits, as in `1.x`,
+    /// `1. 70`, or `1.7_0`, or a component past the patch version is empty,
+    /// as in `1.70.0.`.
+    BadComponent,
+    /// A component exceeds `65535`, as in `1.70.99999`.
+    Overflow,
+    /// The build metadata is empty or invalid, as in `1.70.0+` or
+    /// `1.70.0+a b`.
+    BadMetadata,
+}
+
+impl fmt::Display for VersionParseKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(error::Error::description(self))
+    }
+}
+
+impl error::Error for VersionParseKind {
+    fn description(&self) -> &str {
+        match *self {
+            VersionParseKind::Empty => "empty version",
+            VersionParseKind::MissingMajor => "missing major version",
+            VersionParseKind::MissingMinor => "missing minor version",
+            VersionParseKind::MissingPatch => "missing patch version",
+            VersionParseKind::BadComponent => "invalid version component",
+            VersionParseKind::Overflow => "version component exceeds 65535",
+            VersionParseKind::BadMetadata => "invalid build metadata",
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Version;
+    use super::{Version, PreRelease, VersionParseKind};
 
     macro_rules! assert_to_mmp {
         // We don't use `.into::<Option<_>>` because it's not available in 1.0.
@@ -238,6 +1273,11 @@ mod tests {
         assert_to_mmp!("1.4.4-nightly (d84693b93 2017-07-09))", (1, 4, 4));
         assert_to_mmp!("1.58879.4478-dev", (1, 58879, 4478));
         assert_to_mmp!("1.58879.4478-dev (d84693b93 2017-07-09))", (1, 58879, 4478));
+        assert_to_mmp!("1.70", (1, 70, 0));
+        assert_to_mmp!("1.70.0.1", (1, 70, 0));
+        assert_to_mmp!("1.70.0.1.2", (1, 70, 0));
+        assert_to_mmp!("1.70.0.99999999999999999999", (1, 70, 0));
+        assert_to_mmp!("1.70.0.1-nightly", (1, 70, 0));
     }
 
     #[test]
@@ -250,7 +1290,150 @@ mod tests {
         assert_to_mmp!(".", None);
         assert_to_mmp!("one", None);
         assert_to_mmp!("1.", None);
-        assert_to_mmp!("1.2.3.4.5.6", None);
+        assert_to_mmp!("1.2.3.4.5.x", None);
+        assert_to_mmp!("1.2.3.", None);
+        assert_to_mmp!("1.2.3.4.", None);
+        assert_to_mmp!("abc", None);
+        assert_to_mmp!("rustc", None);
+        assert_to_mmp!("v.1.2", None);
+        assert_to_mmp!("1..2", None);
+        assert_to_mmp!("..", None);
+        assert_to_mmp!("x1.2.3", None);
+        assert_to_mmp!("1.2x.3", None);
+        assert_to_mmp!("\u{e9}1.2.3", None);
+        assert_to_mmp!("1_.70.0", None);
+        assert_to_mmp!("1.7_0.0", None);
+        assert_to_mmp!("1_000.0.0", None);
+        assert_to_mmp!("_1.70.0", None);
+        assert_to_mmp!("1.70.0_", None);
+        assert_to_mmp!("1.70.0._", None);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = |s: &str| {
+            let e = s.parse::<Version>().unwrap_err();
+            (e.index(), e.reason())
+        };
+
+        assert_eq!(error(""), (0, "expected a number"));
+        assert_eq!(error("1."), (2, "expected a number"));
+        assert_eq!(error("1..2"), (2, "expected a number"));
+        assert_eq!(error("one.two"), (0, "expected a digit"));
+        assert_eq!(error("1. 2"), (2, "expected a digit"));
+        assert_eq!(error("1.2x.3"), (3, "expected a digit"));
+        assert_eq!(error("-1.2.3"), (0, "expected a number"));
+        assert_eq!(error("1.65536.2"), (2, "number exceeds 65535"));
+        assert_eq!(error("1.2.3."), (6, "expected a number"));
+        assert_eq!(error("1.2.3.x"), (6, "expected a digit"));
+        assert_eq!(error("1.2.3.4.5a-nightly"), (9, "expected a digit"));
+        assert_eq!(error("1.2.x-nightly"), (4, "expected a digit"));
+        assert_eq!(error("1.2.3+"), (6, "expected build metadata"));
+        assert_eq!(error("1.2.3+a b"), (7, "invalid build metadata"));
+        assert_eq!(error("1.2.3+a+b"), (7, "invalid build metadata"));
+        assert_eq!(error("1.x.3+abc"), (2, "expected a digit"));
+        assert_eq!(error("1_.70.0"), (1, "unexpected `_`"));
+        assert_eq!(error("1.7_0.0"), (3, "unexpected `_`"));
+        assert_eq!(error("_1.70.0"), (0, "unexpected `_`"));
+        assert_eq!(error("1.70.0+a_b"), (8, "invalid build metadata"));
+
+        for s in &["1", "1.2", "1.2.3", "1.2.3-nightly", "1.2.3-beta.4", "1.2.3.4"] {
+            assert_eq!(s.parse::<Version>().ok(), Version::parse(s));
+        }
+    }
+
+    #[test]
+    fn test_parse_detailed() {
+        use self::VersionParseKind::*;
+
+        let kind = |s: &str| Version::parse_detailed(s).err();
+
+        assert_eq!(kind(""), Some(Empty));
+        assert_eq!(kind("-nightly"), Some(MissingMajor));
+        assert_eq!(kind(".70.0"), Some(MissingMajor));
+        assert_eq!(kind("-1.2.3"), Some(MissingMajor));
+        assert_eq!(kind("1."), Some(MissingMinor));
+        assert_eq!(kind("1..0"), Some(MissingMinor));
+        assert_eq!(kind("1.-nightly"), Some(MissingMinor));
+        assert_eq!(kind("1.70."), Some(MissingPatch));
+        assert_eq!(kind("1.70.-nightly"), Some(MissingPatch));
+        assert_eq!(kind("1.70.0."), Some(BadComponent));
+        assert_eq!(kind("1.x"), Some(BadComponent));
+        assert_eq!(kind("1. 70"), Some(BadComponent));
+        assert_eq!(kind("1.7_0"), Some(BadComponent));
+        assert_eq!(kind(" "), Some(BadComponent));
+        assert_eq!(kind("rustc"), Some(BadComponent));
+        assert_eq!(kind("1.70.99999"), Some(Overflow));
+        assert_eq!(kind("65536"), Some(Overflow));
+        assert_eq!(kind("1.70.0+"), Some(BadMetadata));
+        assert_eq!(kind("1.70.0+a b"), Some(BadMetadata));
+
+        for s in &["1", "1.70", "1.70.0", "1.70.0-beta.2", "1.70.0.1", "1.70.0+abc"] {
+            assert_eq!(Version::parse_detailed(s).ok(), Version::parse(s));
+        }
+
+        assert_eq!(MissingMinor.to_string(), "missing minor version");
+        assert_eq!("1.".parse::<Version>().unwrap_err().kind(), MissingMinor);
+    }
+
+    #[test]
+    fn test_build_metadata() {
+        let version = |s| Version::parse(s).unwrap();
+        assert_eq!(version("1.70.0+a"), version("1.70.0+b"));
+        assert_eq!(version("1.70.0+abc123"), version("1.70.0"));
+        assert_eq!(version("1.70+exp.sha.5114f85"), version("1.70.0"));
+        assert_eq!(version("1.70.0-beta.2+build-7"), version("1.70.0-beta.2"));
+        assert_eq!(version("1.70.0-nightly+x").channel(), ::Channel::nightly());
+        assert_eq!(version("1.70.0+20230531").to_string(), "1.70.0");
+        assert!(version("1.70.0+a") < version("1.70.1+a"));
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_to_mmp!("65535.65535.65535", (65535, 65535, 65535));
+        assert_to_mmp!("65536.0.0", None);
+        assert_to_mmp!("1.0.65536", None);
+        assert_to_mmp!("9999999999999999999.0.0", None);
+        assert_to_mmp!("1.99999999999999999999999999999999999999.0", None);
+        assert_to_mmp!("0.0.18446744073709551616", None);
+
+        let long_digits: String = ::std::iter::repeat('9').take(100_000).collect();
+        assert_to_mmp!(&long_digits, None);
+        assert_to_mmp!(&format!("1.{}", long_digits), None);
+        assert_to_mmp!(&format!("1.2.3-{}", long_digits), (1, 2, 3));
+
+        let long_dots: String = ::std::iter::repeat("1.").take(100_000).collect();
+        assert_to_mmp!(&long_dots, None);
+        assert_to_mmp!("\u{0}.\u{ffff}.\u{10ffff}", None);
+        assert_to_mmp!("١.٢.٣", None);
+    }
+
+    #[test]
+    fn test_u64_round_trip() {
+        let versions = ["0.0.0", "0.0.1", "0.1.0", "1.0.0", "1.18.0", "1.70.2", "1.58879.4478",
+            "65535.65535.65535", "1.70.0-nightly", "1.70.0-beta.3", "1.70.0-dev"];
+
+        for (i, a) in versions.iter().enumerate() {
+            let version = Version::parse(a).unwrap();
+            assert_eq!(Version::from_u64(version.to_u64()), version.stripped());
+            assert_eq!(Version::from_u64(version.to_u64()).to_u64(), version.to_u64());
+
+            for b in &versions[i..] {
+                let other = Version::parse(b).unwrap();
+                let cmp = version.stripped().cmp(&other.stripped());
+                assert_eq!(version.to_u64().cmp(&other.to_u64()), cmp);
+            }
+        }
+
+        for &(major, minor, patch) in &[(0, 0, 0), (1, 2, 3), (65535, 0, 1), (1, 65535, 65535)] {
+            let version = Version::from_mmp(major, minor, patch);
+            assert_eq!(version.to_u64() >> 48, 0);
+            assert_eq!(Version::from_u64(version.to_u64()).to_mmp(), (major, minor, patch));
+        }
+
+        assert_eq!(Version::from_u64(0xFFFF_0001_0002_0003), Version::from_mmp(1, 2, 3));
+        assert_eq!(Version::from_u64(0), Version::parse("0.0.0").unwrap());
+        assert_eq!(Version::from_u64(::std::u64::MAX).to_mmp(), (65535, 65535, 65535));
     }
 
     #[test]
@@ -298,6 +1481,370 @@ mod tests {
         assert!(Version::parse("one.two.three").is_none());
     }
 
+    #[test]
+    fn test_pre_release() {
+        let dev = Version::parse("1.50.0-dev").unwrap();
+        let nightly = Version::parse("1.50.0-nightly").unwrap();
+        let beta = Version::parse("1.50.0-beta").unwrap();
+        let stable = Version::parse("1.50.0").unwrap();
+
+        assert!(dev < nightly);
+        assert!(nightly < beta);
+        assert!(beta < stable);
+        assert!(stable < Version::parse("1.50.1-nightly").unwrap());
+        assert!(Version::parse("1.49.0").unwrap() < dev);
+
+        assert!(nightly != stable);
+        assert_eq!(Version::parse("1.50.0-stable"), Some(stable));
+        assert_eq!(Version::parse("1.4.4-nightly (d84693b93 2017-07-09))"),
+            Version::parse("1.4.4-nightly"));
+
+        assert!(dev.channel().is_dev());
+        assert!(nightly.channel().is_nightly());
+        assert!(beta.channel().is_beta());
+        assert!(stable.channel().is_stable());
+        assert!(stable.stripped().channel().is_stable());
+
+        let pre = |v: &Version| v.pre_release().map(|p| p.to_string());
+        assert_eq!(pre(&dev), Some("dev".to_string()));
+        assert_eq!(pre(&nightly), Some("nightly".to_string()));
+        assert_eq!(pre(&beta), Some("beta".to_string()));
+        assert_eq!(pre(&stable), None);
+        assert_eq!(pre(&Version::parse("1.50.0-beta.4").unwrap()), Some("beta.4".to_string()));
+        assert!(dev.pre_release() < nightly.pre_release());
+        assert!(beta.pre_release() < Version::parse("1.50.0-beta.1").unwrap().pre_release());
+
+        for v in &[dev, nightly, beta, stable] {
+            if let Some(pre) = v.pre_release() {
+                assert_eq!(stable.with_pre(pre), *v);
+                assert_eq!(v.with_pre(pre), *v);
+            }
+        }
+
+        let beta_3 = PreRelease::beta(Some(3));
+        assert_eq!(dev.with_pre(beta_3), Version::parse("1.50.0-beta.3").unwrap());
+        assert_eq!(dev.with_pre(PreRelease::nightly()), nightly);
+        assert_eq!(stable.with_pre(PreRelease::dev()), dev);
+        for v in &[dev, nightly, beta, stable] {
+            assert_eq!(v.stripped(), stable);
+            assert!(v.exactly("1.50.0"));
+            assert!(v.at_least("1.50.0-beta"));
+            assert!(v.at_most("1.50.0-nightly"));
+        }
+    }
+
+    #[test]
+    fn test_min_max() {
+        use std::cmp::{min, max};
+
+        let v = |s| Version::parse(s).unwrap();
+
+        assert_eq!(max(v("1.70.0"), v("1.71.0-nightly")), v("1.71.0-nightly"));
+        assert_eq!(min(v("1.70.0"), v("1.71.0-nightly")), v("1.70.0"));
+        assert_eq!(max(v("1.70.0"), v("1.70.0-nightly")), v("1.70.0"));
+        assert_eq!(min(v("1.70.0"), v("1.70.0-nightly")), v("1.70.0-nightly"));
+        assert_eq!(max(v("1.70.0-beta.1"), v("1.70.0-nightly")), v("1.70.0-beta.1"));
+        assert_eq!(max(v("1.70.0-beta.1"), v("1.70.0-beta.3")), v("1.70.0-beta.3"));
+        assert_eq!(max(v("1.70.1-dev"), v("1.70.0")), v("1.70.1-dev"));
+
+        let versions = [v("1.69.0"), v("1.71.0-nightly"), v("1.70.0"), v("1.70.0-beta")];
+        assert_eq!(versions.iter().max(), Some(&v("1.71.0-nightly")));
+        assert_eq!(versions.iter().min(), Some(&v("1.69.0")));
+    }
+
+    #[test]
+    fn test_display_short() {
+        let short = |s| Version::parse(s).unwrap().display_short();
+
+        assert_eq!(short("1.70.0"), "1.70");
+        assert_eq!(short("1.70"), "1.70");
+        assert_eq!(short("1.0.0"), "1");
+        assert_eq!(short("0.0.0"), "0");
+        assert_eq!(short("0.1.0"), "0.1");
+        assert_eq!(short("1.0.1"), "1.0.1");
+        assert_eq!(short("1.70.2"), "1.70.2");
+        assert_eq!(short("1.70.0-dev"), "1.70.0-dev");
+        assert_eq!(short("1.70.0-beta.2"), "1.70.0-beta.2");
+        assert_eq!(short("1.0.0-nightly"), "1.0.0-nightly");
+        assert_eq!(short("1.70.0+abc"), "1.70");
+    }
+
+    #[test]
+    fn test_clamp_to() {
+        let v = |s| Version::parse(s).unwrap();
+        let clamp = |s, min, max| v(s).clamp_to(&v(min), &v(max));
+
+        // Below the range.
+        assert_eq!(clamp("1.0.0", "1.56.0", "1.70.0"), v("1.56.0"));
+        assert_eq!(clamp("1.56.0-beta.2", "1.56.0", "1.70.0"), v("1.56.0"));
+        assert_eq!(clamp("1.70.0-nightly", "1.70.0-beta", "1.70.0"), v("1.70.0-beta"));
+
+        // Within the range.
+        assert_eq!(clamp("1.56.0", "1.56.0", "1.70.0"), v("1.56.0"));
+        assert_eq!(clamp("1.65.3", "1.56.0", "1.70.0"), v("1.65.3"));
+        assert_eq!(clamp("1.70.0-beta.1", "1.56.0", "1.70.0"), v("1.70.0-beta.1"));
+        assert_eq!(clamp("1.70.0", "1.56.0", "1.70.0"), v("1.70.0"));
+
+        // Above the range.
+        assert_eq!(clamp("1.70.1", "1.56.0", "1.70.0"), v("1.70.0"));
+        assert_eq!(clamp("1.70.0", "1.56.0", "1.70.0-nightly"), v("1.70.0-nightly"));
+        assert_eq!(clamp("2.0.0", "1.56.0", "1.70.0"), v("1.70.0"));
+
+        // An empty range.
+        assert_eq!(clamp("1.65.0", "1.70.0", "1.56.0"), v("1.70.0"));
+        assert_eq!(clamp("1.80.0", "1.70.0", "1.56.0"), v("1.70.0"));
+    }
+
+    #[test]
+    fn test_minors_until() {
+        let v = |s| Version::parse(s).unwrap();
+        let minors = |start, end| v(start).minors_until(&v(end)).collect::<Vec<_>>();
+
+        assert_eq!(minors("1.50.0", "1.52.0"), [v("1.50.0"), v("1.51.0")]);
+        assert_eq!(minors("1.50.7-nightly", "1.51.1"), [v("1.50.0"), v("1.51.0")]);
+        assert_eq!(minors("1.50.0", "1.52.0-nightly"), [v("1.50.0"), v("1.51.0")]);
+        assert_eq!(minors("1.65535.0", "2.1.0"), [v("1.65535.0"), v("2.0.0")]);
+        assert!(minors("1.50.0", "1.50.0").is_empty());
+        assert!(minors("1.52.0", "1.50.0").is_empty());
+        assert_eq!(minors("65535.65534.0", "65535.65535.65535"), [
+            v("65535.65534.0"), v("65535.65535.0")
+        ]);
+    }
+
+    #[test]
+    fn test_str_comparisons() {
+        let version = Version::parse("1.56.0").unwrap();
+        assert!(version == "1.56.0");
+        assert!(version == *"1.56");
+        assert!(version >= "1.56.0");
+        assert!(version > "1.50.0");
+        assert!(version < "1.56.1");
+        assert!(version > "1.56.0-nightly");
+        assert!(version != "1.56.0-beta");
+        assert!(version != "one.two");
+        assert_eq!(version.partial_cmp("one.two"), None);
+        assert_eq!(version.partial_cmp(""), None);
+    }
+
+    #[test]
+    fn test_beta_ordinal() {
+        let beta = |s| Version::parse(s).unwrap();
+
+        assert!(beta("1.70.0-beta") < beta("1.70.0-beta.1"));
+        assert!(beta("1.70.0-beta.1") < beta("1.70.0-beta.2"));
+        assert!(beta("1.70.0-beta.2") < beta("1.70.0-beta.10"));
+        assert!(beta("1.70.0-beta.10") < beta("1.70.0"));
+        assert!(beta("1.70.0-nightly") < beta("1.70.0-beta.1"));
+        assert!(beta("1.70.0-beta.4") != beta("1.70.0-beta.2"));
+        assert_eq!(beta("1.70.0-beta.4 (abcdef 2023-05-01)"), beta("1.70.0-beta.4"));
+        assert_eq!(beta("1.70.0-beta.x"), beta("1.70.0-beta"));
+        assert_eq!(beta("1.70.0-beta.99999"), beta("1.70.0-beta"));
+        assert!(beta("1.70.0-beta.4").exactly("1.70.0-beta.2"));
+        assert_eq!(beta("1.70.0-beta.4").stripped(), beta("1.70.0"));
+    }
+
+    #[test]
+    fn test_release_candidate() {
+        let version = |s| Version::parse(s).unwrap();
+
+        // The last betas before a release are release candidates in all but name.
+        assert_eq!(version("1.70.0-beta.7").channel(), ::Channel::beta());
+        assert_eq!(version("1.70.0-beta.7").pre_release(), Some(PreRelease::beta(Some(7))));
+        assert_eq!(::Channel::parse("1.70.0-beta.7"), Some(::Channel::beta()));
+        assert!(version("1.70.0-beta.6") < version("1.70.0-beta.7"));
+
+        // `rustc` never emits `rc`: it isn't recognized as a pre-release tag.
+        assert_eq!(version("1.70.0-rc.1").pre_release(), None);
+        assert_eq!(version("1.70.0-rc.1"), version("1.70.0"));
+    }
+
+    #[test]
+    fn test_in_range() {
+        let in_range = |v, r| Version::parse(v).unwrap().in_range(r);
+
+        let range = ">=1.70.0-beta.2, <1.70.0";
+        assert_eq!(in_range("1.70.0-beta.2", range), Some(true));
+        assert_eq!(in_range("1.70.0-beta.4", range), Some(true));
+        assert_eq!(in_range("1.70.0-beta.10", range), Some(true));
+        assert_eq!(in_range("1.70.0-beta.1", range), Some(false));
+        assert_eq!(in_range("1.70.0-beta", range), Some(false));
+        assert_eq!(in_range("1.70.0-nightly", range), Some(false));
+        assert_eq!(in_range("1.70.0", range), Some(false));
+
+        assert_eq!(in_range("1.70.0-beta.3", ">1.70.0-beta.2,<=1.70.0-beta.3"), Some(true));
+        assert_eq!(in_range("1.70.0-beta.2", ">1.70.0-beta.2,<=1.70.0-beta.3"), Some(false));
+        assert_eq!(in_range("1.70.0-beta.4", ">1.70.0-beta.2,<=1.70.0-beta.3"), Some(false));
+        assert_eq!(in_range("1.70.0-beta.3", "=1.70.0-beta.3"), Some(true));
+        assert_eq!(in_range("1.62.1", ">= 1.56, < 2"), Some(true));
+
+        assert_eq!(in_range("1.70.0", ""), None);
+        assert_eq!(in_range("1.70.0", ">=1.56,"), None);
+        assert_eq!(in_range("1.70.0", ">=1.56, <two"), None);
+        assert_eq!(in_range("1.70.0", "!=1.69"), None);
+        assert_eq!(in_range("1.70.0", "=>1.69"), None);
+        assert_eq!(in_range("1.70.0", "^"), None);
+        assert_eq!(in_range("1.70.0", "~x"), None);
+        assert_eq!(in_range("1.70.0", "1.70.*"), None);
+        assert_eq!(in_range("1.70.0", "*"), None);
+    }
+
+    #[test]
+    fn test_in_range_partial() {
+        let in_range = |v, r| Version::parse(v).unwrap().in_range(r);
+
+        assert_eq!(in_range("1.70.0", "=1.70"), Some(true));
+        assert_eq!(in_range("1.70.1", "=1.70"), Some(true));
+        assert_eq!(in_range("1.71.0", "=1.70"), Some(false));
+        assert_eq!(in_range("1.99.0", "=1"), Some(true));
+        assert_eq!(in_range("2.0.0", "=1"), Some(false));
+        assert_eq!(in_range("1.70.1", "=1.70.0"), Some(false));
+
+        assert_eq!(in_range("1.70.5", "<=1.70"), Some(true));
+        assert_eq!(in_range("1.71.0", "<=1.70"), Some(false));
+        assert_eq!(in_range("1.99.0", "<=1"), Some(true));
+        assert_eq!(in_range("1.70.1", "<=1.70.0"), Some(false));
+
+        assert_eq!(in_range("1.70.1", ">1.70"), Some(false));
+        assert_eq!(in_range("1.71.0", ">1.70"), Some(true));
+        assert_eq!(in_range("1.99.0", ">1"), Some(false));
+        assert_eq!(in_range("2.0.0", ">1"), Some(true));
+        assert_eq!(in_range("1.70.1", ">1.70.0"), Some(true));
+
+        assert_eq!(in_range("1.70.0", ">=1.70"), Some(true));
+        assert_eq!(in_range("1.70.0", "<1.70"), Some(false));
+        assert_eq!(in_range("1.69.9", "<1.70"), Some(true));
+
+        // A comparator without an operator is a caret comparator.
+        assert_eq!(in_range("1.70.0", "1.70"), Some(true));
+        assert_eq!(in_range("1.75.2", "1.70"), Some(true));
+        assert_eq!(in_range("1.69.0", "1.70"), Some(false));
+        assert_eq!(in_range("2.0.0", "1.70"), Some(false));
+        assert_eq!(in_range("1.70.0-beta.3", "1.70.0-beta.2"), Some(true));
+        assert_eq!(in_range("1.70.0-beta.1", "1.70.0-beta.2"), Some(false));
+    }
+
+    #[test]
+    fn test_in_range_caret() {
+        let in_range = |v, r| Version::parse(v).unwrap().in_range(r);
+
+        assert_eq!(in_range("1.70.3", "^1.70.3"), Some(true));
+        assert_eq!(in_range("1.99.0", "^1.70.3"), Some(true));
+        assert_eq!(in_range("1.70.2", "^1.70.3"), Some(false));
+        assert_eq!(in_range("2.0.0", "^1.70.3"), Some(false));
+        assert_eq!(in_range("1.70.0", "^1.70"), Some(true));
+        assert_eq!(in_range("1.69.9", "^1.70"), Some(false));
+        assert_eq!(in_range("1.0.0", "^1"), Some(true));
+        assert_eq!(in_range("2.0.0", "^1"), Some(false));
+        assert_eq!(in_range("1.70.0-nightly", "^1.70"), Some(false));
+        assert_eq!(in_range("1.70.0-beta.2", "^1.70.0-beta"), Some(true));
+
+        assert_eq!(in_range("0.2.5", "^0.2.3"), Some(true));
+        assert_eq!(in_range("0.3.0", "^0.2.3"), Some(false));
+        assert_eq!(in_range("0.2.0", "^0.2"), Some(true));
+        assert_eq!(in_range("0.3.0", "^0.2"), Some(false));
+        assert_eq!(in_range("0.0.3", "^0.0.3"), Some(true));
+        assert_eq!(in_range("0.0.4", "^0.0.3"), Some(false));
+        assert_eq!(in_range("0.0.9", "^0.0"), Some(true));
+        assert_eq!(in_range("0.1.0", "^0.0"), Some(false));
+        assert_eq!(in_range("0.9.0", "^0"), Some(true));
+        assert_eq!(in_range("1.0.0", "^0"), Some(false));
+    }
+
+    #[test]
+    fn test_in_range_tilde() {
+        let in_range = |v, r| Version::parse(v).unwrap().in_range(r);
+
+        assert_eq!(in_range("1.70.3", "~1.70.3"), Some(true));
+        assert_eq!(in_range("1.70.9", "~1.70.3"), Some(true));
+        assert_eq!(in_range("1.70.2", "~1.70.3"), Some(false));
+        assert_eq!(in_range("1.71.0", "~1.70.3"), Some(false));
+        assert_eq!(in_range("1.70.0", "~1.70"), Some(true));
+        assert_eq!(in_range("1.71.0", "~1.70"), Some(false));
+        assert_eq!(in_range("1.99.0", "~1"), Some(true));
+        assert_eq!(in_range("2.0.0", "~1"), Some(false));
+        assert_eq!(in_range("0.2.9", "~0.2"), Some(true));
+        assert_eq!(in_range("0.3.0", "~0.2"), Some(false));
+    }
+
+    #[test]
+    fn test_in_range_mixed() {
+        let in_range = |v, r| Version::parse(v).unwrap().in_range(r);
+
+        assert_eq!(in_range("1.72.0", ">=1.70, <2"), Some(true));
+        assert_eq!(in_range("1.72.0", "^1.56, ~1.72"), Some(true));
+        assert_eq!(in_range("1.73.0", "^1.56, ~1.72"), Some(false));
+        assert_eq!(in_range("1.72.0", "^1.56, <1.72"), Some(false));
+        assert_eq!(in_range("1.72.0", "^ 1.56, ~ 1.72"), Some(true));
+    }
+
+    #[test]
+    fn test_same_release() {
+        let version = |s| Version::parse(s).unwrap();
+        let releases = ["1.70.0-dev", "1.70.0-nightly", "1.70.0-beta", "1.70.0-beta.3", "1.70"];
+        for a in &releases {
+            for b in &releases {
+                assert!(version(a).same_release(&version(b)));
+                assert_eq!(version(a) == version(b), a == b);
+            }
+        }
+
+        assert!(!version("1.70.0").same_release(&version("1.70.1")));
+        assert!(!version("1.70.0-nightly").same_release(&version("1.71.0-nightly")));
+        assert!(!version("1.70.0").same_release(&version("2.70.0")));
+    }
+
+    #[test]
+    fn test_tuple_eq() {
+        let version = |s| Version::parse(s).unwrap();
+        assert_eq!(version("1.70.0"), (1, 70, 0));
+        assert_eq!(version("1.70"), (1, 70, 0));
+        assert_eq!(version("1.70.0-stable"), (1, 70, 0));
+        assert!(version("1.70.1") != (1, 70, 0));
+        assert!(version("1.70.0") != (1, 70, 65536));
+        assert!(version("1.70.0-dev") != (1, 70, 0));
+        assert!(version("1.70.0-nightly") != (1, 70, 0));
+        assert!(version("1.70.0-beta.2") != (1, 70, 0));
+        assert_eq!(version("1.70.0-beta.2").stripped(), (1, 70, 0));
+    }
+
+    #[test]
+    fn test_check_msrv() {
+        let check = |version, msrv| {
+            Version::parse(version).unwrap().check_msrv(Version::parse(msrv).unwrap())
+        };
+
+        assert_eq!(check("1.70.0", "1.70.0"), Ok(()));
+        assert_eq!(check("1.70.0-nightly", "1.70.0"), Ok(()));
+        assert_eq!(check("1.71.0", "1.70.0-beta"), Ok(()));
+        assert_eq!(check("1.65.0", "1.70.0"),
+            Err("requires rustc >= 1.70.0 but found 1.65.0".to_string()));
+        assert_eq!(check("1.69.2-beta.3", "1.70.0"),
+            Err("requires rustc >= 1.70.0 but found 1.69.2-beta.3".to_string()));
+
+        assert_eq!(Version::compatible_with_msrv("1.0.0"), Ok(()));
+        assert_eq!(Version::compatible_with_msrv("one.two"),
+            Err("invalid minimum rustc version `one.two`".to_string()));
+        assert!(Version::compatible_with_msrv("65535.0.0").is_err());
+    }
+
+    #[test]
+    fn test_next() {
+        let next_minor = |s| Version::parse(s).unwrap().next_minor().to_mmp();
+        let next_major = |s| Version::parse(s).unwrap().next_major().to_mmp();
+
+        assert_eq!(next_minor("1.50.3"), (1, 51, 0));
+        assert_eq!(next_minor("1.50.0-beta"), (1, 51, 0));
+        assert_eq!(next_minor("0.0.0"), (0, 1, 0));
+        assert_eq!(next_minor("1.65535.2"), (2, 0, 0));
+        assert_eq!(next_major("1.50.3"), (2, 0, 0));
+        assert_eq!(next_major("1.50.3-nightly"), (2, 0, 0));
+        assert_eq!(next_major("65535.1.1"), (65535, 0, 0));
+
+        let version = Version::parse("1.50.0-nightly").unwrap();
+        assert_eq!(version.next_minor(), Version::from_mmp(1, 51, 0));
+        assert_eq!(version.next_major(), Version::from_mmp(2, 0, 0));
+    }
+
     macro_rules! reflexive_display {
         ($s:expr) => (
             assert_eq!(Version::parse($s).unwrap().to_string(), $s);
@@ -312,5 +1859,27 @@ mod tests {
         reflexive_display!("1.44.0");
         reflexive_display!("2.44.0");
         reflexive_display!("23459.28923.3483");
+        reflexive_display!("1.52.0-nightly");
+        reflexive_display!("1.52.0-beta");
+        reflexive_display!("1.52.0-dev");
+        reflexive_display!("1.70.0-beta.3");
+        reflexive_display!("1.70.0-beta.12");
+
+        assert_eq!(Version::parse("1.52-nightly").unwrap().to_string(), "1.52.0-nightly");
+        assert_eq!(Version::parse("1.52.0-stable").unwrap().to_string(), "1.52.0");
+        for s in &["1.52.0", "1.52.0-nightly", "1.52.0-beta", "1.52.0-dev", "1.70.0-beta.3"] {
+            let version = Version::parse(s).unwrap();
+            assert_eq!(Version::parse(&version.to_string()), Some(version));
+        }
+    }
+
+    #[test]
+    fn debug() {
+        assert_eq!(format!("{:?}", Version::parse("1.52.3-nightly").unwrap()),
+            "Version { major: 1, minor: 52, patch: 3, pre: Some(\"nightly\") }");
+        assert_eq!(format!("{:?}", Version::parse("1.70.0-beta.3").unwrap()),
+            "Version { major: 1, minor: 70, patch: 0, pre: Some(\"beta.3\") }");
+        assert_eq!(format!("{:?}", Version::parse("1.52.3").unwrap()),
+            "Version { major: 1, minor: 52, patch: 3, pre: None }");
     }
 }